@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use neo_rs::prelude::{Http, Transaction};
+
+fuzz_target!(|data: &[u8]| {
+	let _ = Transaction::<Http>::from_bytes(data);
+});
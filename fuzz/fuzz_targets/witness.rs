@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use neo_rs::prelude::{Decoder, NeoSerializable, Witness};
+
+fuzz_target!(|data: &[u8]| {
+	let _ = Witness::decode(&mut Decoder::new(data));
+});
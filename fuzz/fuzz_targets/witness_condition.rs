@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use neo_rs::prelude::{Decoder, NeoSerializable, WitnessCondition};
+
+fuzz_target!(|data: &[u8]| {
+	let _ = WitnessCondition::decode(&mut Decoder::new(data));
+});
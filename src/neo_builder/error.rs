@@ -38,6 +38,8 @@ pub enum BuilderError {
 	IllegalState(String),
 	#[error("Illegal argument: {0}")]
 	IllegalArgument(String),
+	#[error("External signer error: {0}")]
+	ExternalSignerError(String),
 	#[error("Invalid public key: {0}")]
 	CodecError(#[from] CodecError),
 	#[error("Crypto error: {0}")]
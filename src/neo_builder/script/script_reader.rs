@@ -9,38 +9,40 @@ impl ScriptReader {
 	pub fn get_interop_service_code(_hash: String) -> Option<InteropService> {
 		InteropService::from_hash(_hash)
 	}
+	/// Disassembles `script`, one instruction per line. A truncated last instruction is left out.
 	pub fn convert_to_op_code_string(script: &Bytes) -> String {
 		let mut reader = Decoder::new(script);
 		let mut result = String::new();
-		while reader.pointer().clone() < script.len() {
-			if let Ok(op_code) = OpCode::try_from(reader.read_u8()) {
-				result.push_str(&format!("{:?}", op_code).to_uppercase());
-				if let Some(size) = op_code.operand_size() {
-					if size.size().clone() > 0 {
-						result.push_str(&format!(
-							" {}",
-							reader.read_bytes(size.size().clone() as usize).unwrap().to_hex()
-						));
-					} else if size.prefix_size().clone() > 0 {
-						let prefix_size = Self::get_prefix_size(&mut reader, size).unwrap();
-						result.push_str(&format!(
-							" {} {}",
-							prefix_size,
-							reader.read_bytes(prefix_size).unwrap().to_hex()
-						));
+		while let Ok(byte) = reader.read_u8() {
+			let Ok(op_code) = OpCode::try_from(byte) else { continue };
+			let mut line = format!("{:?}", op_code).to_uppercase();
+			if let Some(size) = op_code.operand_size() {
+				if size.size().clone() > 0 {
+					match reader.read_bytes(size.size().clone() as usize) {
+						Ok(operand) => line.push_str(&format!(" {}", operand.to_hex())),
+						Err(_) => break,
+					}
+				} else if size.prefix_size().clone() > 0 {
+					let operand = Self::get_prefix_size(&mut reader, size)
+						.and_then(|prefix_size| Ok((prefix_size, reader.read_bytes(prefix_size)?)));
+					match operand {
+						Ok((prefix_size, operand)) =>
+							line.push_str(&format!(" {} {}", prefix_size, operand.to_hex())),
+						Err(_) => break,
 					}
 				}
-				result.push('\n');
 			}
+			result.push_str(&line);
+			result.push('\n');
 		}
 		result
 	}
 
 	fn get_prefix_size(reader: &mut Decoder, size: OperandSize) -> Result<usize, BuilderError> {
 		match size.prefix_size() {
-			1 => Ok(reader.read_u8() as usize),
-			2 => Ok(reader.read_i16() as usize),
-			4 => Ok(reader.read_i32() as usize),
+			1 => Ok(reader.read_u8()? as usize),
+			2 => Ok(reader.read_u16()? as usize),
+			4 => Ok(reader.read_u32()? as usize),
 			_ => Err(BuilderError::UnsupportedOperation(
 				"Only operand prefix sizes 1, 2, and 4 are supported".to_string(),
 			)),
@@ -66,4 +68,13 @@ mod tests {
 
 		assert_eq!(op_code_string.as_str(), expected_op_code_string);
 	}
+
+	#[test]
+	fn test_convert_truncated_script() {
+		let script = "0c0548656c6c6f0c05576f72".from_hex().unwrap();
+		assert_eq!(ScriptReader::convert_to_op_code_string(&script), "PUSHDATA1 5 48656c6c6f\n");
+
+		let script = "0d1000".from_hex().unwrap();
+		assert_eq!(ScriptReader::convert_to_op_code_string(&script), "");
+	}
 }
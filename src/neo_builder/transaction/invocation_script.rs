@@ -45,7 +45,7 @@ use neo::prelude::{
 // 		key_pair: &KeyPair,
 // 	) -> Result<Self, BuilderError> {
 // 		let message_hash = message.hash256();
-// 		let signature = key_pair.private_key.sign_tx(&message_hash)?;
+// 		let signature = key_pair.expose_secret().sign_tx(&message_hash)?;
 // 		let mut builder = ScriptBuilder::new();
 // 		// Convert signature to bytes
 // 		let signature_bytes = signature.to_bytes();
@@ -127,7 +127,7 @@ impl InvocationScript {
 		message: Vec<u8>,
 		key_pair: &KeyPair,
 	) -> Result<Self, BuilderError> {
-		let signature = key_pair.private_key.sign_tx(&message)?;
+		let signature = key_pair.expose_secret().sign_tx(&message)?;
 		Ok(Self::from_signature(signature))
 	}
 
@@ -159,9 +159,10 @@ impl InvocationScript {
 	pub fn get_signatures(&self) -> Vec<Secp256r1Signature> {
 		let mut reader = Decoder::new(&self.script);
 		let mut sigs = Vec::new();
-		while reader.available() > 0 && reader.read_u8() == OpCode::PushData1 as u8 {
-			reader.read_u8(); // ignore opcode size
-			if let Ok(signature) = Secp256r1Signature::from_bytes(&reader.read_bytes(64).unwrap()) {
+		while reader.read_u8() == Ok(OpCode::PushData1 as u8) {
+			let _ = reader.read_u8(); // ignore opcode size
+			let Ok(bytes) = reader.read_bytes(64) else { break };
+			if let Ok(signature) = Secp256r1Signature::from_bytes(&bytes) {
 				sigs.push(signature);
 			}
 		}
@@ -203,7 +204,7 @@ mod tests {
 		let key_pair = KeyPair::new_random();
 		let script =
 			InvocationScript::from_message_and_key_pair(message.clone(), &key_pair).unwrap();
-		let expected_signature = key_pair.private_key().sign_tx(&message).unwrap();
+		let expected_signature = key_pair.expose_secret().sign_tx(&message).unwrap();
 		let expected = format!(
 			"{}40{}",
 			OpCode::PushData1.to_string(),
@@ -234,7 +235,7 @@ mod tests {
 	fn test_deserialize_signature_invocation_script() {
 		let message = vec![0u8; 10];
 		let key_pair = KeyPair::new_random();
-		let signature = key_pair.private_key().sign_tx(&message).unwrap();
+		let signature = key_pair.expose_secret().sign_tx(&message).unwrap();
 		let script =
 			format!("{}40{}", OpCode::PushData1.to_string(), signature.to_bytes().to_hex());
 		let deserialized =
@@ -253,7 +254,7 @@ mod tests {
 	fn test_get_signatures() {
 		let message = vec![0u8; 10];
 		let key_pair = KeyPair::new_random();
-		let signature = key_pair.private_key.sign_tx(&message).unwrap();
+		let signature = key_pair.expose_secret().sign_tx(&message).unwrap();
 		let inv = InvocationScript::from_signatures(&vec![
 			signature.clone(),
 			signature.clone(),
@@ -1,12 +1,16 @@
 pub use call_flags::*;
 pub use contract_parameters_context::*;
 pub use invocation_script::*;
+pub use notary_request::*;
 pub use oracle_response_code::*;
+pub use oracle_response_transaction_builder::*;
 pub use signers::*;
 pub use transaction::*;
 pub use transaction_attribute::*;
 pub use transaction_builder::*;
 pub use transaction_error::*;
+pub use transaction_inspector::*;
+pub use transaction_preflight::*;
 pub use transaction_send_token::*;
 pub use verification_script::*;
 pub use witness::*;
@@ -16,12 +20,16 @@ pub use witness_scope::*;
 mod call_flags;
 mod contract_parameters_context;
 mod invocation_script;
+mod notary_request;
 mod oracle_response_code;
+mod oracle_response_transaction_builder;
 mod signers;
 mod transaction;
 mod transaction_attribute;
 mod transaction_builder;
 mod transaction_error;
+mod transaction_inspector;
+mod transaction_preflight;
 mod transaction_send_token;
 mod verification_script;
 mod witness;
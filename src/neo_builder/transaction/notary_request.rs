@@ -0,0 +1,357 @@
+use std::str::FromStr;
+
+use getset::Getters;
+use once_cell::sync::Lazy;
+use primitive_types::{H160, H256};
+
+use neo::prelude::*;
+
+pub static NOTARY_CONTRACT_HASH: Lazy<ScriptHash> =
+	Lazy::new(|| ScriptHash::from_str("c1e14f19c3e60d0b9244d06dd7ba9b113135ec3b").unwrap());
+
+/// A request to the P2P notary service, as accepted by the `submitnotaryrequest` RPC method.
+///
+/// It bundles the partially signed main transaction with a fully signed fallback transaction
+/// that the notary nodes send instead if the main transaction does not collect all of its
+/// signatures in time. The request itself is signed by the sender of the fallback transaction.
+#[derive(Debug, Clone, Getters)]
+pub struct P2PNotaryRequest<P: JsonRpcClient + 'static> {
+	#[getset(get = "pub")]
+	main_transaction: Transaction<P>,
+	#[getset(get = "pub")]
+	fallback_transaction: Transaction<P>,
+	#[getset(get = "pub")]
+	witness: Witness,
+}
+
+impl<P: JsonRpcClient + 'static> P2PNotaryRequest<P> {
+	pub fn new(
+		main_transaction: Transaction<P>,
+		fallback_transaction: Transaction<P>,
+		witness: Witness,
+	) -> Self {
+		Self { main_transaction, fallback_transaction, witness }
+	}
+
+	/// Returns the request hash, in the big-endian form used for display and RPC calls.
+	pub fn hash(&self) -> H256 {
+		let mut hash = self.hash_bytes();
+		hash.reverse();
+		H256::from_slice(&hash)
+	}
+
+	/// Returns the data signed by the request witness, i.e. the network magic followed by the
+	/// request hash.
+	pub fn get_hash_data_for_network(&self, network: u32) -> Bytes {
+		let mut data = network.to_le_bytes().to_vec();
+		data.extend(self.hash_bytes());
+		data
+	}
+
+	/// The base64 encoded payload expected by `submitnotaryrequest`.
+	pub fn to_base64(&self) -> String {
+		self.to_array().to_base64()
+	}
+
+	fn hash_bytes(&self) -> Bytes {
+		let mut encoder = Encoder::new();
+		self.main_transaction.encode(&mut encoder);
+		self.fallback_transaction.encode(&mut encoder);
+		encoder.to_bytes().hash256().to_vec()
+	}
+}
+
+impl<P: JsonRpcClient + 'static> NeoSerializable for P2PNotaryRequest<P> {
+	type Error = BuilderError;
+
+	fn size(&self) -> usize {
+		self.main_transaction.size() + self.fallback_transaction.size() + self.witness.size()
+	}
+
+	fn encode(&self, writer: &mut Encoder) {
+		self.main_transaction.encode(writer);
+		self.fallback_transaction.encode(writer);
+		self.witness.encode(writer);
+	}
+
+	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
+		let main_transaction = Transaction::decode(reader)?;
+		let fallback_transaction = Transaction::decode(reader)?;
+		let witness = Witness::decode(reader)?;
+		Ok(Self { main_transaction, fallback_transaction, witness })
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+/// Builds a `P2PNotaryRequest` for a main transaction that is signed by several parties through
+/// the notary service.
+///
+/// The main transaction must carry a `NotaryAssisted` attribute and have the notary contract
+/// among its signers, see `TransactionBuilder::notary_assisted`. Its witnesses hold the
+/// signatures of the sender and placeholders (see `notary_witness`) for the missing ones.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let mut builder = NotaryRequestBuilder::new(main_tx);
+/// builder.not_valid_before(current_height + 20).fee_per_key(fee_per_key);
+/// let request = builder.build(&sender_key_pair, network_magic)?;
+/// provider.submit_notary_request(request.to_base64()).await?;
+/// ```
+#[derive(Debug, Clone, Getters)]
+pub struct NotaryRequestBuilder<P: JsonRpcClient + 'static> {
+	#[getset(get = "pub")]
+	main_transaction: Transaction<P>,
+	not_valid_before: Option<u32>,
+	fee_per_key: i64,
+	fee_per_byte: i64,
+	exec_fee_factor: i64,
+}
+
+impl<P: JsonRpcClient + 'static> NotaryRequestBuilder<P> {
+	pub const DEFAULT_FEE_PER_KEY: i64 = 10_000_000;
+	pub const DEFAULT_FEE_PER_BYTE: i64 = 1000;
+	pub const DEFAULT_EXEC_FEE_FACTOR: i64 = 30;
+
+	// The GAS charged for the `verify` method of the notary contract.
+	const NOTARY_VERIFICATION_PRICE: i64 = 1 << 15;
+
+	pub fn new(main_transaction: Transaction<P>) -> Self {
+		Self {
+			main_transaction,
+			not_valid_before: None,
+			fee_per_key: Self::DEFAULT_FEE_PER_KEY,
+			fee_per_byte: Self::DEFAULT_FEE_PER_BYTE,
+			exec_fee_factor: Self::DEFAULT_EXEC_FEE_FACTOR,
+		}
+	}
+
+	/// Sets the height from which the fallback transaction may be sent, usually the current
+	/// height plus at most `NotaryContract::get_max_not_valid_before_delta`.
+	pub fn not_valid_before(&mut self, height: u32) -> &mut Self {
+		self.not_valid_before = Some(height);
+		self
+	}
+
+	/// Sets the notary service fee per key as returned by
+	/// `NotaryContract::get_notary_service_fee_per_key`.
+	pub fn fee_per_key(&mut self, fee_per_key: i64) -> &mut Self {
+		self.fee_per_key = fee_per_key;
+		self
+	}
+
+	/// Sets the fee per byte as returned by `PolicyContract::get_fee_per_byte`.
+	pub fn fee_per_byte(&mut self, fee_per_byte: i64) -> &mut Self {
+		self.fee_per_byte = fee_per_byte;
+		self
+	}
+
+	/// Sets the execution fee factor as returned by `PolicyContract::get_exec_fee_factor`.
+	pub fn exec_fee_factor(&mut self, exec_fee_factor: i64) -> &mut Self {
+		self.exec_fee_factor = exec_fee_factor;
+		self
+	}
+
+	/// The placeholder witness of the notary contract, replaced by the notary nodes with their
+	/// own signature once the request is complete.
+	pub fn notary_witness() -> Witness {
+		let mut invocation = vec![OpCode::PushData1 as u8, NeoConstants::SIGNATURE_SIZE as u8];
+		invocation.extend(vec![0; NeoConstants::SIGNATURE_SIZE as usize]);
+		Witness::from_scripts(invocation, Bytes::new())
+	}
+
+	/// Assembles the unsigned fallback transaction of `sender`.
+	///
+	/// The fallback is paid by the notary contract from the deposit of `sender`, conflicts with
+	/// the main transaction and does nothing but return.
+	pub fn build_fallback(&self, sender: &H160) -> Result<Transaction<P>, TransactionError> {
+		let main_tx = &self.main_transaction;
+		if !main_tx
+			.attributes
+			.iter()
+			.any(|a| matches!(a, TransactionAttribute::NotaryAssisted { .. }))
+		{
+			return Err(TransactionError::TransactionConfiguration(
+				"The main transaction must have a NotaryAssisted attribute".to_string(),
+			))
+		}
+		if !main_tx.signers.iter().any(|s| s.get_signer_hash() == &*NOTARY_CONTRACT_HASH) {
+			return Err(TransactionError::SignerConfiguration(
+				"The notary contract must be a signer of the main transaction".to_string(),
+			))
+		}
+		let not_valid_before = self.not_valid_before.ok_or(TransactionError::InvalidBlock)?;
+		if not_valid_before >= main_tx.valid_until_block as u32 {
+			return Err(TransactionError::InvalidBlock)
+		}
+
+		let mut tx = Transaction::<P>::new();
+		tx.version = NeoConstants::CURRENT_TX_VERSION;
+		tx.nonce = rand::random::<i32>();
+		tx.valid_until_block = main_tx.valid_until_block;
+		tx.signers = vec![
+			TransactionSigner::new(*NOTARY_CONTRACT_HASH, vec![WitnessScope::None]).into(),
+			TransactionSigner::new(*sender, vec![WitnessScope::None]).into(),
+		];
+		tx.attributes = vec![
+			TransactionAttribute::NotValidBefore { height: not_valid_before },
+			TransactionAttribute::Conflicts { hash: main_tx.get_tx_id() },
+			TransactionAttribute::NotaryAssisted { n_keys: 0 },
+		];
+		tx.script = vec![OpCode::Ret as u8];
+
+		// Size the transaction with a single-sig witness for the sender.
+		tx.witnesses = vec![
+			Self::notary_witness(),
+			Witness::from_scripts(
+				vec![0; 2 + NeoConstants::SIGNATURE_SIZE as usize],
+				vec![0; NeoConstants::VERIFICATION_SCRIPT_SIZE as usize],
+			),
+		];
+		let verification_cost = Self::NOTARY_VERIFICATION_PRICE
+			+ OpCode::PushData1.price() as i64 * 2
+			+ InteropService::SystemCryptoCheckSig.price() as i64;
+		tx.net_fee = tx.size() as i64 * self.fee_per_byte
+			+ self.exec_fee_factor * verification_cost
+			+ self.fee_per_key;
+		tx.witnesses = vec![];
+
+		Ok(tx)
+	}
+
+	/// Builds, signs and bundles the fallback transaction of the key pair's account into a
+	/// notary request.
+	pub fn build(
+		&self,
+		sender: &KeyPair,
+		network: u32,
+	) -> Result<P2PNotaryRequest<P>, BuilderError> {
+		let sender_hash = public_key_to_script_hash(&sender.public_key());
+		let mut fallback = self.build_fallback(&sender_hash)?;
+		let fallback_witness =
+			Witness::create(fallback.get_hash_data_for_network(network), sender)?;
+		fallback.witnesses = vec![Self::notary_witness(), fallback_witness];
+		fallback.size = fallback.size() as i32;
+
+		let mut request =
+			P2PNotaryRequest::new(self.main_transaction.clone(), fallback, Witness::new());
+		request.witness = Witness::create(request.get_hash_data_for_network(network), sender)?;
+		Ok(request)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H160;
+
+	use neo::prelude::{
+		public_key_to_script_hash, Decoder, Http, KeyPair, NeoSerializable, NotaryRequestBuilder,
+		P2PNotaryRequest, Secp256r1PrivateKey, Transaction, TransactionAttribute, TransactionError,
+		TransactionSigner, WitnessScope, NOTARY_CONTRACT_HASH,
+	};
+
+	fn sender() -> KeyPair {
+		KeyPair::from_secret_key(
+			&Secp256r1PrivateKey::from_bytes(
+				&hex::decode("e6e919577dd7b8e97805151c05ae07ff4f752654d6d8797597aca989c02c4cb3")
+					.unwrap(),
+			)
+			.unwrap(),
+		)
+	}
+
+	fn sender_hash() -> H160 {
+		public_key_to_script_hash(&sender().public_key())
+	}
+
+	fn main_transaction() -> Transaction<Http> {
+		let mut tx = Transaction::new();
+		tx.nonce = 1;
+		tx.valid_until_block = 1000;
+		tx.signers = vec![
+			TransactionSigner::new(sender_hash(), vec![WitnessScope::CalledByEntry]).into(),
+			TransactionSigner::new(*NOTARY_CONTRACT_HASH, vec![WitnessScope::None]).into(),
+		];
+		tx.attributes = vec![TransactionAttribute::NotaryAssisted { n_keys: 2 }];
+		tx.script = vec![0x11, 0x40];
+		tx
+	}
+
+	#[test]
+	fn test_notary_witness() {
+		let witness = NotaryRequestBuilder::<Http>::notary_witness();
+		assert_eq!(witness.to_array(), [vec![66, 0x0c, 0x40], vec![0; 64], vec![0]].concat());
+	}
+
+	#[test]
+	fn test_build_fallback() {
+		let main_tx = main_transaction();
+		let mut builder = NotaryRequestBuilder::new(main_tx.clone());
+		builder.not_valid_before(900).fee_per_key(1000);
+
+		let fallback = builder.build_fallback(&sender_hash()).unwrap();
+
+		assert_eq!(fallback.valid_until_block, 1000);
+		assert_eq!(fallback.script, vec![0x40]);
+		assert_eq!(fallback.signers[0].get_signer_hash(), &*NOTARY_CONTRACT_HASH);
+		assert_eq!(fallback.signers[1].get_signer_hash(), &sender_hash());
+		assert_eq!(
+			fallback.attributes,
+			vec![
+				TransactionAttribute::NotValidBefore { height: 900 },
+				TransactionAttribute::Conflicts { hash: main_tx.get_tx_id() },
+				TransactionAttribute::NotaryAssisted { n_keys: 0 },
+			]
+		);
+		assert!(fallback.net_fee > 1000);
+		assert_eq!(fallback.sys_fee, 0);
+	}
+
+	#[test]
+	fn test_build_fallback_requires_notary_assisted_main_transaction() {
+		let mut main_tx = main_transaction();
+		main_tx.attributes.clear();
+		let mut builder = NotaryRequestBuilder::new(main_tx);
+		builder.not_valid_before(900);
+
+		assert!(matches!(
+			builder.build_fallback(&sender_hash()),
+			Err(TransactionError::TransactionConfiguration(_))
+		));
+	}
+
+	#[test]
+	fn test_build_fallback_with_invalid_not_valid_before() {
+		let mut builder = NotaryRequestBuilder::new(main_transaction());
+		builder.not_valid_before(1000);
+
+		assert_eq!(
+			builder.build_fallback(&sender_hash()).err(),
+			Some(TransactionError::InvalidBlock)
+		);
+	}
+
+	#[test]
+	fn test_build_and_serialize_request() {
+		let mut builder = NotaryRequestBuilder::new(main_transaction());
+		builder.not_valid_before(900);
+
+		let request = builder.build(&sender(), 860833102).unwrap();
+		assert_eq!(request.fallback_transaction().witnesses.len(), 2);
+
+		let bytes = request.to_array();
+		assert_eq!(bytes.len(), request.size());
+
+		let mut reader = Decoder::new(&bytes);
+		let decoded = P2PNotaryRequest::<Http>::decode(&mut reader).unwrap();
+		assert_eq!(decoded.hash(), request.hash());
+		assert_eq!(decoded.witness(), request.witness());
+	}
+}
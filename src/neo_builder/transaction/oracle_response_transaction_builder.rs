@@ -0,0 +1,374 @@
+use std::str::FromStr;
+
+use getset::Getters;
+use num_bigint::BigInt;
+use once_cell::sync::Lazy;
+
+use neo::prelude::*;
+
+/// Builds the transaction an oracle node submits in response to an `OracleRequest`.
+///
+/// The response transaction always has the same shape: it calls `finish` on the native
+/// `OracleContract`, carries an `OracleResponse` attribute and is signed by the oracle contract
+/// itself and by the BFT multi-sig account of the designated oracle nodes.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let mut builder = OracleResponseTransactionBuilder::new(request, oracle_nodes);
+/// builder.success(b"42".to_vec()).valid_until_block(request_height + 5760);
+/// let mut tx: Transaction<Http> = builder.build()?;
+/// let signature = builder.sign(&tx, &node_key_pair, network_magic)?;
+/// builder.add_witnesses(&mut tx, vec![signature, ...])?;
+/// ```
+#[derive(Debug, Clone, Getters)]
+pub struct OracleResponseTransactionBuilder {
+	#[getset(get = "pub")]
+	request: OracleRequest,
+	#[getset(get = "pub")]
+	oracle_nodes: Vec<Secp256r1PublicKey>,
+	response_code: OracleResponseCode,
+	result: Bytes,
+	valid_until_block: Option<u32>,
+	fee_per_byte: i64,
+	exec_fee_factor: i64,
+	oracle_verification_fee: i64,
+}
+
+pub static ORACLE_CONTRACT_HASH: Lazy<ScriptHash> =
+	Lazy::new(|| ScriptHash::from_str("fe924b7cfe89ddd271abaf7210a80a7e11178758").unwrap());
+
+impl OracleResponseTransactionBuilder {
+	pub const FINISH_METHOD: &'static str = "finish";
+	pub const DEFAULT_FEE_PER_BYTE: i64 = 1000;
+	pub const DEFAULT_EXEC_FEE_FACTOR: i64 = 30;
+
+	// Opcode prices used to compute the verification cost of the multi-sig witness.
+	const PUSHDATA1_PRICE: i64 = 1 << 3;
+	const PUSHINT_PRICE: i64 = 1 << 0;
+	const CHECK_SIG_PRICE: i64 = 1 << 15;
+
+	pub fn new(request: OracleRequest, oracle_nodes: Vec<Secp256r1PublicKey>) -> Self {
+		Self {
+			request,
+			oracle_nodes,
+			response_code: OracleResponseCode::Success,
+			result: Bytes::new(),
+			valid_until_block: None,
+			fee_per_byte: Self::DEFAULT_FEE_PER_BYTE,
+			exec_fee_factor: Self::DEFAULT_EXEC_FEE_FACTOR,
+			oracle_verification_fee: 0,
+		}
+	}
+
+	/// Sets a successful response carrying the fetched (and filtered) data.
+	pub fn success(&mut self, result: Bytes) -> &mut Self {
+		self.response(OracleResponseCode::Success, result)
+	}
+
+	/// Sets the response code and result. Failed responses never carry a result.
+	pub fn response(&mut self, code: OracleResponseCode, result: Bytes) -> &mut Self {
+		self.response_code = code;
+		self.result = if code == OracleResponseCode::Success { result } else { Bytes::new() };
+		self
+	}
+
+	/// Sets the block until which the response is valid, usually the height of the request
+	/// transaction plus `max_valid_until_block_increment`.
+	pub fn valid_until_block(&mut self, block: u32) -> &mut Self {
+		self.valid_until_block = Some(block);
+		self
+	}
+
+	/// Sets the fee per byte as returned by `PolicyContract::get_fee_per_byte`.
+	pub fn fee_per_byte(&mut self, fee_per_byte: i64) -> &mut Self {
+		self.fee_per_byte = fee_per_byte;
+		self
+	}
+
+	/// Sets the execution fee factor as returned by `PolicyContract::get_exec_fee_factor`.
+	pub fn exec_fee_factor(&mut self, exec_fee_factor: i64) -> &mut Self {
+		self.exec_fee_factor = exec_fee_factor;
+		self
+	}
+
+	/// Sets the GAS consumed by the `verify` method of the oracle contract, e.g. taken from
+	/// `Middleware::invoke_contract_verify`.
+	pub fn oracle_verification_fee(&mut self, fee: i64) -> &mut Self {
+		self.oracle_verification_fee = fee;
+		self
+	}
+
+	/// The script every oracle response transaction must have.
+	pub fn fixed_script() -> Bytes {
+		let mut builder = ScriptBuilder::new();
+		builder
+			.op_code(&[OpCode::NewArray0])
+			.push_integer(BigInt::from(CallFlags::All.value()))
+			.push_data(Self::FINISH_METHOD.as_bytes().to_vec())
+			.push_data(ORACLE_CONTRACT_HASH.to_le_vec())
+			.sys_call(InteropService::SystemContractCall);
+		builder.to_bytes()
+	}
+
+	/// The number of oracle node signatures required, following the BFT rule `n - (n - 1) / 3`.
+	pub fn signing_threshold(&self) -> usize {
+		let n = self.oracle_nodes.len();
+		n - (n - 1) / 3
+	}
+
+	/// The verification script of the oracle nodes' multi-sig account.
+	pub fn verification_script(&self) -> VerificationScript {
+		let mut nodes = self.oracle_nodes.clone();
+		VerificationScript::from_multi_sig(nodes.as_mut_slice(), self.signing_threshold() as u8)
+	}
+
+	/// Assembles the unsigned response transaction.
+	///
+	/// Like the reference oracle service, the response code is downgraded to
+	/// `ResponseTooLarge` or `InsufficientFunds` when the result cannot be delivered.
+	pub fn build<P: JsonRpcClient + 'static>(&self) -> Result<Transaction<P>, TransactionError> {
+		if self.oracle_nodes.is_empty() {
+			return Err(TransactionError::SignerConfiguration(
+				"At least one oracle node is required".to_string(),
+			))
+		}
+		if self.oracle_nodes.len() > NeoConstants::MAX_PUBLIC_KEYS_PER_MULTI_SIG as usize {
+			return Err(TransactionError::SignerConfiguration(
+				"Too many oracle nodes for a multi-sig account".to_string(),
+			))
+		}
+		let valid_until_block = self.valid_until_block.ok_or(TransactionError::InvalidBlock)?;
+
+		let verification_script = self.verification_script();
+		let multi_sig_hash = ScriptHash::from_script(verification_script.script());
+
+		let mut tx = Transaction::<P>::new();
+		tx.version = NeoConstants::CURRENT_TX_VERSION;
+		tx.nonce = self.request.request_id;
+		tx.valid_until_block = valid_until_block as i32;
+		tx.signers = vec![
+			TransactionSigner::new(*ORACLE_CONTRACT_HASH, vec![WitnessScope::None]).into(),
+			TransactionSigner::new(multi_sig_hash, vec![WitnessScope::None]).into(),
+		];
+		tx.script = Self::fixed_script();
+
+		let m = self.signing_threshold() as i64;
+		let n = self.oracle_nodes.len() as i64;
+		let mut network_fee =
+			self.oracle_verification_fee + self.exec_fee_factor * Self::multi_sig_cost(m, n);
+
+		// Everything but the attribute, with the invocation script sized for `m` signatures.
+		let invocation_size = 66 * m as usize;
+		tx.witnesses = vec![
+			Witness::new(),
+			Witness::from_scripts(vec![0; invocation_size], verification_script.script().clone()),
+		];
+		let size_without_attributes = tx.size() - tx.attributes.var_size();
+
+		let mut response_code = self.response_code;
+		let mut result = self.result.clone();
+		if result.len() > TransactionAttribute::MAX_RESULT_SIZE {
+			response_code = OracleResponseCode::ResponseTooLarge;
+			result = Bytes::new();
+		} else if network_fee
+			+ (size_without_attributes + self.attributes(response_code, &result).var_size()) as i64
+				* self.fee_per_byte
+			> self.request.gas_for_response as i64
+		{
+			response_code = OracleResponseCode::InsufficientFunds;
+			result = Bytes::new();
+		}
+
+		tx.attributes = self.attributes(response_code, &result);
+		network_fee +=
+			(size_without_attributes + tx.attributes.var_size()) as i64 * self.fee_per_byte;
+
+		tx.net_fee = network_fee;
+		tx.sys_fee = self.request.gas_for_response as i64 - network_fee;
+		tx.witnesses = vec![];
+		tx.size = tx.size() as i32;
+
+		Ok(tx)
+	}
+
+	/// Signs the response transaction with the key of one oracle node.
+	pub fn sign<P: JsonRpcClient + 'static>(
+		&self,
+		tx: &Transaction<P>,
+		key_pair: &KeyPair,
+		network: u32,
+	) -> Result<Secp256r1Signature, BuilderError> {
+		if !self.oracle_nodes.contains(&key_pair.public_key()) {
+			return Err(BuilderError::SignerConfiguration(
+				"The key pair does not belong to a designated oracle node".to_string(),
+			))
+		}
+		Ok(key_pair.expose_secret().sign_tx(&tx.get_hash_data_for_network(network))?)
+	}
+
+	/// Attaches the oracle contract witness and the multi-sig witness built from the collected
+	/// node signatures. Signatures must be ordered like the sorted oracle node public keys.
+	pub fn add_witnesses<P: JsonRpcClient + 'static>(
+		&self,
+		tx: &mut Transaction<P>,
+		signatures: Vec<Secp256r1Signature>,
+	) -> Result<(), BuilderError> {
+		let multi_sig_witness =
+			Witness::create_multi_sig_witness_script(signatures, self.verification_script())?;
+		tx.witnesses = vec![Witness::new(), multi_sig_witness];
+		tx.size = tx.size() as i32;
+		Ok(())
+	}
+
+	fn attributes(&self, code: OracleResponseCode, result: &[u8]) -> Vec<TransactionAttribute> {
+		vec![TransactionAttribute::OracleResponse(OracleResponse::new(
+			self.request.request_id as u64,
+			code,
+			result,
+		))]
+	}
+
+	fn multi_sig_cost(m: i64, n: i64) -> i64 {
+		Self::PUSHDATA1_PRICE * (m + n) + Self::PUSHINT_PRICE * 2 + Self::CHECK_SIG_PRICE * n
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::{H160, H256};
+
+	use neo::prelude::{
+		Http, KeyPair, NeoSerializable, OracleRequest, OracleResponseCode,
+		OracleResponseTransactionBuilder, Secp256r1PrivateKey, Transaction, TransactionAttribute,
+	};
+
+	fn request(gas_for_response: i32) -> OracleRequest {
+		OracleRequest::new(
+			3,
+			H256::zero(),
+			gas_for_response,
+			"https://example.com/data".to_string(),
+			"$.value".to_string(),
+			H160::from_str("86d58778c8d29e03182f38369f0d97782d303cc0").unwrap(),
+			"callback".to_string(),
+			"".to_string(),
+		)
+	}
+
+	fn nodes() -> Vec<KeyPair> {
+		[
+			"e6e919577dd7b8e97805151c05ae07ff4f752654d6d8797597aca989c02c4cb3",
+			"b4b2b579cac270125259f08a5f414e9235817e7637b9a66cfeb3b77d90c8e7f9",
+			"9117f4bf9be717c9a90994326897f4243503accd06712162267e77f18b49c3a3",
+			"84180ac9d6eb6fba207ea4ef9d2200102d1ebeb4b9c07e2c6a738a42742e27a5",
+		]
+		.iter()
+		.map(|key| {
+			KeyPair::from_secret_key(
+				&Secp256r1PrivateKey::from_bytes(&hex::decode(key).unwrap()).unwrap(),
+			)
+		})
+		.collect()
+	}
+
+	#[test]
+	fn test_fixed_script() {
+		assert_eq!(
+			hex::encode(OracleResponseTransactionBuilder::fixed_script()),
+			"c21f0c0666696e6973680c14588717117e0aa81072afab71d2dd89fe7c4b92fe41627d5b52"
+		);
+	}
+
+	#[test]
+	fn test_signing_threshold() {
+		let keys = nodes().iter().map(|k| k.public_key()).collect::<Vec<_>>();
+		let builder = OracleResponseTransactionBuilder::new(request(10_000_000), keys.clone());
+		assert_eq!(builder.signing_threshold(), 3);
+
+		let builder =
+			OracleResponseTransactionBuilder::new(request(10_000_000), keys[..1].to_vec());
+		assert_eq!(builder.signing_threshold(), 1);
+	}
+
+	#[test]
+	fn test_build_response_transaction() {
+		let keys = nodes().iter().map(|k| k.public_key()).collect::<Vec<_>>();
+		let mut builder = OracleResponseTransactionBuilder::new(request(10_000_000), keys);
+		builder.success(b"42".to_vec()).valid_until_block(5760);
+
+		let tx: Transaction<Http> = builder.build().unwrap();
+
+		assert_eq!(tx.nonce, 3);
+		assert_eq!(tx.valid_until_block, 5760);
+		assert_eq!(tx.signers.len(), 2);
+		assert_eq!(tx.script, OracleResponseTransactionBuilder::fixed_script());
+		assert_eq!(tx.sys_fee + tx.net_fee, 10_000_000);
+
+		let TransactionAttribute::OracleResponse(response) = &tx.attributes[0] else {
+			panic!("Expected an oracle response attribute")
+		};
+		assert_eq!(response.id, 3);
+		assert_eq!(response.response_code, OracleResponseCode::Success);
+		assert_eq!(response.result, b"42".to_vec());
+	}
+
+	#[test]
+	fn test_build_response_with_insufficient_gas() {
+		let keys = nodes().iter().map(|k| k.public_key()).collect::<Vec<_>>();
+		let mut builder = OracleResponseTransactionBuilder::new(request(1000), keys);
+		builder.success(b"42".to_vec()).valid_until_block(5760);
+
+		let tx: Transaction<Http> = builder.build().unwrap();
+
+		let TransactionAttribute::OracleResponse(response) = &tx.attributes[0] else {
+			panic!("Expected an oracle response attribute")
+		};
+		assert_eq!(response.response_code, OracleResponseCode::InsufficientFunds);
+		assert!(response.result.is_empty());
+	}
+
+	#[test]
+	fn test_build_response_too_large() {
+		let keys = nodes().iter().map(|k| k.public_key()).collect::<Vec<_>>();
+		let mut builder = OracleResponseTransactionBuilder::new(request(10_000_000), keys);
+		builder
+			.success(vec![1; TransactionAttribute::MAX_RESULT_SIZE + 1])
+			.valid_until_block(5760);
+
+		let tx: Transaction<Http> = builder.build().unwrap();
+
+		let TransactionAttribute::OracleResponse(response) = &tx.attributes[0] else {
+			panic!("Expected an oracle response attribute")
+		};
+		assert_eq!(response.response_code, OracleResponseCode::ResponseTooLarge);
+	}
+
+	#[test]
+	fn test_sign_and_add_witnesses() {
+		let key_pairs = nodes();
+		let keys = key_pairs.iter().map(|k| k.public_key()).collect::<Vec<_>>();
+		let mut builder = OracleResponseTransactionBuilder::new(request(10_000_000), keys);
+		builder.success(b"42".to_vec()).valid_until_block(5760);
+		let mut tx: Transaction<Http> = builder.build().unwrap();
+
+		let mut signers = key_pairs.clone();
+		signers.sort_by(|a, b| a.public_key().cmp(&b.public_key()));
+		let signatures = signers
+			.iter()
+			.take(builder.signing_threshold())
+			.map(|key_pair| builder.sign(&tx, key_pair, 860833102).unwrap())
+			.collect();
+		builder.add_witnesses(&mut tx, signatures).unwrap();
+
+		assert_eq!(tx.witnesses.len(), 2);
+		assert!(tx.witnesses[0].verification.script().is_empty());
+		assert_eq!(tx.witnesses[1].verification, builder.verification_script());
+		assert_eq!(tx.witnesses[1].invocation.get_signatures().len(), 3);
+		assert_eq!(tx.size as usize, tx.size());
+	}
+}
@@ -72,19 +72,24 @@ impl NeoSerializable for AccountSigner {
 	where
 		Self: Sized,
 	{
-		let signer_hash = reader.read_serializable::<H160>().unwrap();
-		let scopes = WitnessScope::split(reader.read_u8());
+		let signer_hash = reader.read_serializable::<H160>()?;
+		let scopes = WitnessScope::split(reader.read_u8()?);
 		let mut allowed_contracts = vec![];
 		let mut allowed_groups = vec![];
 		let mut rules = vec![];
 		if scopes.contains(&WitnessScope::CustomContracts) {
-			allowed_contracts = reader.read_serializable_list::<H160>().unwrap();
+			allowed_contracts = reader
+				.read_serializable_list_max::<H160>(NeoConstants::MAX_SIGNER_SUBITEMS as usize)?;
 		}
 		if scopes.contains(&WitnessScope::CustomGroups) {
-			allowed_groups = reader.read_serializable_list::<Secp256r1PublicKey>().unwrap();
+			allowed_groups = reader.read_serializable_list_max::<Secp256r1PublicKey>(
+				NeoConstants::MAX_SIGNER_SUBITEMS as usize,
+			)?;
 		}
 		if scopes.contains(&WitnessScope::WitnessRules) {
-			rules = reader.read_serializable_list::<WitnessRule>().unwrap();
+			rules = reader.read_serializable_list_max::<WitnessRule>(
+				NeoConstants::MAX_SIGNER_SUBITEMS as usize,
+			)?;
 		}
 		Ok(Self {
 			signer_hash,
@@ -341,6 +341,12 @@ impl From<ContractSigner> for Signer {
 	}
 }
 
+impl From<TransactionSigner> for Signer {
+	fn from(transaction_signer: TransactionSigner) -> Self {
+		Signer::Transaction(transaction_signer)
+	}
+}
+
 impl Into<AccountSigner> for Signer {
 	fn into(self) -> AccountSigner {
 		match self {
@@ -442,8 +448,7 @@ impl NeoSerializable for Signer {
 		match self {
 			Signer::Account(account_signer) => account_signer.size(),
 			Signer::Contract(contract_signer) => contract_signer.size(),
-			// Signer::Transaction(transaction_signer) => transaction_signer.size(),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.size(),
 		}
 	}
 
@@ -451,8 +456,7 @@ impl NeoSerializable for Signer {
 		match self {
 			Signer::Account(account_signer) => account_signer.encode(writer),
 			Signer::Contract(contract_signer) => contract_signer.encode(writer),
-			// Signer::Transaction(transaction_signer) => transaction_signer.encode(writer),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.encode(writer),
 		}
 	}
 
@@ -460,20 +464,15 @@ impl NeoSerializable for Signer {
 	where
 		Self: Sized,
 	{
-		match reader.read_u8() {
-			0 => Ok(Signer::Account(AccountSigner::decode(reader)?)),
-			1 => Ok(Signer::Contract(ContractSigner::decode(reader)?)),
-			// 2 => Ok(Signer::Transaction(TransactionSigner::decode(reader)?)),
-			_ => Err(TransactionError::InvalidTransaction),
-		}
+		// The wire format does not tell account and contract signers apart.
+		Ok(Signer::Transaction(TransactionSigner::decode(reader)?))
 	}
 
 	fn to_array(&self) -> Vec<u8> {
 		match self {
 			Signer::Account(account_signer) => account_signer.to_array(),
 			Signer::Contract(contract_signer) => contract_signer.to_array(),
-			// Signer::Transaction(transaction_signer) => transaction_signer.to_array(),
-			_ => panic!("Unsupported signer type"),
+			Signer::Transaction(transaction_signer) => transaction_signer.to_array(),
 		}
 	}
 }
@@ -91,28 +91,30 @@ impl SignerTrait for TransactionSigner {
 	}
 
 	fn get_allowed_contracts(&self) -> &Vec<H160> {
-		panic!("Not implemented")
+		static EMPTY: Vec<H160> = Vec::new();
+		self.allowed_contracts.as_ref().unwrap_or(&EMPTY)
 	}
 
 	fn get_allowed_contracts_mut(&mut self) -> &mut Vec<H160> {
-		panic!("Not implemented")
+		self.allowed_contracts.get_or_insert_with(Vec::new)
 	}
 
 	fn get_allowed_groups(&self) -> &Vec<Secp256r1PublicKey> {
-		panic!("Not implemented")
-		// &self.allowed_groups
+		static EMPTY: Vec<Secp256r1PublicKey> = Vec::new();
+		self.allowed_groups.as_ref().unwrap_or(&EMPTY)
 	}
 
 	fn get_allowed_groups_mut(&mut self) -> &mut Vec<Secp256r1PublicKey> {
-		panic!("Not implemented")
+		self.allowed_groups.get_or_insert_with(Vec::new)
 	}
 
 	fn get_rules(&self) -> &Vec<WitnessRule> {
-		panic!("Not implemented")
+		static EMPTY: Vec<WitnessRule> = Vec::new();
+		self.rules.as_ref().unwrap_or(&EMPTY)
 	}
 
 	fn get_rules_mut(&mut self) -> &mut Vec<WitnessRule> {
-		panic!("Not implemented")
+		self.rules.get_or_insert_with(Vec::new)
 	}
 }
 
@@ -154,17 +156,18 @@ impl NeoSerializable for TransactionSigner {
 		Self: Sized,
 	{
 		let mut signer = TransactionSigner::default();
-		signer.set_signer_hash(reader.read_serializable().unwrap());
-		let scopes = WitnessScope::split(reader.read_u8());
+		let max = NeoConstants::MAX_SIGNER_SUBITEMS as usize;
+		signer.set_signer_hash(reader.read_serializable()?);
+		let scopes = WitnessScope::split(reader.read_u8()?);
 		signer.set_scopes(scopes);
 		if signer.get_scopes().contains(&WitnessScope::CustomContracts) {
-			signer.allowed_contracts = Some(reader.read_serializable_list().unwrap());
+			signer.allowed_contracts = Some(reader.read_serializable_list_max(max)?);
 		}
 		if signer.get_scopes().contains(&WitnessScope::CustomGroups) {
-			signer.allowed_groups = Some(reader.read_serializable_list().unwrap());
+			signer.allowed_groups = Some(reader.read_serializable_list_max(max)?);
 		}
 		if signer.get_scopes().contains(&WitnessScope::WitnessRules) {
-			signer.rules = Some(reader.read_serializable_list().unwrap());
+			signer.rules = Some(reader.read_serializable_list_max(max)?);
 		}
 		Ok(signer)
 	}
@@ -5,6 +5,7 @@ use std::{
 	hash::{Hash, Hasher},
 };
 
+use base64::{engine::general_purpose, Engine};
 use neo::config::NeoConstants;
 use primitive_types::{H160, H256, U256};
 use rustc_serialize::hex::ToHex;
@@ -13,9 +14,9 @@ use serde::{Deserialize, Serialize};
 use crate::neo_providers::{JsonRpcClient, Provider};
 use neo::prelude::{
 	deserialize_h256, deserialize_h256_option, deserialize_script_hash, serialize_h256,
-	serialize_h256_option, serialize_script_hash, Bytes, Decoder, Encoder, HashableForVec,
-	Middleware, NameOrAddress, NeoSerializable, RawTransaction, Signer, TransactionAttribute,
-	TransactionError, VMState, VarSizeTrait, Witness,
+	serialize_h256_option, serialize_script_hash, Bytes, CodecError, Decoder, Encoder,
+	HashableForVec, Middleware, NameOrAddress, NeoSerializable, RawTransaction, Signer,
+	TransactionAttribute, TransactionError, VMState, VarSizeTrait, Witness,
 };
 
 #[derive(
@@ -75,6 +76,49 @@ impl<P: JsonRpcClient + 'static> Transaction<P> {
 		Transaction { ..Default::default() }
 	}
 
+	/// Decodes a transaction from its binary serialization, e.g. as returned by
+	/// `getrawtransaction` in non-verbose mode.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, CodecError> {
+		if bytes.len() < Self::HEADER_SIZE {
+			return Err(CodecError::IndexOutOfBounds("Read beyond end of buffer".to_string()))
+		}
+		if bytes.len() > NeoConstants::MAX_TRANSACTION_SIZE as usize {
+			return Err(CodecError::InvalidEncoding(format!(
+				"The transaction exceeds the maximum transaction size of {} bytes",
+				NeoConstants::MAX_TRANSACTION_SIZE
+			)))
+		}
+
+		let mut reader = Decoder::new(bytes);
+		let mut tx = Self::decode(&mut reader).map_err(|e| match e {
+			TransactionError::CodecError(e) => e,
+			e => CodecError::InvalidEncoding(e.to_string()),
+		})?;
+		if reader.available() > 0 {
+			return Err(CodecError::InvalidEncoding(format!(
+				"{} unexpected bytes after the transaction",
+				reader.available()
+			)))
+		}
+		tx.size = bytes.len() as i32;
+		Ok(tx)
+	}
+
+	/// Decodes a hex encoded transaction. A leading `0x` is ignored.
+	pub fn from_hex(hex: &str) -> Result<Self, CodecError> {
+		let bytes = hex::decode(hex.trim().trim_start_matches("0x"))
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Decodes a base64 encoded transaction.
+	pub fn from_base64(base64: &str) -> Result<Self, CodecError> {
+		let bytes = general_purpose::STANDARD
+			.decode(base64.trim())
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
 	pub fn add_witness(&mut self, witness: Witness) {
 		self.witnesses.push(witness);
 	}
@@ -83,15 +127,66 @@ impl<P: JsonRpcClient + 'static> Transaction<P> {
 		if self.provider.is_none() {
 			panic!("Transaction network magic is not set");
 		}
+		let network = self.provider.unwrap().network().await;
+		Ok(self.get_hash_data_for_network(network))
+	}
+
+	/// Returns the data that is signed by the transaction's witnesses, i.e. the network magic
+	/// followed by the transaction hash.
+	pub fn get_hash_data_for_network(&self, network: u32) -> Bytes {
+		let mut data = network.to_le_bytes().to_vec();
+		data.extend(self.get_tx_id_bytes());
+		data
+	}
+
+	/// Verifies the signatures of all witnesses for `network`, e.g. after collecting the
+	/// witnesses of co-signers.
+	///
+	/// Every signer needs a witness at the same position whose verification script hashes to the
+	/// signer, and the signatures of single-sig and multi-sig witnesses have to be valid for
+	/// [`Transaction::get_hash_data_for_network`]. Witnesses of contract signers, which have no
+	/// verification script, are verified by the contract on chain and skipped.
+	pub fn verify_witnesses(&self, network: u32) -> Result<(), TransactionError> {
+		if self.witnesses.len() != self.signers.len() {
+			return Err(TransactionError::IllegalState(format!(
+				"The transaction has {} signers but {} witnesses",
+				self.signers.len(),
+				self.witnesses.len()
+			)))
+		}
+
+		let hash_data = self.get_hash_data_for_network(network);
+		for (index, (signer, witness)) in self.signers.iter().zip(&self.witnesses).enumerate() {
+			if witness.verification.script().is_empty() {
+				continue
+			}
+			if &witness.verification.hash() != signer.get_signer_hash() {
+				return Err(TransactionError::InvalidWitness(
+					index,
+					"The verification script does not match the signer".to_string(),
+				))
+			}
+			witness
+				.verify(&hash_data)
+				.map_err(|e| TransactionError::InvalidWitness(index, e.to_string()))?;
+		}
+		Ok(())
+	}
+
+	/// Returns the transaction hash, in the big-endian form used for display and RPC calls.
+	pub fn get_tx_id(&self) -> H256 {
+		let mut hash = self.get_tx_id_bytes();
+		hash.reverse();
+		H256::from_slice(&hash)
+	}
+
+	fn get_tx_id_bytes(&self) -> [u8; 32] {
 		let mut encoder = Encoder::new();
 		self.serialize_without_witnesses(&mut encoder);
-		let mut data = encoder.to_bytes().hash256();
-		data.splice(0..0, self.provider.unwrap().network().await);
-
-		Ok(data)
+		encoder.to_bytes().hash256()
 	}
 
-	fn serialize_without_witnesses(&self, writer: &mut Encoder) {
+	pub(crate) fn serialize_without_witnesses(&self, writer: &mut Encoder) {
 		writer.write_u8(self.version);
 		writer.write_u32(self.nonce as u32);
 		writer.write_i64(self.sys_fee);
@@ -148,24 +243,31 @@ impl<P: JsonRpcClient + 'static> NeoSerializable for Transaction<P> {
 	where
 		Self: Sized,
 	{
-		let version = reader.read_u8();
-		let nonce = reader.read_u32();
-		let system_fee = reader.read_i64();
-		let network_fee = reader.read_i64();
-		let valid_until_block = reader.read_u32();
+		let max_attributes = NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize;
+		let version = reader.read_u8()?;
+		let nonce = reader.read_u32()?;
+		let system_fee = reader.read_i64()?;
+		let network_fee = reader.read_i64()?;
+		let valid_until_block = reader.read_u32()?;
 
 		// Read signers
-		let signers: Vec<Signer> = reader.read_serializable_list::<Signer>().unwrap();
+		let signers: Vec<Signer> = reader.read_serializable_list_max::<Signer>(max_attributes)?;
+		if signers.is_empty() {
+			return Err(TransactionError::NoSigners)
+		}
 
-		// Read attributes
-		let attributes: Vec<TransactionAttribute> =
-			reader.read_serializable_list::<TransactionAttribute>().unwrap();
+		// Read attributes, which share their limit with the signers
+		let attributes: Vec<TransactionAttribute> = reader
+			.read_serializable_list_max::<TransactionAttribute>(max_attributes - signers.len())?;
 
-		let script = reader.read_var_bytes().unwrap().to_vec();
+		let script = reader.read_var_bytes_max(u16::MAX as usize)?;
+		if script.is_empty() {
+			return Err(TransactionError::EmptyScript)
+		}
 
 		let mut witnesses = vec![];
 		if reader.available() > 0 {
-			witnesses.append(&mut reader.read_serializable_list::<Witness>().unwrap());
+			witnesses.append(&mut reader.read_serializable_list_max::<Witness>(signers.len())?);
 		}
 
 		Ok(Self {
@@ -190,3 +292,71 @@ impl<P: JsonRpcClient + 'static> NeoSerializable for Transaction<P> {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		AccountSigner, ContractSigner, InvocationScript, KeyPair, MockProvider, VerificationScript,
+	};
+
+	use super::*;
+
+	const NETWORK: u32 = 860833102;
+
+	#[test]
+	fn test_verify_witnesses() {
+		let key_pair = KeyPair::new_random();
+		let mut multi_sig_key_pairs = vec![KeyPair::new_random(), KeyPair::new_random()];
+		multi_sig_key_pairs.sort_by_key(|key_pair| key_pair.public_key());
+		let mut public_keys: Vec<_> =
+			multi_sig_key_pairs.iter().map(|key_pair| key_pair.public_key()).collect();
+		let multi_sig_script = VerificationScript::from_multi_sig(&mut public_keys, 2);
+
+		let mut tx: Transaction<MockProvider> = Transaction::new();
+		tx.script = vec![0x40];
+		tx.signers = vec![
+			AccountSigner::called_by_entry_hash160(
+				VerificationScript::from_public_key(&key_pair.public_key()).hash(),
+			)
+			.unwrap()
+			.into(),
+			AccountSigner::called_by_entry_hash160(multi_sig_script.hash()).unwrap().into(),
+			ContractSigner::called_by_entry(H160::repeat_byte(0x01), &[]).into(),
+		];
+		assert!(matches!(tx.verify_witnesses(NETWORK), Err(TransactionError::IllegalState(_))));
+
+		let hash_data = tx.get_hash_data_for_network(NETWORK);
+		let signatures = multi_sig_key_pairs
+			.iter()
+			.map(|key_pair| key_pair.expose_secret().sign_message(&hash_data).unwrap())
+			.collect();
+		tx.witnesses = vec![
+			Witness::create(hash_data.clone(), &key_pair).unwrap(),
+			Witness::create_multi_sig_witness_script(signatures, multi_sig_script).unwrap(),
+			Witness::new(),
+		];
+		tx.verify_witnesses(NETWORK).unwrap();
+		assert!(matches!(
+			tx.verify_witnesses(NETWORK + 1),
+			Err(TransactionError::InvalidWitness(0, _))
+		));
+
+		// A signature of another key
+		let mut tampered = tx.clone();
+		tampered.witnesses[0].invocation = InvocationScript::from_signature(
+			KeyPair::new_random().expose_secret().sign_message(&hash_data).unwrap(),
+		);
+		assert!(matches!(
+			tampered.verify_witnesses(NETWORK),
+			Err(TransactionError::InvalidWitness(0, _))
+		));
+
+		// The witnesses in the wrong order
+		let mut swapped = tx.clone();
+		swapped.witnesses.swap(0, 1);
+		assert!(matches!(
+			swapped.verify_witnesses(NETWORK),
+			Err(TransactionError::InvalidWitness(0, _))
+		));
+	}
+}
@@ -1,11 +1,12 @@
 use std::hash::Hasher;
 
-use rustc_serialize::base64::FromBase64;
+use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 
-use neo::prelude::{Decoder, Encoder, NeoSerializable, TransactionError};
-
-use crate::prelude::Base64Encode;
+use neo::prelude::{
+	deserialize_base64, deserialize_h256, serialize_base64, serialize_h256, var_size, Decoder,
+	Encoder, NeoSerializable, TransactionError,
+};
 
 use super::oracle_response_code::OracleResponseCode;
 
@@ -17,59 +18,78 @@ pub enum TransactionAttribute {
 
 	#[serde(rename = "OracleResponse")]
 	OracleResponse(OracleResponse),
+
+	/// The transaction is not valid before the given block height.
+	#[serde(rename = "NotValidBefore")]
+	NotValidBefore { height: u32 },
+
+	/// The transaction conflicts with (and replaces) the transaction with the given hash.
+	#[serde(rename = "Conflicts")]
+	Conflicts {
+		#[serde(serialize_with = "serialize_h256", deserialize_with = "deserialize_h256")]
+		hash: H256,
+	},
+
+	/// neo-go extension marking a transaction as assisted by the P2P notary service, where
+	/// `n_keys` is the number of keys that are expected to sign it through the notary.
+	#[serde(rename = "NotaryAssisted")]
+	NotaryAssisted {
+		#[serde(rename = "nkeys")]
+		n_keys: u8,
+	},
 }
 
+/// The response of an oracle node to an `OracleRequest`, attached to the response transaction.
+///
+/// The `result` holds the raw bytes, and is base64 encoded in JSON the same way the node returns
+/// it.
 #[derive(Serialize, Deserialize, PartialEq, Hash, Debug, Clone)]
-struct OracleResponse {
-	pub(crate) id: u32,
-	pub(crate) response_code: OracleResponseCode,
-	pub(crate) result: String,
+pub struct OracleResponse {
+	pub id: u64,
+	#[serde(rename = "code")]
+	pub response_code: OracleResponseCode,
+	#[serde(serialize_with = "serialize_base64", deserialize_with = "deserialize_base64")]
+	pub result: Vec<u8>,
+}
+
+impl OracleResponse {
+	pub fn new(id: u64, response_code: OracleResponseCode, result: &[u8]) -> Self {
+		Self { id, response_code, result: result.to_vec() }
+	}
 }
 
 impl TransactionAttribute {
 	pub const MAX_RESULT_SIZE: usize = 0xffff;
 
-	pub fn to_bytes(&self) -> Vec<u8> {
-		let mut bytes = vec![];
+	const HIGH_PRIORITY_BYTE: u8 = 0x01;
+	const ORACLE_RESPONSE_BYTE: u8 = 0x11;
+	const NOT_VALID_BEFORE_BYTE: u8 = 0x20;
+	const CONFLICTS_BYTE: u8 = 0x21;
+	const NOTARY_ASSISTED_BYTE: u8 = 0x22;
 
+	/// The attribute type byte used in the binary encoding.
+	pub fn type_byte(&self) -> u8 {
 		match self {
-			TransactionAttribute::HighPriority => {
-				bytes.push(0x01);
-			},
-			TransactionAttribute::OracleResponse(OracleResponse { id, response_code, result }) => {
-				bytes.push(0x11);
-				bytes.extend(&id.to_be_bytes());
-				bytes.push(response_code.clone() as u8);
-				bytes.extend(result.as_bytes());
-			},
+			TransactionAttribute::HighPriority => Self::HIGH_PRIORITY_BYTE,
+			TransactionAttribute::OracleResponse(_) => Self::ORACLE_RESPONSE_BYTE,
+			TransactionAttribute::NotValidBefore { .. } => Self::NOT_VALID_BEFORE_BYTE,
+			TransactionAttribute::Conflicts { .. } => Self::CONFLICTS_BYTE,
+			TransactionAttribute::NotaryAssisted { .. } => Self::NOTARY_ASSISTED_BYTE,
 		}
+	}
 
-		bytes
+	/// Whether a transaction may carry more than one attribute of this type.
+	pub fn allow_multiple(&self) -> bool {
+		matches!(self, TransactionAttribute::Conflicts { .. })
 	}
 
-	pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
-		match bytes[0] {
-			0x01 => Ok(TransactionAttribute::HighPriority),
-			0x11 => {
-				if bytes.len() < 9 {
-					return Err("Not enough bytes for OracleResponse")
-				}
-				let mut array = [0; 8];
-				let slice_len = bytes[1..9].len();
-				array[8 - slice_len..].copy_from_slice(&bytes[1..9]);
-				let id = u64::from_be_bytes(array);
-				let response_code = OracleResponseCode::try_from(bytes[9]).unwrap();
-				let result =
-					String::from_utf8(bytes[10..].to_vec()).map_err(|_| "Invalid UTF-8").unwrap();
-
-				Ok(TransactionAttribute::OracleResponse(OracleResponse {
-					id: id as u32,
-					response_code,
-					result,
-				}))
-			},
-			_ => Err("Invalid attribute type byte"),
-		}
+	pub fn to_bytes(&self) -> Vec<u8> {
+		self.to_array()
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, TransactionError> {
+		let mut reader = Decoder::new(bytes);
+		Self::decode(&mut reader)
 	}
 
 	pub fn to_json(&self) -> String {
@@ -83,44 +103,70 @@ impl NeoSerializable for TransactionAttribute {
 	fn size(&self) -> usize {
 		match self {
 			TransactionAttribute::HighPriority => 1,
-			TransactionAttribute::OracleResponse(OracleResponse {
-				id: _,
-				response_code: _,
-				result,
-			}) => 1 + 9 + result.len(),
+			TransactionAttribute::OracleResponse(response) =>
+				1 + 8 + 1 + var_size(response.result.len()) + response.result.len(),
+			TransactionAttribute::NotValidBefore { .. } => 1 + 4,
+			TransactionAttribute::Conflicts { .. } => 1 + 32,
+			TransactionAttribute::NotaryAssisted { .. } => 1 + 1,
 		}
 	}
 
 	fn encode(&self, writer: &mut Encoder) {
 		match self {
 			TransactionAttribute::HighPriority => {
-				writer.write_u8(0x01);
+				writer.write_u8(Self::HIGH_PRIORITY_BYTE);
 			},
-			TransactionAttribute::OracleResponse(OracleResponse { id, response_code, result }) => {
-				writer.write_u8(0x11);
-				let mut v = id.to_be_bytes();
-				v.reverse();
-				writer.write(&v);
-				writer.write_u8(response_code.clone() as u8);
-				writer.write_var_bytes(result.from_base64().unwrap().as_slice());
+			TransactionAttribute::OracleResponse(response) => {
+				writer.write_u8(Self::ORACLE_RESPONSE_BYTE);
+				writer.write_u64(response.id);
+				writer.write_u8(response.response_code.into());
+				writer.write_var_bytes(&response.result);
+			},
+			TransactionAttribute::NotValidBefore { height } => {
+				writer.write_u8(Self::NOT_VALID_BEFORE_BYTE);
+				writer.write_u32(*height);
+			},
+			TransactionAttribute::Conflicts { hash } => {
+				writer.write_u8(Self::CONFLICTS_BYTE);
+				// Hashes are displayed big-endian but serialized little-endian.
+				let mut bytes = hash.as_bytes().to_vec();
+				bytes.reverse();
+				writer.write_bytes(&bytes);
+			},
+			TransactionAttribute::NotaryAssisted { n_keys } => {
+				writer.write_u8(Self::NOTARY_ASSISTED_BYTE);
+				writer.write_u8(*n_keys);
 			},
 		}
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		match reader.read_u8() {
-			0x01 => Ok(TransactionAttribute::HighPriority),
-			0x11 => {
-				let id = reader.read_u32();
-				let response_code = OracleResponseCode::try_from(reader.read_u8()).unwrap();
-				let result = reader.read_var_bytes().unwrap().to_base64();
-
-				Ok(TransactionAttribute::OracleResponse(OracleResponse {
+		match reader.read_u8()? {
+			Self::HIGH_PRIORITY_BYTE => Ok(TransactionAttribute::HighPriority),
+			Self::ORACLE_RESPONSE_BYTE => {
+				let id = reader.read_u64()?;
+				let response_code = OracleResponseCode::try_from(reader.read_u8()?)
+					.map_err(|_| TransactionError::InvalidTransaction)?;
+				let result = reader.read_var_bytes_max(Self::MAX_RESULT_SIZE)?;
+				if response_code != OracleResponseCode::Success && !result.is_empty() {
+					return Err(TransactionError::InvalidTransaction)
+				}
+
+				Ok(TransactionAttribute::OracleResponse(OracleResponse::new(
 					id,
 					response_code,
-					result,
-				}))
+					&result,
+				)))
+			},
+			Self::NOT_VALID_BEFORE_BYTE =>
+				Ok(TransactionAttribute::NotValidBefore { height: reader.read_u32()? }),
+			Self::CONFLICTS_BYTE => {
+				let mut bytes = reader.read_bytes(32)?;
+				bytes.reverse();
+				Ok(TransactionAttribute::Conflicts { hash: H256::from_slice(&bytes) })
 			},
+			Self::NOTARY_ASSISTED_BYTE =>
+				Ok(TransactionAttribute::NotaryAssisted { n_keys: reader.read_u8()? }),
 			_ => Err(TransactionError::InvalidTransaction),
 		}
 	}
@@ -131,3 +177,106 @@ impl NeoSerializable for TransactionAttribute {
 		writer.to_bytes()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H256;
+
+	use neo::prelude::{NeoSerializable, OracleResponse, OracleResponseCode, TransactionAttribute};
+
+	#[test]
+	fn test_serialize_oracle_response() {
+		let attribute = TransactionAttribute::OracleResponse(OracleResponse::new(
+			0x0102030405060708,
+			OracleResponseCode::Success,
+			b"neo",
+		));
+
+		assert_eq!(attribute.to_array(), hex::decode("11080706050403020100036e656f").unwrap());
+		assert_eq!(attribute.size(), 14);
+	}
+
+	#[test]
+	fn test_deserialize_oracle_response() {
+		let bytes = hex::decode("11080706050403020100036e656f").unwrap();
+		let attribute = TransactionAttribute::from_bytes(&bytes).unwrap();
+
+		let TransactionAttribute::OracleResponse(response) = attribute else {
+			panic!("Expected an oracle response attribute")
+		};
+		assert_eq!(response.id, 0x0102030405060708);
+		assert_eq!(response.response_code, OracleResponseCode::Success);
+		assert_eq!(response.result, b"neo".to_vec());
+	}
+
+	#[test]
+	fn test_deserialize_oracle_response_with_result_on_failure() {
+		let bytes = hex::decode("11010000000000000014036e656f").unwrap();
+		assert!(TransactionAttribute::from_bytes(&bytes).is_err());
+	}
+
+	#[test]
+	fn test_oracle_response_json() {
+		let attribute = TransactionAttribute::OracleResponse(OracleResponse::new(
+			1,
+			OracleResponseCode::Success,
+			b"neo",
+		));
+
+		assert_eq!(
+			attribute.to_json(),
+			r#"{"type":"OracleResponse","id":1,"code":"Success","result":"bmVv"}"#
+		);
+		assert_eq!(
+			serde_json::from_str::<TransactionAttribute>(&attribute.to_json()).unwrap(),
+			attribute
+		);
+		assert!(serde_json::from_str::<TransactionAttribute>(
+			r#"{"type":"OracleResponse","id":1,"code":"Success","result":"not base64!"}"#
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn test_not_valid_before() {
+		let attribute = TransactionAttribute::NotValidBefore { height: 0x01020304 };
+
+		assert_eq!(attribute.to_array(), hex::decode("2004030201").unwrap());
+		assert_eq!(attribute.size(), 5);
+		assert_eq!(TransactionAttribute::from_bytes(&attribute.to_array()).unwrap(), attribute);
+		assert_eq!(attribute.to_json(), r#"{"type":"NotValidBefore","height":16909060}"#);
+	}
+
+	#[test]
+	fn test_conflicts() {
+		let hash =
+			H256::from_str("0x0102030405060708091011121314151617181920212223242526272829303132")
+				.unwrap();
+		let attribute = TransactionAttribute::Conflicts { hash };
+
+		assert_eq!(
+			attribute.to_array(),
+			hex::decode("213231302928272625242322212019181716151413121110090807060504030201")
+				.unwrap()
+		);
+		assert_eq!(attribute.size(), 33);
+		assert_eq!(TransactionAttribute::from_bytes(&attribute.to_array()).unwrap(), attribute);
+		assert!(attribute.allow_multiple());
+
+		let json = attribute.to_json();
+		assert_eq!(serde_json::from_str::<TransactionAttribute>(&json).unwrap(), attribute);
+	}
+
+	#[test]
+	fn test_notary_assisted() {
+		let attribute = TransactionAttribute::NotaryAssisted { n_keys: 3 };
+
+		assert_eq!(attribute.to_array(), vec![0x22, 0x03]);
+		assert_eq!(attribute.size(), 2);
+		assert_eq!(TransactionAttribute::from_bytes(&attribute.to_array()).unwrap(), attribute);
+		assert_eq!(attribute.to_json(), r#"{"type":"NotaryAssisted","nkeys":3}"#);
+		assert!(!attribute.allow_multiple());
+	}
+}
@@ -43,6 +43,7 @@ pub struct TransactionBuilder<P: JsonRpcClient + 'static> {
 	additional_network_fee: u64,
 	additional_system_fee: u64,
 	attributes: Vec<TransactionAttribute>,
+	#[getset(get = "pub")]
 	script: Option<Bytes>,
 	fee_consumer: Option<Box<dyn Fn(u64, u64)>>,
 	fee_error: Option<TransactionError>,
@@ -188,6 +189,97 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 		self
 	}
 
+	pub fn additional_network_fee(&mut self, fee: u64) -> &mut Self {
+		self.additional_network_fee = fee;
+		self
+	}
+
+	pub fn additional_system_fee(&mut self, fee: u64) -> &mut Self {
+		self.additional_system_fee = fee;
+		self
+	}
+
+	/// Adds the given attributes to the transaction.
+	///
+	/// A transaction can hold at most `MAX_TRANSACTION_ATTRIBUTES` attributes including its
+	/// signers, and only `Conflicts` attributes may appear more than once.
+	pub fn add_attributes(
+		&mut self,
+		attributes: Vec<TransactionAttribute>,
+	) -> Result<&mut Self, TransactionError> {
+		let mut merged = self.attributes.clone();
+		for attribute in attributes {
+			if !attribute.allow_multiple()
+				&& merged.iter().any(|a| a.type_byte() == attribute.type_byte())
+			{
+				return Err(TransactionError::DuplicateAttribute(attribute.to_json()))
+			}
+			merged.push(attribute);
+		}
+		Self::check_attribute_limit(&merged, &self.signers)?;
+		self.attributes = merged;
+		Ok(self)
+	}
+
+	fn check_attribute_limit(
+		attributes: &[TransactionAttribute],
+		signers: &[Signer],
+	) -> Result<(), TransactionError> {
+		if attributes.len() + signers.len() > NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize {
+			return Err(TransactionError::TooManyAttributes(
+				NeoConstants::MAX_TRANSACTION_ATTRIBUTES,
+			))
+		}
+		Ok(())
+	}
+
+	/// Prepares a builder for a transaction that replaces `stuck`, a transaction still waiting in
+	/// the memory pool.
+	///
+	/// The replacement keeps the signers, script and validity of `stuck` and declares a
+	/// `Conflicts` attribute on its hash. Nodes only accept the replacement if it is signed by
+	/// the sender of `stuck` and pays a higher network fee, so the network fee of `stuck` plus
+	/// `fee_bump` is added on top of the fee calculated for the replacement.
+	pub fn replace(stuck: &Transaction<P>, fee_bump: u64) -> Result<Self, TransactionError> {
+		if stuck.signers.is_empty() {
+			return Err(TransactionError::NoSigners)
+		}
+
+		let mut builder = Self::new();
+		builder.provider = stuck.provider;
+		builder.version = stuck.version;
+		builder.nonce = stuck.nonce as u32;
+		builder.valid_until_block = Some(stuck.valid_until_block as u32);
+		builder.signers = stuck.signers.clone();
+		builder.script = Some(stuck.script.clone());
+		builder.additional_network_fee = stuck.net_fee as u64 + fee_bump;
+		builder.add_attributes(stuck.attributes.clone())?;
+		builder
+			.add_attributes(vec![TransactionAttribute::Conflicts { hash: stuck.get_tx_id() }])?;
+		Ok(builder)
+	}
+
+	/// Marks the transaction as assisted by the P2P notary service, with `n_keys` keys expected
+	/// to sign it through notary requests.
+	///
+	/// Adds the `NotaryAssisted` attribute and the notary contract as a signer, and raises the
+	/// additional network fee by the notary service fee of `(n_keys + 1) * fee_per_key`, where
+	/// `fee_per_key` is taken from `NotaryContract::get_notary_service_fee_per_key`.
+	pub fn notary_assisted(
+		&mut self,
+		n_keys: u8,
+		fee_per_key: u64,
+	) -> Result<&mut Self, TransactionError> {
+		self.add_attributes(vec![TransactionAttribute::NotaryAssisted { n_keys }])?;
+		if !self.signers.iter().any(|s| s.get_signer_hash() == &*NOTARY_CONTRACT_HASH) {
+			self.signers.push(
+				TransactionSigner::new(*NOTARY_CONTRACT_HASH, vec![WitnessScope::None]).into(),
+			);
+		}
+		self.additional_network_fee += (n_keys as u64 + 1) * fee_per_key;
+		Ok(self)
+	}
+
 	// Get unsigned transaction
 	pub async fn get_unsigned_tx(&mut self) -> Result<Transaction<P>, TransactionError> {
 		// Validate configuration
@@ -210,6 +302,7 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 		if self.signers.len() > NeoConstants::MAX_SIGNER_SUBITEMS as usize {
 			return Err(TransactionError::TooManySigners)
 		}
+		Self::check_attribute_limit(&self.attributes, &self.signers)?;
 
 		// Validate script
 		if let Some(script) = &self.script {
@@ -297,36 +390,42 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 	}
 
 	// Sign transaction
-	pub async fn sign(&mut self) -> Result<&mut Self, BuilderError> {
-		let mut transaction =
-			self.get_unsigned_tx().await.unwrap().with_provider(self.provider.unwrap());
+	pub async fn sign(&mut self) -> Result<Transaction<P>, BuilderError> {
+		self.sign_with(&[]).await
+	}
+
+	/// Signs the transaction like [`TransactionBuilder::sign`], using `external_signers` for the
+	/// accounts that hold no private key, such as the accounts of HSM or KMS keys.
+	///
+	/// The witness of such an account is created by the external signer with the same script
+	/// hash. The external signers may be of different kinds, e.g. one HSM and one KMS key.
+	pub async fn sign_with(
+		&mut self,
+		external_signers: &[&dyn ExternalSigner],
+	) -> Result<Transaction<P>, BuilderError> {
+		let provider = self.provider.ok_or(TransactionError::NoProvider)?;
+		let mut transaction = self.get_unsigned_tx().await?;
+		transaction.with_provider(provider);
 		let tx_bytes = transaction.get_hash_data().await?;
 
 		let mut witnesses_to_add = Vec::new();
 
-		for signer in &mut transaction.signers {
-			if Self::is_account_signer(signer) {
-				let account_signer = signer.as_account_signer().unwrap();
-				let acc = &account_signer.account;
-				if acc.is_multi_sig() {
-					return Err(BuilderError::IllegalState(
-						"Transactions with multi-sig signers cannot be signed automatically."
-							.to_string(),
-					))
-				}
-
-				let key_pair = acc.key_pair().as_ref().ok_or_else(|| {
-					BuilderError::InvalidConfiguration(
-						"Cannot create transaction signature because account does not hold a private key.".to_string(),
-					)
-				})?;
-
-				witnesses_to_add.push(Witness::create(tx_bytes.clone(), key_pair)?);
-			} else {
-				let contract_signer = signer.as_contract_signer().unwrap();
-				witnesses_to_add
-					.push(Witness::create_contract_witness(contract_signer.verify_params.clone())?);
-			}
+		for signer in &transaction.signers {
+			let witness = match Self::local_witness(signer, &tx_bytes)? {
+				Some(witness) => witness,
+				None => {
+					let external_signer = external_signers
+						.iter()
+						.find(|external| &external.script_hash() == signer.get_signer_hash())
+						.ok_or_else(Self::missing_private_key)?;
+					let signature = external_signer
+						.sign_message(&tx_bytes)
+						.await
+						.map_err(|e| BuilderError::ExternalSignerError(e.to_string()))?;
+					external_signer.witness(signature)
+				},
+			};
+			witnesses_to_add.push(witness);
 		}
 
 		for witness in witnesses_to_add {
@@ -336,6 +435,36 @@ impl<P: JsonRpcClient> TransactionBuilder<P> {
 		Ok(transaction)
 	}
 
+	// The witness of a contract signer or of an account holding its key pair, `None` for
+	// accounts without a key pair
+	fn local_witness(signer: &Signer, tx_bytes: &Bytes) -> Result<Option<Witness>, BuilderError> {
+		if !Self::is_account_signer(signer) {
+			let contract_signer = signer.as_contract_signer().unwrap();
+			return Ok(Some(Witness::create_contract_witness(
+				contract_signer.verify_params.clone(),
+			)?))
+		}
+
+		let acc = &signer.as_account_signer().unwrap().account;
+		if acc.is_multi_sig() {
+			return Err(BuilderError::IllegalState(
+				"Transactions with multi-sig signers cannot be signed automatically.".to_string(),
+			))
+		}
+
+		match acc.key_pair() {
+			Some(key_pair) => Ok(Some(Witness::create(tx_bytes.clone(), key_pair)?)),
+			None => Ok(None),
+		}
+	}
+
+	fn missing_private_key() -> BuilderError {
+		BuilderError::InvalidConfiguration(
+			"Cannot create transaction signature because account does not hold a private key."
+				.to_string(),
+		)
+	}
+
 	fn signers_contain_multi_sig_with_committee_member(&self, committee: &HashSet<H160>) -> bool {
 		for signer in &self.signers {
 			if let Some(account_signer) = signer.as_account_signer() {
@@ -395,13 +524,16 @@ mod tests {
 		},
 	};
 
+	use async_trait::async_trait;
 	use lazy_static::lazy_static;
 	use openssl::rand;
 	use primitive_types::{H160, H256};
 
 	use neo::prelude::{
-		Account, AccountSigner, AccountTrait, ContractSigner, Http, Middleware, NeoConstants,
-		Provider, ScriptBuilder, TransactionAttribute, TransactionBuilder, Witness,
+		Account, AccountSigner, AccountTrait, BuilderError, ContractSigner, ExternalSigner, Http,
+		KeyPair, Middleware, NeoConstants, Provider, ScriptBuilder, Secp256r1PublicKey,
+		Secp256r1Signature, SignerError, Transaction, TransactionAttribute, TransactionBuilder,
+		TransactionError, Witness, NOTARY_CONTRACT_HASH,
 	};
 	use rand::random;
 
@@ -522,6 +654,146 @@ mod tests {
 		assert!(signers.contains(&ACCOUNT2.deref().key_pair.unwrap().public_key()));
 	}
 
+	// Stands in for an HSM holding the key of ACCOUNT2
+	struct LocalKeySigner(KeyPair);
+
+	#[async_trait]
+	impl ExternalSigner for LocalKeySigner {
+		fn public_key(&self) -> &Secp256r1PublicKey {
+			&self.0.public_key
+		}
+
+		async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+			Ok(self.0.expose_secret().sign_prehash(digest)?)
+		}
+	}
+
+	#[tokio::test]
+	async fn test_sign_transaction_with_external_signer() {
+		let key_pair = ACCOUNT2.deref().key_pair().clone().unwrap();
+		let external_account = Account::from_public_key(&key_pair.public_key()).unwrap();
+		let external_signer = LocalKeySigner(key_pair);
+
+		let mut builder = TransactionBuilder::with_provider(TEST_PROVIDER.deref());
+		builder
+			.set_script(vec![0x01, 0x02, 0x03])
+			.set_signers(vec![
+				AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into(),
+				AccountSigner::called_by_entry(&external_account).unwrap().into(),
+			])
+			.valid_until_block(1000)
+			.unwrap();
+
+		let err = builder.sign().await.unwrap_err();
+		assert!(matches!(err, BuilderError::InvalidConfiguration(_)));
+
+		let tx = builder.sign_with(&[&external_signer]).await.unwrap();
+		assert_eq!(tx.witnesses().len(), 2);
+		let tx_bytes = tx.get_hash_data().await.unwrap();
+		for witness in tx.witnesses() {
+			witness.verify(&tx_bytes).unwrap();
+		}
+		assert_eq!(tx.witnesses()[1].verification, external_signer.verification_script());
+	}
+
+	#[tokio::test]
+	async fn test_sign_without_provider() {
+		let err = TransactionBuilder::<Http>::new()
+			.set_script(vec![0x01, 0x02, 0x03])
+			.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()])
+			.valid_until_block(1000)
+			.unwrap()
+			.sign()
+			.await
+			.unwrap_err();
+
+		assert!(matches!(err, BuilderError::TransactionError(TransactionError::NoProvider)));
+	}
+
+	#[test]
+	fn test_fail_adding_more_than_max_attributes_to_tx() {
+		let one_too_many = NeoConstants::MAX_TRANSACTION_ATTRIBUTES + 1;
+		let attributes = (0..one_too_many)
+			.map(|i| TransactionAttribute::Conflicts { hash: H256::from_low_u64_be(i as u64) })
+			.collect();
+
+		let error = TransactionBuilder::<Http>::new().add_attributes(attributes).err().unwrap();
+
+		assert_eq!(
+			error.to_string(),
+			format!(
+				"A transaction cannot have more than {} attributes (including signers)",
+				NeoConstants::MAX_TRANSACTION_ATTRIBUTES
+			)
+		);
+	}
+
+	#[test]
+	fn test_fail_adding_more_than_max_attributes_to_tx_with_signers() {
+		let mut builder = TransactionBuilder::<Http>::new();
+		builder.set_signers(vec![
+			AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into(),
+			AccountSigner::called_by_entry(ACCOUNT2.deref()).unwrap().into(),
+		]);
+
+		let attributes = (0..NeoConstants::MAX_TRANSACTION_ATTRIBUTES - 1)
+			.map(|i| TransactionAttribute::Conflicts { hash: H256::from_low_u64_be(i as u64) })
+			.collect();
+
+		let error = builder.add_attributes(attributes).err().unwrap();
+
+		assert!(error.to_string().contains("A transaction cannot have more than"));
+	}
+
+	#[test]
+	fn test_fail_adding_duplicate_attributes() {
+		let error = TransactionBuilder::<Http>::new()
+			.add_attributes(vec![
+				TransactionAttribute::HighPriority,
+				TransactionAttribute::HighPriority,
+			])
+			.err()
+			.unwrap();
+
+		assert!(matches!(error, TransactionError::DuplicateAttribute(_)));
+	}
+
+	#[test]
+	fn test_replace_stuck_transaction() {
+		let mut stuck = Transaction::<Http>::new();
+		stuck.nonce = 7;
+		stuck.valid_until_block = 1000;
+		stuck.net_fee = 1_000_000;
+		stuck.signers = vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()];
+		stuck.script = vec![1, 2, 3];
+
+		let builder = TransactionBuilder::replace(&stuck, 10).unwrap();
+
+		assert_eq!(builder.signers(), &stuck.signers);
+		assert_eq!(builder.additional_network_fee, 1_000_010);
+		assert_eq!(
+			builder.attributes,
+			vec![TransactionAttribute::Conflicts { hash: stuck.get_tx_id() }]
+		);
+	}
+
+	#[test]
+	fn test_notary_assisted() {
+		let mut builder = TransactionBuilder::<Http>::new();
+		builder.set_signers(vec![AccountSigner::called_by_entry(ACCOUNT1.deref()).unwrap().into()]);
+		builder.notary_assisted(2, 10_000_000).unwrap();
+
+		assert_eq!(builder.signers().len(), 2);
+		assert_eq!(builder.signers()[1].get_signer_hash(), &*NOTARY_CONTRACT_HASH);
+		assert_eq!(builder.attributes, vec![TransactionAttribute::NotaryAssisted { n_keys: 2 }]);
+		assert_eq!(builder.additional_network_fee, 30_000_000);
+
+		assert!(matches!(
+			builder.notary_assisted(2, 10_000_000),
+			Err(TransactionError::DuplicateAttribute(_))
+		));
+	}
+
 	// 	#[tokio::test]
 	// 	async fn test_send_invoke_function() {
 	// 		let script = ScriptBuilder::new()
@@ -704,41 +976,6 @@ mod tests {
 	// 	}
 	//
 	// 	#[tokio::test]
-	// 	async fn test_fail_adding_more_than_max_attributes_to_tx() {
-	// 		let one_too_many = NeoConstants::MAX_TRANSACTION_ATTRIBUTES + 1;
-	// 		let attributes = vec![TransactionAttribute::HighPriority; one_too_many as usize];
-	//
-	// 		let error = TransactionBuilder::with_provider(TEST_PROVIDER.deref()).attributes(&attributes).await.err().unwrap();
-	//
-	// 		assert_eq!(
-	// 			error.to_string(),
-	// 			format!(
-	// 				"A transaction cannot have more than {} attributes",
-	// 				NeoConstants::MAX_TRANSACTION_ATTRIBUTES
-	// 			)
-	// 		);
-	// 	}
-	//
-	// 	#[tokio::test]
-	// 	async fn test_fail_adding_more_than_max_attributes_to_tx_with_signers() {
-	// 		let mut builder = TransactionBuilder::with_provider(TEST_PROVIDER.deref());
-	// 		builder.set_signers(vec![
-	// 			AccountSigner::called_by_entry(&Account::default()).unwrap().into(),
-	// 			AccountSigner::called_by_entry(&Account::default()).unwrap().into(),
-	// 			AccountSigner::called_by_entry(&Account::default()).unwrap().into(),
-	// 		]);
-	//
-	// 		let attributes = vec![
-	// 			TransactionAttribute::HighPriority;
-	// 			(NeoConstants::MAX_TRANSACTION_ATTRIBUTES - 3) as usize
-	// 		];
-	//
-	// 		let error = builder.attributes(&attributes).await.err().unwrap();
-	//
-	// 		assert!(error.to_string().contains("A transaction cannot have more than"));
-	// 	}
-	//
-	// 	#[tokio::test]
 	// 	async fn test_automatic_setting_of_valid_until_block_variable() {
 	// 		let tx = TransactionBuilder::with_provider(TEST_PROVIDER.deref())
 	// 			.script(vec![1, 2, 3])
@@ -18,18 +18,26 @@ pub enum TransactionError {
 	InvalidWitnessCondition,
 	#[error("Too many signers")]
 	TooManySigners,
+	#[error("A transaction cannot have more than {0} attributes (including signers)")]
+	TooManyAttributes(u32),
+	#[error("Duplicate transaction attribute: {0}")]
+	DuplicateAttribute(String),
 	#[error("Duplicate signer")]
 	DuplicateSigner,
 	#[error("No signers")]
 	NoSigners,
 	#[error("No script")]
 	NoScript,
+	#[error("No provider")]
+	NoProvider,
 	#[error("Empty script")]
 	EmptyScript,
 	#[error("Invalid sender")]
 	InvalidSender,
 	#[error("Invalid state:{0}")]
 	IllegalState(String),
+	#[error("Invalid witness {0}: {1}")]
+	InvalidWitness(usize, String),
 	#[error("Transaction too large")]
 	TxTooLarge,
 	#[error("Transaction configuration error: {0}")]
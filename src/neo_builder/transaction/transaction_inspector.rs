@@ -0,0 +1,300 @@
+use std::fmt::Write;
+
+use rust_decimal::Decimal;
+
+use neo::prelude::*;
+
+/// The kind of account a witness belongs to, derived from its verification script.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WitnessType {
+	SingleSig(Secp256r1PublicKey),
+	MultiSig {
+		threshold: usize,
+		public_keys: Vec<Secp256r1PublicKey>,
+	},
+	/// An empty verification script, i.e. the `verify` method of a deployed contract.
+	Contract,
+	/// A verification script that is not a standard signature account.
+	Custom,
+}
+
+impl From<&Witness> for WitnessType {
+	fn from(witness: &Witness) -> Self {
+		let verification = &witness.verification;
+		if verification.script().is_empty() {
+			return WitnessType::Contract
+		}
+		match (verification.get_signing_threshold(), verification.get_public_keys()) {
+			(Ok(1), Ok(keys)) if verification.is_single_sig() =>
+				WitnessType::SingleSig(keys[0].clone()),
+			(Ok(threshold), Ok(public_keys)) => WitnessType::MultiSig { threshold, public_keys },
+			_ => WitnessType::Custom,
+		}
+	}
+}
+
+/// Renders a transaction in a human readable form, e.g. to understand a raw transaction pasted
+/// from a block explorer or a node log.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let tx = Transaction::<Http>::from_hex(raw_tx)?;
+/// println!("{}", TransactionInspector::inspect(&tx));
+/// ```
+pub struct TransactionInspector;
+
+impl TransactionInspector {
+	const GAS_DECIMALS: u32 = 8;
+
+	pub fn inspect<P: JsonRpcClient + 'static>(tx: &Transaction<P>) -> String {
+		let mut out = String::new();
+		writeln!(out, "Hash:              {:#x}", tx.get_tx_id()).unwrap();
+		writeln!(out, "Version:           {}", tx.version).unwrap();
+		writeln!(out, "Nonce:             {}", tx.nonce as u32).unwrap();
+		writeln!(out, "Valid until block: {}", tx.valid_until_block as u32).unwrap();
+		writeln!(out, "System fee:        {} GAS", Self::format_gas(tx.sys_fee)).unwrap();
+		writeln!(out, "Network fee:       {} GAS", Self::format_gas(tx.net_fee)).unwrap();
+		writeln!(out, "Size:              {} bytes", tx.size()).unwrap();
+
+		writeln!(out, "Signers:").unwrap();
+		for (i, signer) in tx.signers.iter().enumerate() {
+			out.push_str(&Self::format_signer(i, signer));
+		}
+
+		writeln!(out, "Attributes:").unwrap();
+		for (i, attribute) in tx.attributes.iter().enumerate() {
+			writeln!(out, "  [{}] {}", i, Self::format_attribute(attribute)).unwrap();
+		}
+
+		writeln!(out, "Script:").unwrap();
+		for line in Self::disassemble(&tx.script) {
+			writeln!(out, "  {}", line).unwrap();
+		}
+
+		writeln!(out, "Witnesses:").unwrap();
+		for (i, witness) in tx.witnesses.iter().enumerate() {
+			writeln!(out, "  [{}] {}", i, Self::format_witness(witness)).unwrap();
+		}
+		out
+	}
+
+	/// Formats an amount of GAS fractions with the 8 decimals of the GAS token.
+	pub fn format_gas(fractions: i64) -> String {
+		Decimal::new(fractions, Self::GAS_DECIMALS).to_string()
+	}
+
+	/// Disassembles a script into one instruction per line, naming the called interop services.
+	pub fn disassemble(script: &Bytes) -> Vec<String> {
+		ScriptReader::convert_to_op_code_string(script)
+			.lines()
+			.map(|line| match line.strip_prefix("SYSCALL ") {
+				Some(hash) => match InteropService::from_hash(hash.to_string()) {
+					Some(service) => format!("SYSCALL {} ({})", hash, service),
+					None => line.to_string(),
+				},
+				None => line.to_string(),
+			})
+			.collect()
+	}
+
+	fn format_signer(index: usize, signer: &Signer) -> String {
+		let hash = signer.get_signer_hash();
+		let scopes =
+			signer.get_scopes().iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", ");
+		let mut out = format!(
+			"  [{}] {} ({:#x}) scopes: {}\n",
+			index,
+			hash.to_address(),
+			hash,
+			if scopes.is_empty() { WitnessScope::None.to_string() } else { scopes }
+		);
+		for contract in signer.get_allowed_contracts() {
+			writeln!(out, "      allowed contract: {:#x}", contract).unwrap();
+		}
+		for group in signer.get_allowed_groups() {
+			writeln!(out, "      allowed group: {}", hex::encode(group.get_encoded(true))).unwrap();
+		}
+		for rule in signer.get_rules() {
+			writeln!(
+				out,
+				"      rule: {} {}",
+				rule.action,
+				serde_json::to_string(&rule.condition).unwrap_or_default()
+			)
+			.unwrap();
+		}
+		out
+	}
+
+	fn format_attribute(attribute: &TransactionAttribute) -> String {
+		match attribute {
+			TransactionAttribute::HighPriority => "HighPriority".to_string(),
+			TransactionAttribute::OracleResponse(response) => format!(
+				"OracleResponse id: {}, code: {:?}, result: {}",
+				response.id,
+				response.response_code,
+				base64::encode(&response.result)
+			),
+			TransactionAttribute::NotValidBefore { height } =>
+				format!("NotValidBefore height: {}", height),
+			TransactionAttribute::Conflicts { hash } => format!("Conflicts hash: {:#x}", hash),
+			TransactionAttribute::NotaryAssisted { n_keys } =>
+				format!("NotaryAssisted keys: {}", n_keys),
+		}
+	}
+
+	fn format_witness(witness: &Witness) -> String {
+		let signatures = Self::count_signatures(witness.invocation.script());
+		match WitnessType::from(witness) {
+			WitnessType::SingleSig(key) => format!(
+				"single-sig {} ({} signature(s))",
+				hex::encode(key.get_encoded(true)),
+				signatures
+			),
+			WitnessType::MultiSig { threshold, public_keys } => format!(
+				"multi-sig {}/{} ({} signature(s))",
+				threshold,
+				public_keys.len(),
+				signatures
+			),
+			WitnessType::Contract =>
+				format!("contract, invocation: {}", hex::encode(witness.invocation.script())),
+			WitnessType::Custom =>
+				format!("custom, verification: {}", hex::encode(witness.verification.script())),
+		}
+	}
+
+	fn count_signatures(invocation: &[u8]) -> usize {
+		invocation
+			.chunks(2 + NeoConstants::SIGNATURE_SIZE as usize)
+			.take_while(|push| {
+				push.len() == 2 + NeoConstants::SIGNATURE_SIZE as usize
+					&& push[0] == OpCode::PushData1.opcode()
+					&& push[1] == NeoConstants::SIGNATURE_SIZE as u8
+			})
+			.count()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		public_key_to_script_hash, CodecError, Http, KeyPair, NeoConstants, NeoSerializable,
+		Secp256r1PrivateKey, Transaction, TransactionAttribute, TransactionInspector,
+		TransactionSigner, VarSizeTrait, Witness, WitnessScope, WitnessType,
+	};
+
+	fn key_pair() -> KeyPair {
+		KeyPair::from_secret_key(
+			&Secp256r1PrivateKey::from_bytes(
+				&hex::decode("e6e919577dd7b8e97805151c05ae07ff4f752654d6d8797597aca989c02c4cb3")
+					.unwrap(),
+			)
+			.unwrap(),
+		)
+	}
+
+	fn transaction() -> Transaction<Http> {
+		let key_pair = key_pair();
+		let mut tx = Transaction::new();
+		tx.nonce = 42;
+		tx.valid_until_block = 1000;
+		tx.sys_fee = 1_000_000;
+		tx.net_fee = 123_456;
+		tx.signers = vec![TransactionSigner::new(
+			public_key_to_script_hash(&key_pair.public_key()),
+			vec![WitnessScope::CalledByEntry],
+		)
+		.into()];
+		tx.attributes = vec![TransactionAttribute::HighPriority];
+		tx.script = hex::decode("0c0548656c6c6f41cfe7479611").unwrap();
+		tx.witnesses = vec![Witness::create(tx.get_hash_data_for_network(0), &key_pair).unwrap()];
+		tx
+	}
+
+	#[test]
+	fn test_from_hex_and_base64() {
+		let tx = transaction();
+		let bytes = tx.to_array();
+
+		let decoded = Transaction::<Http>::from_hex(&hex::encode(&bytes)).unwrap();
+		assert_eq!(decoded, tx);
+		assert_eq!(decoded.size as usize, bytes.len());
+
+		let decoded = Transaction::<Http>::from_hex(&format!("0x{}", hex::encode(&bytes))).unwrap();
+		assert_eq!(decoded, tx);
+
+		let base64 = base64::encode(&bytes);
+		assert_eq!(Transaction::<Http>::from_base64(&base64).unwrap(), tx);
+	}
+
+	#[test]
+	fn test_from_hex_with_malformed_input() {
+		assert!(matches!(
+			Transaction::<Http>::from_hex("not hex"),
+			Err(CodecError::InvalidEncoding(_))
+		));
+		assert!(matches!(
+			Transaction::<Http>::from_hex("0001"),
+			Err(CodecError::IndexOutOfBounds(_))
+		));
+
+		let mut bytes = transaction().to_array();
+		bytes.push(0);
+		assert!(Transaction::<Http>::from_bytes(&bytes).is_err());
+	}
+
+	#[test]
+	fn test_from_bytes_with_truncated_input() {
+		let tx = transaction();
+		let bytes = tx.to_array();
+		// Without its witnesses the transaction is a valid unsigned transaction.
+		let unsigned_len = bytes.len() - tx.witnesses.var_size();
+		for len in (0..bytes.len()).filter(|len| *len != unsigned_len) {
+			assert!(Transaction::<Http>::from_bytes(&bytes[..len]).is_err());
+		}
+		assert!(Transaction::<Http>::from_bytes(&bytes[..unsigned_len])
+			.unwrap()
+			.witnesses
+			.is_empty());
+	}
+
+	#[test]
+	fn test_from_bytes_with_too_many_signers() {
+		let mut bytes = transaction().to_array();
+		// The signer count follows the 25 header bytes.
+		bytes[25] = 17;
+		assert!(Transaction::<Http>::from_bytes(&bytes).is_err());
+
+		let too_large = vec![0u8; NeoConstants::MAX_TRANSACTION_SIZE as usize + 1];
+		assert!(Transaction::<Http>::from_bytes(&too_large).is_err());
+	}
+
+	#[test]
+	fn test_witness_type() {
+		let tx = transaction();
+		assert_eq!(
+			WitnessType::from(&tx.witnesses[0]),
+			WitnessType::SingleSig(key_pair().public_key())
+		);
+		assert_eq!(WitnessType::from(&Witness::new()), WitnessType::Contract);
+	}
+
+	#[test]
+	fn test_inspect() {
+		let output = TransactionInspector::inspect(&transaction());
+
+		assert!(output.contains("Nonce:             42"));
+		assert!(output.contains("System fee:        0.01000000 GAS"));
+		assert!(output.contains("Network fee:       0.00123456 GAS"));
+		assert!(output.contains("scopes: CalledByEntry"));
+		assert!(output.contains("[0] HighPriority"));
+		assert!(output.contains("PUSHDATA1 5 48656c6c6f"));
+		assert!(output.contains("SYSCALL cfe74796 (System.Runtime.Log)"));
+		assert!(output.contains("single-sig "));
+		assert!(output.contains("(1 signature(s))"));
+	}
+}
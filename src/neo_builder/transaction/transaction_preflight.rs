@@ -0,0 +1,317 @@
+use primitive_types::H160;
+use thiserror::Error;
+
+use neo::prelude::*;
+
+/// A reason for the node to reject a transaction, found by [`Transaction::preflight`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PreflightIssue {
+	#[error("The transaction has no signers")]
+	NoSigners,
+	#[error("The transaction has {signers} signers but {witnesses} witnesses")]
+	WitnessCountMismatch { signers: usize, witnesses: usize },
+	#[error("The signer {0:#x} is blocked by the policy contract")]
+	BlockedSigner(H160),
+	#[error("The signer {0:#x} appears more than once")]
+	DuplicateSigner(H160),
+	#[error(
+		"The transaction has {count} attributes including signers, at most {max} are allowed"
+	)]
+	TooManyAttributes { count: usize, max: usize },
+	#[error("The attribute {0} appears more than once")]
+	DuplicateAttribute(String),
+	#[error("The transaction has {size} bytes, at most {max} are allowed")]
+	TooLarge { size: usize, max: usize },
+	#[error(
+		"The transaction expired at block {valid_until_block}, the chain is at block {height}"
+	)]
+	Expired { valid_until_block: u32, height: u32 },
+	#[error("The transaction is valid until block {valid_until_block}, at most {max} is allowed")]
+	ValidUntilBlockTooFar { valid_until_block: u32, max: u32 },
+	#[error(
+		"The transaction is not valid before block {not_valid_before}, the chain is at block {height}"
+	)]
+	NotYetValid { not_valid_before: u32, height: u32 },
+	#[error("The network fee {actual} is below the required {required}")]
+	InsufficientNetworkFee { required: i64, actual: i64 },
+	#[error("The sender {sender:#x} holds {balance} GAS fractions but the fees are {required}")]
+	InsufficientBalance { sender: H160, required: i64, balance: i64 },
+}
+
+/// The outcome of [`Transaction::preflight`], with the chain policy it was checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+	/// The index of the latest block.
+	pub height: u32,
+	pub fee_per_byte: i64,
+	pub exec_fee_factor: i64,
+	/// The network fee the size and the witnesses of the transaction require. Witnesses of
+	/// deployed contracts and other non-standard accounts are not included.
+	pub required_network_fee: i64,
+	pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+	/// Returns `true` if no issues were found.
+	pub fn is_ok(&self) -> bool {
+		self.issues.is_empty()
+	}
+}
+
+impl<P: JsonRpcClient + 'static> Transaction<P> {
+	// Opcode prices used to compute the verification cost of standard witnesses.
+	const PUSHDATA1_PRICE: i64 = 1 << 3;
+	const PUSHINT_PRICE: i64 = 1 << 0;
+	const CHECK_SIG_PRICE: i64 = 1 << 15;
+
+	/// Checks the transaction against the current chain policy before it is sent, reporting every
+	/// reason for the node to reject it instead of only the first.
+	///
+	/// Fails only if the chain state cannot be queried. A signed transaction should be checked,
+	/// as the witnesses count towards the size and the network fee.
+	pub async fn preflight(
+		&self,
+		provider: &Provider<P>,
+	) -> Result<PreflightReport, TransactionError> {
+		let height = provider.get_block_count().await?.saturating_sub(1);
+		let max_increment = provider
+			.get_version()
+			.await?
+			.protocol
+			.map(|protocol| protocol.max_valid_until_block_increment)
+			.ok_or_else(|| {
+				TransactionError::IllegalState("The node did not return its protocol".to_string())
+			})?;
+		let policy = PolicyContract::new(Some(provider));
+		let fee_per_byte = policy.get_fee_per_byte().await.map_err(Self::preflight_error)? as i64;
+		let exec_fee_factor =
+			policy.get_exec_fee_factor().await.map_err(Self::preflight_error)? as i64;
+
+		let mut issues = self.check_structure();
+
+		let valid_until_block = self.valid_until_block as u32;
+		if valid_until_block <= height {
+			issues.push(PreflightIssue::Expired { valid_until_block, height });
+		} else if valid_until_block > height + max_increment {
+			issues.push(PreflightIssue::ValidUntilBlockTooFar {
+				valid_until_block,
+				max: height + max_increment,
+			});
+		}
+		for attribute in &self.attributes {
+			if let TransactionAttribute::NotValidBefore { height: not_valid_before } = attribute {
+				if *not_valid_before > height {
+					issues.push(PreflightIssue::NotYetValid {
+						not_valid_before: *not_valid_before,
+						height,
+					});
+				}
+			}
+		}
+
+		let required_network_fee = self.required_network_fee(fee_per_byte, exec_fee_factor);
+		if self.net_fee < required_network_fee {
+			issues.push(PreflightIssue::InsufficientNetworkFee {
+				required: required_network_fee,
+				actual: self.net_fee,
+			});
+		}
+
+		for signer in &self.signers {
+			let hash = *signer.get_signer_hash();
+			if policy.is_blocked(&hash).await.map_err(Self::preflight_error)? {
+				issues.push(PreflightIssue::BlockedSigner(hash));
+			}
+		}
+
+		if let Some(sender) = self.signers.first().map(|signer| *signer.get_signer_hash()) {
+			let output = GasToken::new(Some(provider))
+				.call_invoke_function("balanceOf", vec![sender.into()], vec![])
+				.await
+				.map_err(Self::preflight_error)?;
+			let balance = output.stack.first().and_then(StackItem::as_int).ok_or_else(|| {
+				TransactionError::IllegalState("Unexpected GAS balance".to_string())
+			})?;
+			let required = self.sys_fee + self.net_fee;
+			if balance < required {
+				issues.push(PreflightIssue::InsufficientBalance { sender, required, balance });
+			}
+		}
+
+		Ok(PreflightReport { height, fee_per_byte, exec_fee_factor, required_network_fee, issues })
+	}
+
+	/// Checks the limits that do not depend on the chain state.
+	fn check_structure(&self) -> Vec<PreflightIssue> {
+		let mut issues = vec![];
+		if self.signers.is_empty() {
+			issues.push(PreflightIssue::NoSigners);
+		}
+		if self.signers.len() != self.witnesses.len() {
+			issues.push(PreflightIssue::WitnessCountMismatch {
+				signers: self.signers.len(),
+				witnesses: self.witnesses.len(),
+			});
+		}
+
+		let mut signer_hashes: Vec<H160> = vec![];
+		for signer in &self.signers {
+			let hash = *signer.get_signer_hash();
+			if signer_hashes.contains(&hash) {
+				issues.push(PreflightIssue::DuplicateSigner(hash));
+			}
+			signer_hashes.push(hash);
+		}
+
+		let max_attributes = NeoConstants::MAX_TRANSACTION_ATTRIBUTES as usize;
+		let count = self.signers.len() + self.attributes.len();
+		if count > max_attributes {
+			issues.push(PreflightIssue::TooManyAttributes { count, max: max_attributes });
+		}
+		for (i, attribute) in self.attributes.iter().enumerate() {
+			if !attribute.allow_multiple()
+				&& self.attributes[..i].iter().any(|a| a.type_byte() == attribute.type_byte())
+			{
+				issues.push(PreflightIssue::DuplicateAttribute(attribute.to_json()));
+			}
+		}
+
+		let size = self.size();
+		let max_size = NeoConstants::MAX_TRANSACTION_SIZE as usize;
+		if size > max_size {
+			issues.push(PreflightIssue::TooLarge { size, max: max_size });
+		}
+		issues
+	}
+
+	/// Returns the network fee for the size of the transaction and the verification of its
+	/// single- and multi-sig witnesses.
+	pub fn required_network_fee(&self, fee_per_byte: i64, exec_fee_factor: i64) -> i64 {
+		let verification_cost: i64 = self
+			.witnesses
+			.iter()
+			.map(|witness| match WitnessType::from(witness) {
+				WitnessType::SingleSig(_) => Self::PUSHDATA1_PRICE * 2 + Self::CHECK_SIG_PRICE,
+				WitnessType::MultiSig { threshold, public_keys } => {
+					let (m, n) = (threshold as i64, public_keys.len() as i64);
+					Self::PUSHDATA1_PRICE * (m + n)
+						+ Self::PUSHINT_PRICE * 2
+						+ Self::CHECK_SIG_PRICE * n
+				},
+				WitnessType::Contract | WitnessType::Custom => 0,
+			})
+			.sum();
+		self.size() as i64 * fee_per_byte + verification_cost * exec_fee_factor
+	}
+
+	fn preflight_error(error: ContractError) -> TransactionError {
+		match error {
+			ContractError::ProviderError(e) => TransactionError::ProviderError(e),
+			e => TransactionError::IllegalState(e.to_string()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		Account, AccountSigner, AccountTrait, MockProvider, NeoProtocol, NeoVersion,
+		PreflightIssue, Provider, StackItem, Transaction, TransactionAttribute, Witness,
+	};
+
+	/// Queues the chain state for a transaction with one signer, from the last request to the
+	/// first.
+	fn mocked(block_count: u32, blocked: bool, balance: i64) -> Provider<MockProvider> {
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocation(StackItem::Integer { value: balance }).unwrap();
+		mock.push_invocation(StackItem::Boolean { value: blocked }).unwrap();
+		mock.push_invocation(StackItem::Integer { value: 30 }).unwrap();
+		mock.push_invocation(StackItem::Integer { value: 1000 }).unwrap();
+		mock.push(NeoVersion {
+			tcp_port: None,
+			ws_port: None,
+			nonce: 0,
+			user_agent: "/Neo:3.6.0/".to_string(),
+			protocol: Some(NeoProtocol {
+				network: 860833102,
+				validators_count: Some(7),
+				ms_per_block: 15000,
+				max_valid_until_block_increment: 5760,
+				max_traceable_blocks: 2102400,
+				address_version: 53,
+				max_transactions_per_block: 512,
+				memory_pool_max_transactions: 50000,
+				initial_gas_distribution: 5200000000000000,
+			}),
+		})
+		.unwrap();
+		mock.push(block_count).unwrap();
+		provider
+	}
+
+	fn transaction(account: &Account) -> Transaction<MockProvider> {
+		let mut tx = Transaction::new();
+		tx.signers = vec![AccountSigner::called_by_entry(account).unwrap().into()];
+		tx.script = vec![0x40];
+		tx.valid_until_block = 1100;
+		tx.witnesses = vec![Witness::from_scripts(
+			vec![0; 66],
+			account.verification_script().as_ref().unwrap().script().clone(),
+		)];
+		tx.net_fee = tx.required_network_fee(1000, 30);
+		tx.sys_fee = 1_000_000;
+		tx
+	}
+
+	#[tokio::test]
+	async fn test_preflight() {
+		let account = Account::create().unwrap();
+		let tx = transaction(&account);
+
+		let provider = mocked(1000, false, 100_000_000);
+		let report = tx.preflight(&provider).await.unwrap();
+		assert!(report.is_ok(), "{:?}", report.issues);
+		assert_eq!(report.height, 999);
+		assert_eq!(report.required_network_fee, tx.net_fee);
+	}
+
+	#[tokio::test]
+	async fn test_preflight_reports_all_issues() {
+		let account = Account::create().unwrap();
+		let mut tx = transaction(&account);
+		tx.net_fee -= 1;
+		tx.attributes =
+			vec![TransactionAttribute::HighPriority, TransactionAttribute::HighPriority];
+
+		let provider = mocked(2000, true, 0);
+		let report = tx.preflight(&provider).await.unwrap();
+		let sender = account.get_script_hash();
+		assert!(report
+			.issues
+			.contains(&PreflightIssue::Expired { valid_until_block: 1100, height: 1999 }));
+		assert!(report.issues.contains(&PreflightIssue::BlockedSigner(sender)));
+		assert!(report
+			.issues
+			.iter()
+			.any(|issue| matches!(issue, PreflightIssue::DuplicateAttribute(_))));
+		assert!(report
+			.issues
+			.iter()
+			.any(|issue| matches!(issue, PreflightIssue::InsufficientNetworkFee { .. })));
+		assert!(report
+			.issues
+			.iter()
+			.any(|issue| matches!(issue, PreflightIssue::InsufficientBalance { .. })));
+	}
+
+	#[test]
+	fn test_check_structure() {
+		let account = Account::create().unwrap();
+		let mut tx = transaction(&account);
+		tx.signers.push(tx.signers[0].clone());
+		let issues = tx.check_structure();
+		assert!(issues.contains(&PreflightIssue::DuplicateSigner(account.get_script_hash())));
+		assert!(issues.contains(&PreflightIssue::WitnessCountMismatch { signers: 2, witnesses: 1 }));
+		assert!(!issues.iter().any(|issue| matches!(issue, PreflightIssue::TooLarge { .. })));
+	}
+}
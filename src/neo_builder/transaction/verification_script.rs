@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use neo::prelude::{
 	var_size, BuilderError, Bytes, Decoder, Encoder, InteropService, NeoSerializable, OpCode,
-	ScriptBuilder, Secp256r1PublicKey, Secp256r1Signature,
+	ScriptBuilder, ScriptHashExtension, Secp256r1PublicKey, Secp256r1Signature,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Getters, Setters, Serialize, Deserialize)]
@@ -71,81 +71,80 @@ impl VerificationScript {
 	///
 	/// Returns `true` if this script is from a multi-signature account.
 	/// Otherwise returns `false`.
-	#[doc(hidden)]
 	pub fn is_multi_sig(&self) -> bool {
-		if self.script.len() < 42 {
-			return false
-		}
+		self.multi_sig_layout().is_some()
+	}
 
-		let mut reader = Decoder::new(&self.script);
+	/// Parses a multi-sig script the same way the reference node does and returns the signing
+	/// threshold, the offset of the first public key push and the number of public keys.
+	fn multi_sig_layout(&self) -> Option<(usize, usize, usize)> {
+		let script = &self.script;
+		if script.len() < 42 {
+			return None
+		}
 
-		let n = match reader.by_ref().read_push_int() {
-			Ok(n) => n,
-			Err(_) => return false,
-		};
-		if !(1..=16).contains(&(n.to_i32().unwrap())) {
-			return false
+		let (threshold, mut i) = Self::read_small_int(script, 0)?;
+		if threshold < 1 || threshold > 1024 {
+			return None
 		}
 
-		let mut m: BigInt = BigInt::zero();
-		while reader.by_ref().read_u8() == OpCode::PushData1.opcode() {
-			let len = reader.by_ref().read_u8();
-			if len != 33 {
-				return false
+		let keys_offset = i;
+		let mut n = 0;
+		while script[i] == OpCode::PushData1.opcode() {
+			if script.len() <= i + 35 || script[i + 1] != 33 {
+				return None
 			}
-			reader.by_ref().read_encoded_ec_point();
-			m += 1;
-			reader.mark();
+			i += 35;
+			n += 1;
 		}
-
-		if !(m >= n && m <= BigInt::from(16)) {
-			return false
+		if n < threshold || n > 1024 {
+			return None
 		}
 
-		reader.reset();
-
-		if BigInt::from(reader.read_push_int().unwrap()) != m
-			|| reader.read_u8() != OpCode::Syscall.opcode()
-		{
-			return false
+		let (count, i) = Self::read_small_int(script, i)?;
+		if count != n || script.len() != i + 5 || script[i] != OpCode::Syscall.opcode() {
+			return None
 		}
-
-		let service_bytes = &reader.read_bytes(4).unwrap();
-		let hash = &InteropService::SystemCryptoCheckMultiSig.hash().from_hex().unwrap();
-		if service_bytes != hash {
-			return false
+		if script[i + 1..].to_hex() != InteropService::SystemCryptoCheckMultiSig.hash() {
+			return None
 		}
 
-		match reader.by_ref().read_var_int() {
-			Ok(v) =>
-				if BigInt::from(v) != m {
-					return false
-				},
-			Err(_) => return false,
-		}
+		Some((threshold, keys_offset, n))
+	}
 
-		if reader.by_ref().read_u8() != OpCode::Syscall as u8 {
-			return false
+	/// Reads a `PUSH1`..`PUSH16`, `PUSHINT8` or `PUSHINT16` at `offset` and returns the value
+	/// and the offset that follows it.
+	fn read_small_int(script: &[u8], offset: usize) -> Option<(usize, usize)> {
+		let op_code = *script.get(offset)?;
+		if (OpCode::Push1.opcode()..=OpCode::Push16.opcode()).contains(&op_code) {
+			return Some(((op_code - OpCode::Push0.opcode()) as usize, offset + 1))
 		}
-
-		true
+		if op_code == OpCode::PushInt8.opcode() {
+			return Some((*script.get(offset + 1)? as usize, offset + 2))
+		}
+		if op_code == OpCode::PushInt16.opcode() {
+			let bytes = script.get(offset + 1..offset + 3)?;
+			return Some((u16::from_le_bytes([bytes[0], bytes[1]]) as usize, offset + 3))
+		}
+		None
 	}
 
 	// other methods
 	pub fn hash(&self) -> H160 {
-		H160::from_slice(&self.script)
+		H160::from_script(&self.script)
 	}
 
 	pub fn get_signatures(&self) -> Vec<Secp256r1Signature> {
 		let mut reader = Decoder::new(&self.script);
 		let mut signatures = vec![];
 
-		while reader.by_ref().read_u8() == OpCode::PushData1 as u8 {
-			let len = reader.by_ref().read_u8();
-			let sig =
-				Secp256r1Signature::from_bytes(&reader.by_ref().read_bytes(len as usize).unwrap())
-					.unwrap();
-			signatures.push(sig);
+		while reader.read_u8() == Ok(OpCode::PushData1 as u8) {
+			let Ok(len) = reader.read_u8() else { break };
+			let Ok(bytes) = reader.read_bytes(len as usize) else { break };
+			match Secp256r1Signature::from_bytes(&bytes) {
+				Ok(sig) => signatures.push(sig),
+				Err(_) => break,
+			}
 		}
 
 		signatures
@@ -154,29 +153,25 @@ impl VerificationScript {
 	pub fn get_public_keys(&self) -> Result<Vec<Secp256r1PublicKey>, BuilderError> {
 		if self.is_single_sig() {
 			let mut reader = Decoder::new(&self.script);
-			reader.by_ref().read_u8(); // skip pushdata1
-			reader.by_ref().read_u8(); // skip length
-
-			let mut point = [0; 33];
-			point.copy_from_slice(&reader.by_ref().read_bytes(33).unwrap());
+			reader.read_u8()?; // skip pushdata1
+			reader.read_u8()?; // skip length
 
-			let key = Secp256r1PublicKey::from_bytes(&point).unwrap();
+			let point = reader.read_bytes(33)?;
+			let key = Secp256r1PublicKey::from_bytes(&point).map_err(|_| {
+				BuilderError::InvalidScript("Invalid public key in script".to_string())
+			})?;
 			return Ok(vec![key])
 		}
 
-		if self.is_multi_sig() {
-			let mut reader = Decoder::new(&self.script);
-			reader.by_ref().read_var_int().unwrap(); // skip threshold
-
-			let mut keys = vec![];
-			while reader.by_ref().read_u8() == OpCode::PushData1 as u8 {
-				reader.by_ref().read_u8(); // skip length
-				let mut point = [0; 33];
-				point.copy_from_slice(&reader.by_ref().read_bytes(33).unwrap());
-				keys.push(Secp256r1PublicKey::from_bytes(&point).unwrap());
-			}
-
-			return Ok(keys)
+		if let Some((_, keys_offset, n)) = self.multi_sig_layout() {
+			return (0..n)
+				.map(|i| {
+					let start = keys_offset + i * 35 + 2;
+					Secp256r1PublicKey::from_bytes(&self.script[start..start + 33]).map_err(|_| {
+						BuilderError::InvalidScript("Invalid public key in script".to_string())
+					})
+				})
+				.collect()
 		}
 
 		Err(BuilderError::InvalidScript("Invalid verification script".to_string()))
@@ -185,9 +180,8 @@ impl VerificationScript {
 	pub fn get_signing_threshold(&self) -> Result<usize, BuilderError> {
 		if self.is_single_sig() {
 			Ok(1)
-		} else if self.is_multi_sig() {
-			let reader = &mut Decoder::new(&self.script);
-			Ok(reader.by_ref().read_var_int()? as usize)
+		} else if let Some((threshold, ..)) = self.multi_sig_layout() {
+			Ok(threshold)
 		} else {
 			Err(BuilderError::InvalidScript("Invalid verification script".to_string()))
 		}
@@ -309,13 +303,22 @@ mod tests {
 
 	#[test]
 	fn test_get_signing_threshold() {
-		// let key = hex!("...").to_vec();
-		//
-		// let script = VerificationScript::from(key);
-		// assert_eq!(script.get_signing_threshold(), 2);
-		//
-		// let script = VerificationScript::from(long_script);
-		// assert_eq!(script.get_signing_threshold(), 127);
+		let mut keys = [
+			"02028a99826edc0c97d18e22b6932373d908d323aa7f92656a77ec26e8861699ef",
+			"031d8e1630ce640966967bc6d95223d21f44304133003140c3b52004dc981349c9",
+			"03f0f9b358dfed564e74ffe242713f8bc866414226649f59859b140a130818898b",
+		]
+		.map(|key| Secp256r1PublicKey::from_bytes(&hex::decode(key).unwrap()).unwrap());
+
+		let script = VerificationScript::from_multi_sig(&mut keys, 2);
+		assert!(script.is_multi_sig());
+		assert_eq!(script.get_signing_threshold().unwrap(), 2);
+		assert_eq!(script.get_public_keys().unwrap(), keys.to_vec());
+		assert_eq!(script.get_nr_of_accounts().unwrap(), 3);
+
+		let script = VerificationScript::from_public_key(&keys[0]);
+		assert!(!script.is_multi_sig());
+		assert_eq!(script.get_signing_threshold().unwrap(), 1);
 	}
 
 	#[test]
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use neo::prelude::{
-	BuilderError, Bytes, ContractParameter, Decoder, Encoder, InvocationScript, KeyPair,
-	NeoSerializable, ScriptBuilder, Secp256r1PublicKey, Secp256r1Signature, VerificationScript,
+	BuilderError, Bytes, ContractParameter, CryptoError, Decoder, Encoder, InvocationScript,
+	KeyPair, NeoSerializable, ScriptBuilder, Secp256r1PublicKey, Secp256r1Signature,
+	VerificationScript,
 };
 
 #[derive(Hash, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -12,6 +13,9 @@ pub struct Witness {
 }
 
 impl Witness {
+	const MAX_INVOCATION_SCRIPT_SIZE: usize = 1024;
+	const MAX_VERIFICATION_SCRIPT_SIZE: usize = 1024;
+
 	pub fn new() -> Self {
 		Self { invocation: InvocationScript::new(), verification: VerificationScript::new() }
 	}
@@ -33,7 +37,7 @@ impl Witness {
 	pub fn create(message_to_sign: Bytes, key_pair: &KeyPair) -> Result<Self, BuilderError> {
 		let invocation_script =
 			InvocationScript::from_message_and_key_pair(message_to_sign, key_pair).unwrap();
-		let verification_script = VerificationScript::from(key_pair.public_key().get_encoded(true));
+		let verification_script = VerificationScript::from_public_key(&key_pair.public_key());
 		Ok(Self { invocation: invocation_script, verification: verification_script })
 	}
 
@@ -79,6 +83,32 @@ impl Witness {
 			verification: VerificationScript::new(),
 		})
 	}
+
+	/// Checks that the invocation script holds valid signatures of `message` for the single-sig
+	/// or multi-sig verification script of this witness.
+	///
+	/// Like `System.Crypto.CheckMultisig`, the signatures of a multi-sig witness have to be in the
+	/// order of the public keys in the verification script.
+	pub fn verify(&self, message: &[u8]) -> Result<(), BuilderError> {
+		let threshold = self.verification.get_signing_threshold()?;
+		let public_keys = self.verification.get_public_keys()?;
+		let signatures = self.invocation.get_signatures();
+		if signatures.len() != threshold {
+			return Err(BuilderError::InvalidScript(format!(
+				"Expected {} signatures but the invocation script has {}",
+				threshold,
+				signatures.len()
+			)))
+		}
+
+		let mut keys = public_keys.iter();
+		for signature in &signatures {
+			if !keys.by_ref().any(|key| key.verify(message, signature).is_ok()) {
+				return Err(CryptoError::SignatureVerificationError.into())
+			}
+		}
+		Ok(())
+	}
 }
 
 impl NeoSerializable for Witness {
@@ -94,9 +124,9 @@ impl NeoSerializable for Witness {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let invocation = InvocationScript::decode(reader)?;
-		let verification = VerificationScript::decode(reader)?;
-		Ok(Self { invocation, verification })
+		let invocation = reader.read_var_bytes_max(Self::MAX_INVOCATION_SCRIPT_SIZE)?;
+		let verification = reader.read_var_bytes_max(Self::MAX_VERIFICATION_SCRIPT_SIZE)?;
+		Ok(Self::from_scripts(invocation, verification))
 	}
 	fn to_array(&self) -> Vec<u8> {
 		let mut writer = Encoder::new();
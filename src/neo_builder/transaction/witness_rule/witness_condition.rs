@@ -348,24 +348,41 @@ impl NeoSerializable for WitnessCondition {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let byte = reader.read_u8();
+		Self::decode_with_depth(reader, Self::MAX_NESTING_DEPTH)
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+impl WitnessCondition {
+	/// Decodes a condition whose `Not`, `And` and `Or` expressions are nested at most
+	/// `max_depth` levels deep, like the reference implementation.
+	fn decode_with_depth(reader: &mut Decoder, max_depth: usize) -> Result<Self, TransactionError> {
+		let byte = reader.read_u8()?;
+		if matches!(byte, Self::NOT_BYTE | Self::AND_BYTE | Self::OR_BYTE) && max_depth == 0 {
+			return Err(TransactionError::InvalidWitnessCondition)
+		}
 		match byte {
 			WitnessCondition::BOOLEAN_BYTE => {
-				let b = reader.read_bool();
+				let b = reader.read_bool()?;
 				Ok(WitnessCondition::Boolean(b))
 			},
 			WitnessCondition::NOT_BYTE => {
-				let exp = WitnessCondition::decode(reader)?;
+				let exp = WitnessCondition::decode_with_depth(reader, max_depth - 1)?;
 				Ok(WitnessCondition::Not(Box::from(exp)))
 			},
 			WitnessCondition::OR_BYTE | WitnessCondition::AND_BYTE => {
-				let len = reader.read_var_int()? as usize;
-				if len > Self::MAX_SUBITEMS {
-					return Err(TransactionError::InvalidWitnessCondition)
-				}
+				let len = reader
+					.read_var_int_max(Self::MAX_SUBITEMS as u64)
+					.map_err(|_| TransactionError::InvalidWitnessCondition)?
+					as usize;
 				let mut expressions = Vec::with_capacity(len);
 				for _ in 0..len {
-					expressions.push(WitnessCondition::decode(reader)?);
+					expressions.push(WitnessCondition::decode_with_depth(reader, max_depth - 1)?);
 				}
 				if byte == Self::OR_BYTE {
 					Ok(WitnessCondition::Or(expressions))
@@ -393,10 +410,4 @@ impl NeoSerializable for WitnessCondition {
 			_ => Err(TransactionError::InvalidTransaction),
 		}
 	}
-
-	fn to_array(&self) -> Vec<u8> {
-		let mut writer = Encoder::new();
-		self.encode(&mut writer);
-		writer.to_bytes()
-	}
 }
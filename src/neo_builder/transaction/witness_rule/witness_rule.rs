@@ -29,9 +29,10 @@ impl NeoSerializable for WitnessRule {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let action = reader.read_u8();
+		let action = WitnessAction::try_from(reader.read_u8()?)
+			.map_err(|_| TransactionError::InvalidWitnessCondition)?;
 		let condition = WitnessCondition::decode(reader)?;
-		Ok(Self { action: WitnessAction::try_from(action).unwrap(), condition })
+		Ok(Self { action, condition })
 	}
 	fn to_array(&self) -> Vec<u8> {
 		let mut writer = Encoder::new();
@@ -110,6 +111,30 @@ mod tests {
 		assert_eq!(bytes, writer.to_bytes());
 	}
 
+	#[test]
+	fn test_decode_nesting_depth() {
+		let bytes = hex::decode("01010000").unwrap();
+		assert!(WitnessCondition::from_bytes(&bytes).is_ok());
+
+		let bytes = hex::decode("0101010000").unwrap();
+		assert_eq!(
+			WitnessCondition::from_bytes(&bytes),
+			Err(TransactionError::InvalidWitnessCondition)
+		);
+	}
+
+	#[test]
+	fn test_decode_too_many_subitems() {
+		let mut bytes = vec![0x02, 17];
+		for _ in 0..17 {
+			bytes.extend_from_slice(&[0x00, 0x01]);
+		}
+		assert_eq!(
+			WitnessCondition::from_bytes(&bytes),
+			Err(TransactionError::InvalidWitnessCondition)
+		);
+	}
+
 	#[test]
 	fn test_boolean_nil_values() {
 		let json = r#"{
@@ -5,17 +5,14 @@
 /// ```
 ///
 /// use neo_rs::prelude::Decoder;
-/// let data = [0x01, 0x02, 0x03, 0x04];
+/// let data = [0x01, 0x02, 0x03, 0x04, 0x01, 0x02, 0x03, 0x04];
 /// let mut decoder = Decoder::new(&data);
 ///
-/// assert_eq!(decoder.read_bool(), true);
-/// assert_eq!(decoder.read_u8(), 2);
-/// assert_eq!(decoder.read_u16(), 0x0403);
-/// assert_eq!(decoder.read_i16(), 0x0403);
-/// assert_eq!(decoder.read_u32(), 0x04030201);
-/// assert_eq!(decoder.read_i32(), 0x04030201);
-/// assert_eq!(decoder.read_u64(), 0x0807060504030201);
-/// assert_eq!(decoder.read_i64(), 0x0807060504030201);
+/// assert_eq!(decoder.read_bool().unwrap(), true);
+/// assert_eq!(decoder.read_u8().unwrap(), 2);
+/// assert_eq!(decoder.read_u16().unwrap(), 0x0403);
+/// assert_eq!(decoder.read_u32().unwrap(), 0x04030201);
+/// assert!(decoder.read_u8().is_err());
 /// ```
 use getset::{Getters, Setters};
 use num_bigint::{BigInt, Sign};
@@ -49,72 +46,83 @@ impl<'a> Iterator for Decoder<'a> {
 }
 
 impl<'a> Decoder<'a> {
+	/// The default maximum length of variable-length byte arrays and strings.
+	pub const MAX_VAR_BYTES: usize = 0x1000000;
+	/// The default maximum number of items of a serializable list.
+	pub const MAX_LIST_ITEMS: usize = 0x1000000;
+
 	/// Creates a new binary decoder that reads from the given byte slice.
 	pub fn new(data: &'a [u8]) -> Self {
 		Self { data, pointer: 0, marker: 0 }
 	}
 
-	/// Reads a boolean value from the byte slice.
-	pub fn read_bool(&mut self) -> bool {
-		let val = self.data[self.pointer] == 1;
-		self.pointer += 1;
-		val
+	/// Reads a boolean value from the byte slice. Only `0` and `1` are valid booleans.
+	pub fn read_bool(&mut self) -> Result<bool, CodecError> {
+		match self.read_u8()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			b => Err(CodecError::InvalidEncoding(format!("Invalid boolean value {}", b))),
+		}
 	}
 
 	/// Reads an unsigned 8-bit integer from the byte slice.
-	pub fn read_u8(&mut self) -> u8 {
-		let val = self.data[self.pointer];
+	pub fn read_u8(&mut self) -> Result<u8, CodecError> {
+		let val = *self
+			.data
+			.get(self.pointer)
+			.ok_or(CodecError::IndexOutOfBounds("Read beyond end of buffer".to_string()))?;
 		self.pointer += 1;
-		val
+		Ok(val)
 	}
 
 	/// Reads an unsigned 16-bit integer from the byte slice.
-	pub fn read_u16(&mut self) -> u16 {
-		let bytes = self.read_bytes(2).unwrap();
-		u16::from_ne_bytes(bytes.try_into().unwrap())
+	pub fn read_u16(&mut self) -> Result<u16, CodecError> {
+		Ok(u16::from_le_bytes(self.read_array()?))
 	}
 
 	/// Reads a signed 16-bit integer from the byte slice.
-	pub fn read_i16(&mut self) -> i16 {
-		let bytes = self.read_bytes(2).unwrap();
-		i16::from_ne_bytes(bytes.try_into().unwrap())
+	pub fn read_i16(&mut self) -> Result<i16, CodecError> {
+		Ok(i16::from_le_bytes(self.read_array()?))
 	}
 
 	/// Reads an unsigned 32-bit integer from the byte slice.
-	pub fn read_u32(&mut self) -> u32 {
-		let bytes = self.read_bytes(4).unwrap();
-		u32::from_ne_bytes(bytes.try_into().unwrap())
+	pub fn read_u32(&mut self) -> Result<u32, CodecError> {
+		Ok(u32::from_le_bytes(self.read_array()?))
 	}
 
 	/// Reads a signed 32-bit integer from the byte slice.
-	pub fn read_i32(&mut self) -> i32 {
-		let bytes = self.read_bytes(4).unwrap();
-		i32::from_ne_bytes(bytes.try_into().unwrap())
+	pub fn read_i32(&mut self) -> Result<i32, CodecError> {
+		Ok(i32::from_le_bytes(self.read_array()?))
 	}
 
 	/// Reads an unsigned 64-bit integer from the byte slice.
-	pub fn read_u64(&mut self) -> u64 {
-		let bytes = self.read_bytes(8).unwrap();
-		u64::from_ne_bytes(bytes.try_into().unwrap())
+	pub fn read_u64(&mut self) -> Result<u64, CodecError> {
+		Ok(u64::from_le_bytes(self.read_array()?))
 	}
-	pub fn read_i64(&mut self) -> i64 {
-		let bytes = self.read_bytes(8).unwrap();
-		i64::from_ne_bytes(bytes.try_into().unwrap())
+
+	/// Reads a signed 64-bit integer from the byte slice.
+	pub fn read_i64(&mut self) -> Result<i64, CodecError> {
+		Ok(i64::from_le_bytes(self.read_array()?))
+	}
+
+	fn read_array<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+		let bytes = self.read_bytes(N)?;
+		Ok(bytes.try_into().expect("read_bytes returns exactly N bytes"))
 	}
 
 	pub fn read_bigint(&mut self) -> Result<BigInt, CodecError> {
-		let byte = self.read_u8();
+		let byte = self.read_u8()?;
 
 		let negative = byte & 0x80 != 0;
 		let len = match byte {
 			0..=0x4b => 1,
-			0x4c => self.read_u8() as usize,
-			0x4d => self.read_u16() as usize,
-			0x4e => self.read_u32() as usize,
+			0x4c => self.read_u8()? as usize,
+			0x4d => self.read_u16()? as usize,
+			0x4e => self.read_u32()? as usize,
 			_ => return Err(CodecError::InvalidFormat),
 		};
 
-		let bytes = self.read_bytes(len).unwrap();
+		let bytes = self.read_bytes(len)?;
 		if negative {
 			// Flip sign bit
 			if let Some(byte) = bytes.to_owned().get_mut(len - 1) {
@@ -129,17 +137,17 @@ impl<'a> Decoder<'a> {
 	}
 
 	/// Reads an encoded EC point from the byte slice.
-	pub fn read_encoded_ec_point(&mut self) -> Result<Vec<u8>, &'static str> {
-		let byte = self.read_u8();
+	pub fn read_encoded_ec_point(&mut self) -> Result<Vec<u8>, CodecError> {
+		let byte = self.read_u8()?;
 		match byte {
-			0x02 | 0x03 => Ok(self.read_bytes(32).unwrap()),
-			_ => Err("Invalid encoded EC point"),
+			0x02 | 0x03 => self.read_bytes(32),
+			_ => Err(CodecError::InvalidEncoding("Invalid encoded EC point".to_string())),
 		}
 	}
 
 	/// Reads a byte slice of the given length from the byte slice.
 	pub fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, CodecError> {
-		if self.pointer + length > self.data.len() {
+		if length > self.available() {
 			return Err(CodecError::IndexOutOfBounds("Read beyond end of buffer".to_string()))
 		}
 		let result = self.data[self.pointer..self.pointer + length].to_vec();
@@ -149,23 +157,66 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a variable-length byte slice from the byte slice.
 	pub fn read_var_bytes(&mut self) -> Result<Vec<u8>, CodecError> {
-		let len = self.read_var_int().unwrap() as usize;
+		self.read_var_bytes_max(Self::MAX_VAR_BYTES)
+	}
+
+	/// Reads a variable-length byte slice of at most `max` bytes from the byte slice.
+	pub fn read_var_bytes_max(&mut self, max: usize) -> Result<Vec<u8>, CodecError> {
+		let len = self.read_var_int_max(max as u64)? as usize;
 		self.read_bytes(len)
 	}
 
 	/// Reads a variable-length integer from the byte slice.
-	pub fn read_var_int(&mut self) -> Result<i64, CodecError> {
-		let first = self.read_u8();
-		match first {
-			0xfd => Ok(self.read_i16() as i64),
-			0xfe => Ok(self.read_i32() as i64),
-			0xff => Ok(self.read_i64() as i64),
-			_ => Ok(first as i64),
+	pub fn read_var_int(&mut self) -> Result<u64, CodecError> {
+		self.read_var_int_max(u64::MAX)
+	}
+
+	/// Reads a variable-length integer of at most `max` from the byte slice.
+	///
+	/// Like the reference implementation, values that are not encoded in their shortest form are
+	/// rejected.
+	pub fn read_var_int_max(&mut self, max: u64) -> Result<u64, CodecError> {
+		let first = self.read_u8()?;
+		let value = match first {
+			0xfd => {
+				let v = self.read_u16()? as u64;
+				if v < 0xfd {
+					return Err(CodecError::InvalidEncoding("Non-canonical var int".to_string()))
+				}
+				v
+			},
+			0xfe => {
+				let v = self.read_u32()? as u64;
+				if v <= u16::MAX as u64 {
+					return Err(CodecError::InvalidEncoding("Non-canonical var int".to_string()))
+				}
+				v
+			},
+			0xff => {
+				let v = self.read_u64()?;
+				if v <= u32::MAX as u64 {
+					return Err(CodecError::InvalidEncoding("Non-canonical var int".to_string()))
+				}
+				v
+			},
+			_ => first as u64,
+		};
+		if value > max {
+			return Err(CodecError::InvalidEncoding(format!(
+				"Var int {} exceeds the maximum of {}",
+				value, max
+			)))
 		}
+		Ok(value)
 	}
 
 	pub fn read_var_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_var_bytes().unwrap();
+		self.read_var_string_max(Self::MAX_VAR_BYTES)
+	}
+
+	/// Reads a variable-length UTF-8 string of at most `max` bytes from the byte slice.
+	pub fn read_var_string_max(&mut self, max: usize) -> Result<String, CodecError> {
+		let bytes = self.read_var_bytes_max(max)?;
 
 		let string = match String::from_utf8(bytes.to_vec()) {
 			Ok(s) => s,
@@ -183,11 +234,11 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push byte slice from the byte slice.
 	pub fn read_push_bytes(&mut self) -> Result<Vec<u8>, CodecError> {
-		let opcode = self.read_u8();
+		let opcode = self.read_u8()?;
 		let len = match OpCode::try_from(opcode)? {
-			OpCode::PushData1 => self.read_u8() as usize,
-			OpCode::PushData2 => self.read_i16() as usize,
-			OpCode::PushData4 => self.read_i32() as usize,
+			OpCode::PushData1 => self.read_u8()? as usize,
+			OpCode::PushData2 => self.read_u16()? as usize,
+			OpCode::PushData4 => self.read_u32()? as usize,
 			_ => return Err(CodecError::InvalidOpCode),
 		};
 
@@ -196,7 +247,7 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push integer from the byte slice.
 	pub fn read_push_int(&mut self) -> Result<BigInt, CodecError> {
-		let byte = self.read_u8();
+		let byte = self.read_u8()?;
 
 		if (OpCode::PushM1 as u8..=OpCode::Push16 as u8).contains(&byte) {
 			return Ok(BigInt::from(byte as i8 - OpCode::Push0 as i8))
@@ -219,24 +270,31 @@ impl<'a> Decoder<'a> {
 
 	/// Reads a push string from the byte slice.
 	pub fn read_push_string(&mut self) -> Result<String, CodecError> {
-		let bytes = self.read_push_bytes().unwrap();
+		let bytes = self.read_push_bytes()?;
 		String::from_utf8(Vec::from(bytes))
 			.map_err(|_| CodecError::InvalidEncoding("Invalid UTF-8".to_string()))
 	}
 
 	/// Reads a deserializable value from the byte slice.
 	pub fn read_serializable<T: NeoSerializable>(&mut self) -> Result<T, CodecError> {
-		T::decode(self).map_err(|_e| CodecError::InvalidFormat)
+		T::decode(self).map_err(|e| CodecError::InvalidEncoding(format!("{:?}", e)))
 	}
 
 	/// Reads a list of deserializable values from the byte slice.
 	pub fn read_serializable_list<T: NeoSerializable>(&mut self) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut list = Vec::with_capacity(len as usize);
+		self.read_serializable_list_max(Self::MAX_LIST_ITEMS)
+	}
+
+	/// Reads a list of at most `max` deserializable values from the byte slice.
+	pub fn read_serializable_list_max<T: NeoSerializable>(
+		&mut self,
+		max: usize,
+	) -> Result<Vec<T>, CodecError> {
+		let len = self.read_var_int_max(max as u64)? as usize;
+		// Every item takes at least one byte, which bounds the allocation by the input size.
+		let mut list = Vec::with_capacity(len.min(self.available()));
 		for _ in 0..len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
+			list.push(self.read_serializable()?);
 		}
 		Ok(list)
 	}
@@ -244,15 +302,14 @@ impl<'a> Decoder<'a> {
 	pub fn read_serializable_list_var_bytes<T: NeoSerializable>(
 		&mut self,
 	) -> Result<Vec<T>, CodecError> {
-		let len = self.read_var_int().unwrap();
-		let mut bytes_read = 0;
+		let len = self.read_var_int_max(self.available() as u64)? as usize;
 		let offset = self.pointer;
-		let mut list = Vec::with_capacity(len as usize);
-		while bytes_read < len {
-			T::decode(self)
-				.and_then(|item| Ok(list.push(item)))
-				.expect("TODO: panic message");
-			bytes_read = (self.pointer - offset) as i64;
+		let mut list = Vec::new();
+		while self.pointer - offset < len {
+			list.push(self.read_serializable()?);
+		}
+		if self.pointer - offset != len {
+			return Err(CodecError::InvalidFormat)
 		}
 		Ok(list)
 	}
@@ -290,7 +347,7 @@ impl<'a> Decoder<'a> {
 mod tests {
 	use num_bigint::BigInt;
 
-	use neo::prelude::Decoder;
+	use neo::prelude::{CodecError, Decoder};
 
 	#[test]
 	fn test_read_push_data_bytes() {
@@ -349,30 +406,80 @@ mod tests {
 	#[test]
 	fn test_read_u32() {
 		let max = [0xffu8; 4];
-		assert_eq!(Decoder::new(&max).read_u32(), 4_294_967_295);
+		assert_eq!(Decoder::new(&max).read_u32().unwrap(), 4_294_967_295);
 
 		let one = hex::decode("01000000").unwrap();
-		assert_eq!(Decoder::new(&one).read_u32(), 1);
+		assert_eq!(Decoder::new(&one).read_u32().unwrap(), 1);
 
 		let zero = [0u8; 4];
-		assert_eq!(Decoder::new(&zero).read_u32(), 0);
+		assert_eq!(Decoder::new(&zero).read_u32().unwrap(), 0);
 
 		let custom = hex::decode("8cae0000ff").unwrap();
-		assert_eq!(Decoder::new(&custom).read_u32(), 44_684);
+		assert_eq!(Decoder::new(&custom).read_u32().unwrap(), 44_684);
 	}
 
 	#[test]
 	fn test_read_i64() {
 		let min = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80];
-		assert_eq!(Decoder::new(&min).read_i64(), i64::MIN);
+		assert_eq!(Decoder::new(&min).read_i64().unwrap(), i64::MIN);
 
 		let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
-		assert_eq!(Decoder::new(&max).read_i64(), i64::MAX);
+		assert_eq!(Decoder::new(&max).read_i64().unwrap(), i64::MAX);
 
 		let zero = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
-		assert_eq!(Decoder::new(&zero).read_i64(), 0);
+		assert_eq!(Decoder::new(&zero).read_i64().unwrap(), 0);
 
 		let custom = [0x11, 0x33, 0x22, 0x8c, 0xae, 0x00, 0x00, 0x00, 0xff];
-		assert_eq!(Decoder::new(&custom).read_i64(), 749_675_361_041);
+		assert_eq!(Decoder::new(&custom).read_i64().unwrap(), 749_675_361_041);
+	}
+
+	#[test]
+	fn test_fail_reading_beyond_end_of_buffer() {
+		assert!(matches!(Decoder::new(&[]).read_u8(), Err(CodecError::IndexOutOfBounds(_))));
+		assert!(Decoder::new(&[0x01, 0x02, 0x03]).read_u32().is_err());
+		assert!(Decoder::new(&[0x01; 7]).read_i64().is_err());
+		assert!(Decoder::new(&hex::decode("0501020304").unwrap()).read_var_bytes().is_err());
+		assert!(Decoder::new(&hex::decode("fd").unwrap()).read_var_int().is_err());
+	}
+
+	#[test]
+	fn test_read_bool() {
+		assert_eq!(Decoder::new(&[0x00]).read_bool().unwrap(), false);
+		assert_eq!(Decoder::new(&[0x01]).read_bool().unwrap(), true);
+		assert!(Decoder::new(&[0x02]).read_bool().is_err());
+	}
+
+	#[test]
+	fn test_read_var_int() {
+		assert_eq!(Decoder::new(&[0xfc]).read_var_int().unwrap(), 0xfc);
+		assert_eq!(Decoder::new(&[0xfd, 0xfd, 0x00]).read_var_int().unwrap(), 0xfd);
+		assert_eq!(Decoder::new(&[0xfd, 0xff, 0xff]).read_var_int().unwrap(), 0xffff);
+		assert_eq!(Decoder::new(&[0xfe, 0x00, 0x00, 0x01, 0x00]).read_var_int().unwrap(), 65_536);
+		assert_eq!(
+			Decoder::new(&[0xff, 0, 0, 0, 0, 1, 0, 0, 0]).read_var_int().unwrap(),
+			4_294_967_296
+		);
+
+		// Values must use the shortest encoding.
+		assert!(Decoder::new(&[0xfd, 0x01, 0x00]).read_var_int().is_err());
+		assert!(Decoder::new(&[0xfe, 0xff, 0xff, 0x00, 0x00]).read_var_int().is_err());
+	}
+
+	#[test]
+	fn test_read_var_int_max() {
+		assert_eq!(Decoder::new(&[0x10]).read_var_int_max(16).unwrap(), 16);
+		assert!(Decoder::new(&[0x11]).read_var_int_max(16).is_err());
+		assert!(Decoder::new(&hex::decode("03010203").unwrap()).read_var_bytes_max(2).is_err());
+	}
+
+	#[test]
+	fn test_read_serializable_list_max() {
+		let data = hex::decode("03010203").unwrap();
+		assert_eq!(Decoder::new(&data).read_serializable_list_max::<u8>(3).unwrap(), vec![1, 2, 3]);
+		assert!(Decoder::new(&data).read_serializable_list_max::<u8>(2).is_err());
+
+		// A huge length prefix must fail on the missing items instead of allocating.
+		let data = hex::decode("feffffff7f01").unwrap();
+		assert!(Decoder::new(&data).read_serializable_list::<u8>().is_err());
 	}
 }
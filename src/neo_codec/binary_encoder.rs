@@ -63,11 +63,15 @@ impl Encoder {
 		self.data.extend_from_slice(&v.to_le_bytes());
 	}
 
+	pub fn write_u64(&mut self, v: u64) {
+		self.data.extend_from_slice(&v.to_le_bytes());
+	}
+
 	pub fn write_bytes(&mut self, bytes: &[u8]) {
 		self.data.extend_from_slice(bytes);
 	}
 
-	fn write_var_int(&mut self, v: i64) {
+	pub fn write_var_int(&mut self, v: i64) {
 		if v < 0 {
 			panic!("Negative value not allowed")
 		}
@@ -76,7 +76,7 @@ impl NeoSerializable for u8 {
 	where
 		Self: Sized,
 	{
-		Ok(reader.read_u8())
+		reader.read_u8()
 	}
 
 	fn to_array(&self) -> Vec<u8> {
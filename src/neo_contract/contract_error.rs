@@ -31,6 +31,12 @@ pub enum ContractError {
 	/// Error indicating an invalid argument error
 	#[error("Invalid argument error: {0}")]
 	InvalidArgError(String),
+	/// Error indicating a block index that is negative or beyond the current block count
+	#[error("Invalid block index: {0}")]
+	InvalidBlockIndex(String),
+	/// Error indicating a malformed or unsupported NEP-9 URI
+	#[error("Invalid NEP-9 URI: {0}")]
+	InvalidNeoUri(String),
 	/// Error indicating a provider error, transparently wrapped
 	#[error(transparent)]
 	ProviderError(#[from] ProviderError),
@@ -48,10 +48,6 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for FungibleTokenContract<'a, P> {
 	fn set_symbol(&mut self, symbol: String) {
 		self.symbol = Option::from(symbol);
 	}
-
-	async fn resolve_nns_text_record(&self, _name: &NNSName) -> Result<H160, ContractError> {
-		todo!()
-	}
 }
 
 #[async_trait]
@@ -9,9 +9,11 @@ pub use name_service::*;
 pub use neo_token::*;
 pub use neo_uri::*;
 pub use nft_contract::*;
+pub use notary_contract::*;
 pub use policy_contract::*;
 pub use role_management::*;
 pub use traits::*;
+pub use unclaimed_gas::*;
 
 mod contract_error;
 mod contract_management;
@@ -22,6 +24,8 @@ mod name_service;
 mod neo_token;
 mod neo_uri;
 mod nft_contract;
+mod notary_contract;
 mod policy_contract;
 mod role_management;
 mod traits;
+mod unclaimed_gas;
@@ -3,56 +3,68 @@ use async_trait::async_trait;
 use futures::FutureExt;
 use neo::prelude::{
 	deserialize_script_hash, deserialize_script_hash_option, serialize_script_hash,
-	serialize_script_hash_option, AddressOrScriptHash, ContractError, ContractParameter,
-	JsonRpcClient, Middleware, NNSName, NeoIterator, NonFungibleTokenTrait, Provider, ScriptHash,
+	serialize_script_hash_option, Account, AccountSigner, AccountTrait, ContractError,
+	ContractParameter, ContractParameterType, InvocationResult, JsonRpcClient, Middleware, NNSName,
+	NNSRoot, NeoIterator, NonFungibleTokenTrait, Provider, RecordState, RecordType, ScriptHash,
 	SmartContractTrait, StackItem, TokenTrait, TransactionBuilder,
 };
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 use std::{string::ToString, sync::Arc};
 
-#[repr(u8)]
-enum RecordType {
-	None = 0,
-	Txt = 1,
-	A = 2,
-	Aaaa = 3,
-	Cname = 4,
-	Srv = 5,
-	Url = 6,
-	Oauth = 7,
-	Ipfs = 8,
-	Email = 9,
-	Dnssec = 10,
-	Tlsa = 11,
-	Smimea = 12,
-	Hippo = 13,
-	Http = 14,
-	Sshfp = 15,
-	Onion = 16,
-	Xmpp = 17,
-	Magnet = 18,
-	Tor = 19,
-	I2p = 20,
-	Git = 21,
-	Keybase = 22,
-	Briar = 23,
-	Zcash = 24,
-	Mini = 25,
-}
-
 // NameState struct
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NameState {
 	pub name: String,
-	pub expiration: u32,
+	/// Milliseconds since the Unix epoch.
+	pub expiration: u64,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	#[serde(deserialize_with = "deserialize_script_hash_option")]
 	#[serde(serialize_with = "serialize_script_hash_option")]
 	pub admin: Option<ScriptHash>,
 }
 
+impl NameState {
+	const NAME_PROPERTY: &'static [u8] = b"name";
+	const EXPIRATION_PROPERTY: &'static [u8] = b"expiration";
+	const ADMIN_PROPERTY: &'static [u8] = b"admin";
+
+	/// Parses the map returned by the `properties` method of the NNS contract.
+	pub fn from_stack_item(item: &StackItem) -> Result<Self, ContractError> {
+		let map = item.as_map().ok_or_else(|| {
+			ContractError::UnexpectedReturnType(format!("Expected a map, got {}", item.to_string()))
+		})?;
+
+		let (mut name, mut expiration, mut admin) = (None, None, None);
+		for (key, value) in map {
+			match key.as_bytes().as_deref() {
+				Some(Self::NAME_PROPERTY) =>
+					name = value.as_bytes().and_then(|bytes| String::from_utf8(bytes).ok()),
+				Some(Self::EXPIRATION_PROPERTY) => expiration = value.as_int().map(|ms| ms as u64),
+				Some(Self::ADMIN_PROPERTY) =>
+					admin = value.as_bytes().filter(|bytes| bytes.len() == 20).map(|mut bytes| {
+						bytes.reverse();
+						H160::from_slice(&bytes)
+					}),
+				_ => {},
+			}
+		}
+
+		match (name, expiration) {
+			(Some(name), Some(expiration)) => Ok(Self { name, expiration, admin }),
+			_ => Err(ContractError::UnexpectedReturnType(
+				"The name properties lack the name or expiration".to_string(),
+			)),
+		}
+	}
+
+	/// Whether the name has expired at `now`, in milliseconds since the Unix epoch.
+	pub fn is_expired(&self, now: u64) -> bool {
+		self.expiration <= now
+	}
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NeoNameService<'a, P: JsonRpcClient> {
 	#[serde(deserialize_with = "deserialize_script_hash")]
@@ -77,70 +89,186 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 	const DELETE_RECORD: &'static str = "deleteRecord";
 	const RESOLVE: &'static str = "resolve";
 	const PROPERTIES: &'static str = "properties";
-
-	const NAME_PROPERTY: &'static str = "name";
-	const EXPIRATION_PROPERTY: &'static str = "expiration";
-	const ADMIN_PROPERTY: &'static str = "admin";
+	const OWNER_OF: &'static str = "ownerOf";
+	const TOKENS_OF: &'static str = "tokensOf";
+	const TRANSFER: &'static str = "transfer";
 
 	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
 		Self { script_hash: provider.unwrap().nns_resolver().clone(), provider }
 	}
 
-	// Implementation
+	// Roots and prices, managed by the committee
+
+	pub async fn add_root(&self, root: &str) -> Result<TransactionBuilder<P>, ContractError> {
+		NNSRoot::new(root).map_err(|e| ContractError::InvalidNeoNameServiceRoot(e.to_string()))?;
 
-	async fn add_root(&self, root: &str) -> Result<TransactionBuilder<P>, ContractError> {
-		let args = vec![root.to_string().into()];
+		let args = vec![root.into()];
 		self.invoke_function(Self::ADD_ROOT, args).await
 	}
 
-	async fn get_roots(&self) -> Result<NeoIterator<String, P>, ContractError> {
-		let args = vec![];
-		let roots = self
-			.call_function_returning_iterator(
-				Self::ROOTS,
-				args,
-				Arc::new(|item: StackItem| item.to_string()),
-			)
-			.await;
-
-		Ok(roots)
+	pub async fn get_roots(&self) -> Result<NeoIterator<String, P>, ContractError> {
+		self.call_function_returning_iterator(Self::ROOTS, vec![], Arc::new(Self::string_of))
+			.await
 	}
 
-	async fn get_symbol(&self) -> Result<String, ContractError> {
-		Ok("NNS".to_string())
+	/// Sets the registration prices in GAS fractions. The first price is the default, the
+	/// following ones are for names of length 1, 2 and so on.
+	pub async fn set_price(
+		&self,
+		prices: Vec<i64>,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		if prices.is_empty() || prices.iter().any(|price| *price < -1) {
+			return Err(ContractError::InvalidArgError(format!("Invalid prices {:?}", prices)))
+		}
+
+		let prices = prices.into_iter().map(ContractParameter::integer).collect();
+		self.invoke_function(Self::SET_PRICE, vec![ContractParameter::array(prices)])
+			.await
 	}
 
-	async fn get_decimals(&self) -> Result<u8, ContractError> {
-		Ok(0)
+	/// Returns the price in GAS fractions of registering a name of `length` characters.
+	pub async fn get_price(&self, length: u8) -> Result<i64, ContractError> {
+		let output =
+			self.call_invoke_function(Self::GET_PRICE, vec![length.into()], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		Self::first_item(&output)?
+			.as_int()
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Int".to_string()))
 	}
 
-	// Register a name
+	// Names
 
+	pub async fn is_available(&self, name: &str) -> Result<bool, ContractError> {
+		let args = vec![name.into()];
+		self.call_function_returning_bool(Self::IS_AVAILABLE, args).await
+	}
+
+	/// Registers the second-level domain `name`, e.g. `alice.neo`, for `owner`.
 	pub async fn register(
 		&self,
 		name: &str,
 		owner: H160,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
+		Self::validate(name, false)?;
+		self.check_domain_name_availability(name, true).await?;
 
 		let args = vec![name.into(), owner.into()];
 		self.invoke_function(Self::REGISTER, args).await
 	}
 
-	// Set admin for a name
+	/// Registers the subdomain `name`, e.g. `pay.alice.neo`, for `owner`. The transaction has to be
+	/// signed by the owner or admin of the parent domain.
+	pub async fn register_subdomain(
+		&self,
+		name: &str,
+		owner: H160,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		let name = Self::validate(name, true)?;
+		if name.is_second_level_domain() {
+			return Err(ContractError::InvalidNeoName(format!(
+				"{} is not a subdomain, use register instead",
+				name
+			)))
+		}
+		self.check_domain_name_availability(&name.second_level_domain(), false).await?;
+		self.check_domain_name_availability(name.name(), true).await?;
+
+		let args = vec![name.name().as_str().into(), owner.into()];
+		self.invoke_function(Self::REGISTER, args).await
+	}
+
+	/// Extends the registration of `name` by `years`.
+	pub async fn renew(
+		&self,
+		name: &str,
+		years: u32,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		Self::validate(name, false)?;
+		if !(1..=10).contains(&years) {
+			return Err(ContractError::InvalidArgError(format!(
+				"A name can be renewed for 1 to 10 years, not {}",
+				years
+			)))
+		}
+		self.check_domain_name_availability(name, false).await?;
+
+		let args = vec![name.into(), years.into()];
+		self.invoke_function(Self::RENEW, args).await
+	}
 
 	pub async fn set_admin(
 		&self,
 		name: &str,
 		admin: H160,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
+		Self::validate(name, true)?;
+		self.check_domain_name_availability(name, false).await?;
 
 		let args = vec![name.into(), admin.into()];
 		self.invoke_function(Self::SET_ADMIN, args).await
 	}
 
-	// Set record
+	/// Returns the expiration and admin of the second-level domain `name`.
+	pub async fn get_name_state(&self, name: &str) -> Result<NameState, ContractError> {
+		Self::validate(name, false)?;
+
+		let output = self.call_invoke_function(Self::PROPERTIES, vec![name.into()], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		NameState::from_stack_item(Self::first_item(&output)?)
+	}
+
+	// Ownership
+
+	pub async fn owner_of(&self, name: &str) -> Result<H160, ContractError> {
+		Self::validate(name, false)?;
+
+		let output = self.call_invoke_function(Self::OWNER_OF, vec![name.into()], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		let mut bytes = Self::first_item(&output)?
+			.as_bytes()
+			.filter(|bytes| bytes.len() == 20)
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Script hash".to_string()))?;
+		bytes.reverse();
+		Ok(H160::from_slice(&bytes))
+	}
+
+	/// Returns the second-level domains owned by `owner`.
+	pub async fn names_of(&self, owner: H160) -> Result<NeoIterator<String, P>, ContractError> {
+		self.call_function_returning_iterator(
+			Self::TOKENS_OF,
+			vec![owner.into()],
+			Arc::new(Self::string_of),
+		)
+		.await
+	}
+
+	/// Transfers the second-level domain `name`, which is an NEP-11 token, from `from` to `to`.
+	pub async fn transfer_name(
+		&self,
+		from: &Account,
+		to: H160,
+		name: &str,
+		data: Option<ContractParameter>,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		let owner = self.owner_of(name).await?;
+		if owner != from.get_script_hash() {
+			return Err(ContractError::InvalidStateError(format!(
+				"{} is owned by {:#x}",
+				name, owner
+			)))
+		}
+
+		let data = data.unwrap_or_else(|| ContractParameter::new(ContractParameterType::Any));
+		let mut builder =
+			self.invoke_function(Self::TRANSFER, vec![to.into(), name.into(), data]).await?;
+		let signer = AccountSigner::called_by_entry(from)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		builder.set_signers(vec![signer.into()]);
+		Ok(builder)
+	}
+
+	// Records
 
 	pub async fn set_record(
 		&self,
@@ -148,86 +276,105 @@ impl<'a, P: JsonRpcClient> NeoNameService<'a, P> {
 		record_type: RecordType,
 		data: &str,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let args = vec![name.into(), (record_type as u8).into(), data.into()];
+		Self::validate(name, true)?;
 
+		let args = vec![name.into(), record_type.byte_repr().into(), data.into()];
 		self.invoke_function(Self::SET_RECORD, args).await
 	}
 
-	// Delete record
+	pub async fn get_record(
+		&self,
+		name: &str,
+		record_type: RecordType,
+	) -> Result<String, ContractError> {
+		Self::validate(name, true)?;
+
+		let args = vec![name.into(), record_type.byte_repr().into()];
+		let output = self.call_invoke_function(Self::GET_RECORD, args, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		match Self::first_item(&output)? {
+			StackItem::Any => Err(ContractError::UnresolvableDomainName(format!(
+				"{} has no {} record",
+				name, record_type
+			))),
+			item => Ok(Self::string_of(item.clone())),
+		}
+	}
+
+	/// Returns an iterator over the records of `name`. Each item is an error if the node returns
+	/// something other than a record.
+	pub async fn get_all_records(
+		&self,
+		name: &str,
+	) -> Result<NeoIterator<Result<RecordState, ContractError>, P>, ContractError> {
+		Self::validate(name, true)?;
+
+		self.call_function_returning_iterator(
+			Self::GET_ALL_RECORDS,
+			vec![name.into()],
+			Arc::new(|item: StackItem| {
+				RecordState::from_stack_item(&item)
+					.map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))
+			}),
+		)
+		.await
+	}
 
 	pub async fn delete_record(
 		&self,
 		name: &str,
 		record_type: RecordType,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let args = vec![name.into(), (record_type as u8).into()];
+		Self::validate(name, true)?;
+
+		let args = vec![name.into(), record_type.byte_repr().into()];
 		self.invoke_function(Self::DELETE_RECORD, args).await
 	}
 
-	pub async fn is_available(&self, name: &str) -> Result<bool, ContractError> {
-		let args = vec![name.into()];
-		self.call_function_returning_bool(Self::IS_AVAILABLE, args).await
-	}
-	pub async fn renew(
+	/// Resolves the record of `name`, following CNAME records on chain.
+	pub async fn resolve(
 		&self,
 		name: &str,
-		years: u32,
-	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.check_domain_name_availability(name, true).await.unwrap();
+		record_type: RecordType,
+	) -> Result<String, ContractError> {
+		Self::validate(name, true)?;
+
+		let args = vec![name.into(), record_type.byte_repr().into()];
+		let output = self.call_invoke_function(Self::RESOLVE, args, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+		match Self::first_item(&output)? {
+			StackItem::Any => Err(ContractError::UnresolvableDomainName(name.to_string())),
+			item => Ok(Self::string_of(item.clone())),
+		}
+	}
 
-		let args = vec![name.into(), years.into()];
-		self.invoke_function(Self::RENEW, args).await
+	fn validate(name: &str, allow_multi_fragments: bool) -> Result<NNSName, ContractError> {
+		NNSName::is_valid(name, allow_multi_fragments)
+			.and_then(|_| NNSName::new(name))
+			.map_err(|e| ContractError::InvalidNeoName(e.to_string()))
 	}
 
-	async fn get_name_state(&self, name: &[u8]) -> Result<NameState, ContractError> {
-		let args = vec![name.into()];
-		let result = self
-			.provider
-			.unwrap()
-			.invoke_function(&self.script_hash, Self::PROPERTIES.to_string(), args, None)
-			.await
-			.unwrap()
-			.stack[0]
-			.clone();
-
-		let map = result.as_map().unwrap();
-		let name = map
-			.get(&StackItem::ByteString { value: Self::NAME_PROPERTY.to_string() })
-			.unwrap()
-			.as_string()
-			.unwrap();
-		let expiration = map
-			.get(&StackItem::ByteString { value: Self::EXPIRATION_PROPERTY.to_string() })
-			.unwrap()
-			.as_int()
-			.unwrap() as u32;
-		let admin = map
-			.get(&StackItem::ByteString { value: Self::ADMIN_PROPERTY.to_string() })
-			.unwrap()
-			.as_address()
-			.unwrap();
-
-		Ok(NameState {
-			name,
-			expiration,
-			admin: Some(AddressOrScriptHash::from(admin).script_hash()),
+	fn first_item(output: &InvocationResult) -> Result<&StackItem, ContractError> {
+		output.stack.first().ok_or_else(|| {
+			ContractError::UnexpectedReturnType("The result stack is empty".to_string())
 		})
 	}
+
+	fn string_of(item: StackItem) -> String {
+		String::from_utf8_lossy(&item.as_bytes().unwrap_or_default()).into_owned()
+	}
+
 	async fn check_domain_name_availability(
 		&self,
 		name: &str,
 		should_be_available: bool,
 	) -> Result<(), ContractError> {
-		let is_available = self.is_available(name).await.unwrap();
+		let is_available = self.is_available(name).await?;
 
 		if should_be_available && !is_available {
-			return Err(ContractError::DomainNameNotAvailable(
-				"Domain name already taken".to_string(),
-			))
+			return Err(ContractError::DomainNameNotAvailable(name.to_string()))
 		} else if !should_be_available && is_available {
-			return Err(ContractError::DomainNameNotRegistered(
-				"Domain name not registered".to_string(),
-			))
+			return Err(ContractError::DomainNameNotRegistered(name.to_string()))
 		}
 
 		Ok(())
@@ -259,27 +406,6 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoNameService<'a, P> {
 	fn set_symbol(&mut self, _symbol: String) {
 		panic!("Cannot set symbol for NNS")
 	}
-
-	async fn resolve_nns_text_record(&self, name: &NNSName) -> Result<H160, ContractError> {
-		let req = {
-			self.provider()
-				.unwrap()
-				.invoke_function(
-					&self.script_hash(),
-					"resolve".to_string(),
-					vec![
-						ContractParameter::from(name.name()),
-						ContractParameter::from(RecordType::Txt as u8),
-					],
-					None,
-				)
-				.await
-		};
-
-		let address = req.unwrap().stack.first().unwrap().clone();
-
-		Ok(H160::from_slice(&address.as_bytes().unwrap()))
-	}
 }
 
 impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoNameService<'a, P> {
@@ -301,3 +427,164 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoNameService<'a, P> {
 }
 
 impl<'a, P: JsonRpcClient> NonFungibleTokenTrait<'a, P> for NeoNameService<'a, P> {}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H160;
+
+	use neo::prelude::{
+		ContractError, InvocationResult, MapEntry, MockProvider, NameState, NeoNameService,
+		NeoVMStateType, Provider, RecordState, RecordType, StackItem,
+	};
+
+	fn string(value: &str) -> StackItem {
+		StackItem::ByteString { value: hex::encode(value) }
+	}
+
+	fn mocked(results: Vec<StackItem>) -> Provider<MockProvider> {
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocations(results).unwrap();
+		provider
+	}
+
+	#[test]
+	fn test_name_state_from_stack_item() {
+		let admin = H160::repeat_byte(0x01);
+		let item = StackItem::Map {
+			value: vec![
+				MapEntry::new(string("name"), string("alice.neo")),
+				MapEntry::new(
+					string("expiration"),
+					StackItem::Integer { value: 1_700_000_000_000 },
+				),
+				MapEntry::new(
+					string("admin"),
+					StackItem::ByteString {
+						value: hex::encode(
+							admin.as_bytes().iter().rev().copied().collect::<Vec<_>>(),
+						),
+					},
+				),
+			],
+		};
+
+		let state = NameState::from_stack_item(&item).unwrap();
+		assert_eq!(state.name, "alice.neo");
+		assert_eq!(state.expiration, 1_700_000_000_000);
+		assert_eq!(state.admin, Some(admin));
+		assert!(state.is_expired(1_700_000_000_000));
+		assert!(!state.is_expired(1_699_999_999_999));
+
+		assert!(NameState::from_stack_item(&StackItem::Map { value: vec![] }).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_names_are_validated() {
+		let provider = mocked(vec![]);
+		let nns = NeoNameService::new(Some(&provider));
+
+		assert!(matches!(
+			nns.register("pay.alice.neo", H160::zero()).await,
+			Err(ContractError::InvalidNeoName(_))
+		));
+		assert!(matches!(
+			nns.register_subdomain("alice.neo", H160::zero()).await,
+			Err(ContractError::InvalidNeoName(_))
+		));
+		assert!(matches!(
+			nns.set_record("alice_bob.neo", RecordType::TXT, "data").await,
+			Err(ContractError::InvalidNeoName(_))
+		));
+		assert!(matches!(
+			nns.add_root("1neo").await,
+			Err(ContractError::InvalidNeoNameServiceRoot(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_register_subdomain() {
+		// The parent is registered and the subdomain is available.
+		let provider =
+			mocked(vec![StackItem::Boolean { value: false }, StackItem::Boolean { value: true }]);
+		let nns = NeoNameService::new(Some(&provider));
+		assert!(nns.register_subdomain("pay.alice.neo", H160::zero()).await.is_ok());
+
+		let provider = mocked(vec![StackItem::Boolean { value: true }]);
+		let nns = NeoNameService::new(Some(&provider));
+		assert!(matches!(
+			nns.register_subdomain("pay.alice.neo", H160::zero()).await,
+			Err(ContractError::DomainNameNotRegistered(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_get_record() {
+		let provider = mocked(vec![string("10.0.0.1"), StackItem::Any]);
+		let nns = NeoNameService::new(Some(&provider));
+
+		assert_eq!(nns.get_record("alice.neo", RecordType::A).await.unwrap(), "10.0.0.1");
+		assert!(matches!(
+			nns.get_record("alice.neo", RecordType::AAAA).await,
+			Err(ContractError::UnresolvableDomainName(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_get_all_records_with_malformed_item() {
+		let (provider, mock) = Provider::mocked();
+		let record = StackItem::Array {
+			value: vec![
+				string("alice.neo"),
+				StackItem::Integer { value: RecordType::A.byte_repr() as i64 },
+				string("10.0.0.1"),
+			],
+		};
+		mock.push(vec![record, StackItem::Integer { value: 1 }]).unwrap();
+		let mut iterator = InvocationResult::halt(vec![StackItem::InteropInterface {
+			id: "iterator".to_string(),
+			interface: "IIterator".to_string(),
+		}]);
+		iterator.session_id = Some("session".to_string());
+		mock.push(iterator).unwrap();
+		let nns = NeoNameService::new(Some(&provider));
+
+		let records = nns.get_all_records("alice.neo").await.unwrap().traverse(10).await.unwrap();
+		assert_eq!(
+			records[0].as_ref().unwrap(),
+			&RecordState::new("alice.neo".to_string(), RecordType::A, "10.0.0.1".to_string())
+		);
+		assert!(matches!(records[1], Err(ContractError::UnexpectedReturnType(_))));
+	}
+
+	#[tokio::test]
+	async fn test_get_all_records_errors() {
+		let (provider, mock) = Provider::mocked();
+		let mut fault = InvocationResult::halt(vec![]);
+		fault.state = NeoVMStateType::Fault;
+		fault.exception = Some("The name does not exist".to_string());
+		mock.push(fault).unwrap();
+		let nns = NeoNameService::new(Some(&provider));
+		assert!(matches!(
+			nns.get_all_records("alice.neo").await,
+			Err(ContractError::UnexpectedReturnType(_))
+		));
+
+		// A node with sessions disabled returns the iterator without a session
+		let provider = mocked(vec![StackItem::InteropInterface {
+			id: "iterator".to_string(),
+			interface: "IIterator".to_string(),
+		}]);
+		let nns = NeoNameService::new(Some(&provider));
+		assert!(matches!(
+			nns.get_all_records("alice.neo").await,
+			Err(ContractError::InvalidStateError(_))
+		));
+
+		let provider = mocked(vec![StackItem::Integer { value: 1 }]);
+		let nns = NeoNameService::new(Some(&provider));
+		assert!(matches!(
+			nns.get_all_records("alice.neo").await,
+			Err(ContractError::UnexpectedReturnType(_))
+		));
+	}
+}
@@ -1,5 +1,8 @@
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
 use neo::prelude::*;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 use primitive_types::H160;
 use serde::{Deserialize, Serialize};
 
@@ -24,7 +27,16 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 	pub const SYMBOL: &'static str = "NEO";
 	pub const TOTAL_SUPPLY: u64 = 100_000_000;
 
-	pub(crate) fn new(provider: Option<&'a Provider<P>>) -> Self {
+	/// The share of the GAS generated per block that goes to the voters of the committee, in
+	/// percent.
+	pub const VOTER_REWARD_RATIO: i64 = 80;
+	/// The share of the total supply that must have voted before the candidates with the most
+	/// votes replace the standby committee, in percent.
+	pub const EFFECTIVE_VOTER_TURNOUT: i64 = 20;
+
+	const PREFIX_VOTER_REWARD_PER_COMMITTEE: u8 = 23;
+
+	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
 		Self {
 			script_hash: Self::calc_native_contract_hash(Self::NAME).unwrap(),
 			total_supply: Some(Self::TOTAL_SUPPLY),
@@ -36,7 +48,8 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 
 	// Unclaimed Gas
 
-	async fn unclaimed_gas(
+	/// Returns the GAS fractions the account can claim at `block_height`.
+	pub async fn unclaimed_gas(
 		&self,
 		account: &Account,
 		block_height: i32,
@@ -44,93 +57,216 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 		self.unclaimed_gas_contract(&account.get_script_hash(), block_height).await
 	}
 
-	async fn unclaimed_gas_contract(
+	pub async fn unclaimed_gas_contract(
 		&self,
 		script_hash: &H160,
 		block_height: i32,
 	) -> Result<i64, ContractError> {
-		Ok(self
-			.call_function_returning_int(
-				"unclaimedGas",
-				vec![script_hash.into(), block_height.into()],
-			)
+		self.call_function_returning_i64(
+			"unclaimedGas",
+			vec![script_hash.into(), block_height.into()],
+		)
+		.await
+	}
+
+	/// Calculates the GAS fractions the account can claim in the next block locally, from its
+	/// account state and the GAS per block history of `calculator`.
+	pub async fn calculate_unclaimed_gas(
+		&self,
+		script_hash: &H160,
+		calculator: &UnclaimedGasCalculator,
+	) -> Result<i64, ContractError> {
+		let provider = self
+			.provider
+			.ok_or_else(|| ContractError::InvalidStateError("No provider set".to_string()))?;
+		let state = self.get_account_state(script_hash).await?;
+		let latest_gas_per_vote = match &state.public_key {
+			Some(candidate) => Some(self.get_latest_gas_per_vote(candidate).await?),
+			None => None,
+		};
+		let end = provider.get_block_count().await?;
+		Ok(calculator.calculate(&state, end, latest_gas_per_vote))
+	}
+
+	/// Returns the GAS per vote accumulated by the voters of `candidate`, scaled by
+	/// [`UnclaimedGasCalculator::GAS_PER_VOTE_FACTOR`].
+	pub async fn get_latest_gas_per_vote(
+		&self,
+		candidate: &Secp256r1PublicKey,
+	) -> Result<i64, ContractError> {
+		let provider = self
+			.provider
+			.ok_or_else(|| ContractError::InvalidStateError("No provider set".to_string()))?;
+		let mut key = vec![Self::PREFIX_VOTER_REWARD_PER_COMMITTEE];
+		key.extend(candidate.get_encoded(true));
+		let value = match provider
+			.get_storage(self.script_hash, &general_purpose::STANDARD.encode(key))
 			.await
-			.unwrap() as i64)
+		{
+			Ok(value) => value,
+			// Candidates that have never been in the committee have no entry, which the node
+			// reads as 0
+			Err(e) if RpcError::error_code(&e) == Some(NeoRpcErrorCode::UnknownStorageItem) =>
+				return Ok(0),
+			Err(e) => return Err(e.into()),
+		};
+		let bytes = general_purpose::STANDARD
+			.decode(value)
+			.map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))?;
+		BigInt::from_signed_bytes_le(&bytes)
+			.to_i64()
+			.ok_or_else(|| ContractError::UnexpectedReturnType("GAS per vote".to_string()))
+	}
+
+	/// Builds a transaction claiming the GAS of all `accounts` at once, by transferring 0 NEO from
+	/// each account to itself. Every account signs with the `CalledByEntry` scope.
+	pub async fn claim_gas(
+		&self,
+		accounts: &[Account],
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		if accounts.is_empty() {
+			return Err(ContractError::InvalidArgError("No accounts to claim GAS for".to_string()))
+		}
+
+		let mut script = ScriptBuilder::new();
+		let mut signers = Vec::with_capacity(accounts.len());
+		for account in accounts {
+			let script_hash = account.get_script_hash();
+			script
+				.contract_call(
+					&self.script_hash,
+					"transfer",
+					&[
+						script_hash.into(),
+						script_hash.into(),
+						ContractParameter::integer(0),
+						ContractParameter::new(ContractParameterType::Any),
+					],
+					None,
+				)
+				.map_err(|e| ContractError::RuntimeError(e.to_string()))?
+				.op_code(&[OpCode::Assert]);
+			let signer = AccountSigner::called_by_entry(account)
+				.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+			signers.push(signer.into());
+		}
+
+		let mut builder = TransactionBuilder::new();
+		builder.set_script(script.to_bytes());
+		builder.set_signers(signers);
+		Ok(builder)
 	}
 
 	// Candidate Registration
 
-	async fn register_candidate(
+	/// Builds a transaction registering `candidate_key` as a candidate. It must be signed by the
+	/// account of the key, which also pays the register price as system fee.
+	pub async fn register_candidate(
 		&self,
 		candidate_key: &Secp256r1PublicKey,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.invoke_function("registerCandidate", vec![candidate_key.into()]).await
+		let mut builder =
+			self.invoke_function("registerCandidate", vec![candidate_key.into()]).await?;
+		builder.set_signers(vec![Self::candidate_signer(candidate_key)?]);
+		Ok(builder)
 	}
 
-	async fn unregister_candidate(
+	/// Builds a transaction unregistering `candidate_key`, signed by the account of the key.
+	pub async fn unregister_candidate(
 		&self,
 		candidate_key: &Secp256r1PublicKey,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.invoke_function("unregisterCandidate", vec![candidate_key.into()]).await
+		let mut builder =
+			self.invoke_function("unregisterCandidate", vec![candidate_key.into()]).await?;
+		builder.set_signers(vec![Self::candidate_signer(candidate_key)?]);
+		Ok(builder)
+	}
+
+	fn candidate_signer(candidate_key: &Secp256r1PublicKey) -> Result<Signer, ContractError> {
+		let account = Account::from_public_key(candidate_key)
+			.map_err(|e| ContractError::InvalidArgError(e.to_string()))?;
+		let signer = AccountSigner::called_by_entry(&account)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		Ok(signer.into())
 	}
 
 	// Committee and Candidates Information
 
-	async fn get_committee(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		self.call_function_returning_list_of_public_keys("getCommittee")
-			.await
-			.map_err(|e| ContractError::UnexpectedReturnType(e.to_string()))
-	}
-
-	async fn get_candidates(&self) -> Result<Vec<Candidate>, ContractError> {
-		let candidates = self.call_invoke_function("getCandidates", vec![], vec![]).await.unwrap();
-		let item = candidates.stack.first().unwrap();
-		if let StackItem::Array { value: array } = item {
-			Ok(array
-				.to_vec()
-				.chunks(2)
-				.filter_map(|v| {
-					if v.len() == 2 {
-						Some(Candidate::from(v.to_vec()).unwrap())
-					} else {
-						None
-					}
+	/// Returns the public keys of the current committee.
+	pub async fn get_committee(&self) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		self.call_function_returning_list_of_public_keys("getCommittee").await
+	}
+
+	/// Returns the public keys of the validators of the next block.
+	pub async fn get_next_block_validators(
+		&self,
+	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
+		let provider = self
+			.provider
+			.ok_or_else(|| ContractError::InvalidStateError("No provider set".to_string()))?;
+		provider
+			.get_next_block_validators()
+			.await?
+			.iter()
+			.map(|validator| {
+				Secp256r1PublicKey::from_encoded(&validator.public_key).ok_or_else(|| {
+					ContractError::UnexpectedReturnType(format!(
+						"Invalid validator key {}",
+						validator.public_key
+					))
 				})
-				.collect::<Vec<Candidate>>())
-		} else {
-			Err(ContractError::UnexpectedReturnType("Candidates".to_string()))
-		}
+			})
+			.collect()
 	}
 
-	async fn is_candidate(&self, public_key: &Secp256r1PublicKey) -> Result<bool, ContractError> {
-		Ok(self
-			.get_candidates()
-			.await
-			.unwrap()
-			.into_iter()
-			.any(|c| c.public_key == *public_key))
+	/// Returns the registered candidates and their votes.
+	pub async fn get_candidates(&self) -> Result<Vec<Candidate>, ContractError> {
+		let output = self.call_invoke_function("getCandidates", vec![], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		output
+			.stack
+			.first()
+			.and_then(StackItem::as_array)
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Candidates".to_string()))?
+			.iter()
+			.map(Candidate::from_stack_item)
+			.collect()
+	}
+
+	pub async fn is_candidate(
+		&self,
+		public_key: &Secp256r1PublicKey,
+	) -> Result<bool, ContractError> {
+		Ok(self.get_candidates().await?.into_iter().any(|c| c.public_key == *public_key))
 	}
 
 	// Voting
 
-	async fn vote(
+	/// Builds a transaction voting for `candidate` with the NEO of `voter`, or cancelling the vote
+	/// of `voter` if `candidate` is `None`. It must be signed by the voter.
+	pub async fn vote(
 		&self,
-		voter: &H160,
+		voter: &Account,
 		candidate: Option<&Secp256r1PublicKey>,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		let params = match candidate {
-			Some(key) => vec![voter.into(), key.into()],
-			None => vec![voter.into(), ContractParameter::new(ContractParameterType::Any)],
-		};
-
-		self.invoke_function("vote", params).await
+		let script = self.build_vote_script(&voter.get_script_hash(), candidate).await?;
+		let mut builder = TransactionBuilder::new();
+		builder.set_script(script);
+		let signer = AccountSigner::called_by_entry(voter)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		builder.set_signers(vec![signer.into()]);
+		Ok(builder)
 	}
 
-	async fn cancel_vote(&self, voter: &H160) -> Result<TransactionBuilder<P>, ContractError> {
+	pub async fn cancel_vote(
+		&self,
+		voter: &Account,
+	) -> Result<TransactionBuilder<P>, ContractError> {
 		self.vote(voter, None).await
 	}
 
-	async fn build_vote_script(
+	pub async fn build_vote_script(
 		&self,
 		voter: &H160,
 		candidate: Option<&Secp256r1PublicKey>,
@@ -143,91 +279,232 @@ impl<'a, P: JsonRpcClient> NeoToken<'a, P> {
 		self.build_invoke_function_script("vote", params).await
 	}
 
+	// Governance Analytics
+
+	/// Returns the candidates ranked the way the committee is elected: by votes, then by public
+	/// key.
+	pub async fn get_vote_standings(&self) -> Result<Vec<VoteStanding>, ContractError> {
+		let candidates = self.get_candidates().await?;
+		let committee = self.get_committee().await?;
+		let validators = self.get_next_block_validators().await?;
+		Ok(Self::rank(candidates, &committee, &validators))
+	}
+
+	/// Projects the committee and validators that are elected at the next committee refresh if
+	/// the votes do not change.
+	///
+	/// As long as fewer candidates than committee members are registered or less than
+	/// [`Self::EFFECTIVE_VOTER_TURNOUT`] percent of the NEO has voted, the standby committee stays
+	/// in office, which is projected as the current committee and validators.
+	pub async fn project_committee(&self) -> Result<CommitteeProjection, ContractError> {
+		let candidates = self.get_candidates().await?;
+		let committee = self.get_committee().await?;
+		let validators = self.get_next_block_validators().await?;
+
+		let total_votes: i64 = candidates.iter().map(|c| c.votes).sum();
+		let turnout_reached =
+			total_votes * 100 >= Self::TOTAL_SUPPLY as i64 * Self::EFFECTIVE_VOTER_TURNOUT;
+		if !turnout_reached || candidates.len() < committee.len() {
+			return Ok(CommitteeProjection {
+				committee,
+				validators,
+				joining: vec![],
+				leaving: vec![],
+			})
+		}
+
+		let standings = Self::rank(candidates, &committee, &validators);
+		let projected = standings
+			.iter()
+			.take(committee.len())
+			.map(|s| s.public_key.clone())
+			.collect::<Vec<_>>();
+		let mut projected_validators =
+			projected.iter().take(validators.len()).cloned().collect::<Vec<_>>();
+		projected_validators.sort();
+
+		Ok(CommitteeProjection {
+			joining: projected.iter().filter(|k| !committee.contains(k)).cloned().collect(),
+			leaving: committee.iter().filter(|k| !projected.contains(k)).cloned().collect(),
+			committee: projected,
+			validators: projected_validators,
+		})
+	}
+
+	/// Estimates the GAS fractions that `neo_amount` NEO voted for `candidate` earns over `blocks`
+	/// blocks, assuming the votes of `neo_amount` are already counted for the candidate.
+	///
+	/// Only voters of committee members are rewarded. The voters of a validator share twice the
+	/// reward of the voters of another committee member.
+	pub async fn estimate_voter_reward(
+		&self,
+		candidate: &Secp256r1PublicKey,
+		neo_amount: i64,
+		blocks: u32,
+	) -> Result<i64, ContractError> {
+		let committee = self.get_committee().await?;
+		if !committee.contains(candidate) {
+			return Ok(0)
+		}
+		let validators = self.get_next_block_validators().await?;
+		let votes = self
+			.get_candidates()
+			.await?
+			.into_iter()
+			.find(|c| c.public_key == *candidate)
+			.map(|c| c.votes)
+			.unwrap_or_default();
+		let gas_per_block = self.get_gas_per_block().await?;
+
+		Ok(Self::voter_reward(
+			gas_per_block as i64,
+			committee.len(),
+			validators.len(),
+			validators.contains(candidate),
+			votes,
+			neo_amount,
+			blocks,
+		))
+	}
+
+	fn rank(
+		mut candidates: Vec<Candidate>,
+		committee: &[Secp256r1PublicKey],
+		validators: &[Secp256r1PublicKey],
+	) -> Vec<VoteStanding> {
+		candidates.sort_by(|a, b| b.votes.cmp(&a.votes).then(a.public_key.cmp(&b.public_key)));
+		let total_votes: i64 = candidates.iter().map(|c| c.votes).sum();
+
+		candidates
+			.into_iter()
+			.enumerate()
+			.map(|(index, candidate)| VoteStanding {
+				rank: index + 1,
+				share: if total_votes > 0 {
+					candidate.votes as f64 / total_votes as f64
+				} else {
+					0.0
+				},
+				in_committee: committee.contains(&candidate.public_key),
+				is_validator: validators.contains(&candidate.public_key),
+				public_key: candidate.public_key,
+				votes: candidate.votes,
+			})
+			.collect()
+	}
+
+	fn voter_reward(
+		gas_per_block: i64,
+		committee_size: usize,
+		validators_count: usize,
+		is_validator: bool,
+		votes: i64,
+		neo_amount: i64,
+		blocks: u32,
+	) -> i64 {
+		if votes <= 0 || committee_size == 0 {
+			return 0
+		}
+		let factor = if is_validator { 2 } else { 1 };
+		// Divide once at the end to keep the precision of the fractions.
+		let reward = gas_per_block as i128
+			* Self::VOTER_REWARD_RATIO as i128
+			* factor
+			* committee_size as i128
+			* neo_amount as i128
+			* blocks as i128
+			/ (100 * (committee_size + validators_count) as i128 * votes as i128);
+		reward.min(i64::MAX as i128) as i64
+	}
+
 	// Network Settings
 
-	async fn get_gas_per_block(&self) -> Result<i32, ContractError> {
+	pub async fn get_gas_per_block(&self) -> Result<i32, ContractError> {
 		self.call_function_returning_int("getGasPerBlock", vec![]).await
 	}
 
-	async fn set_gas_per_block(
+	/// Builds a transaction setting the GAS generated per block. It must be signed by the
+	/// committee.
+	pub async fn set_gas_per_block(
 		&self,
 		gas_per_block: i32,
 	) -> Result<TransactionBuilder<P>, ContractError> {
 		self.invoke_function("setGasPerBlock", vec![gas_per_block.into()]).await
 	}
 
-	async fn get_register_price(&self) -> Result<i32, ContractError> {
-		self.call_function_returning_int("getRegisterPrice", vec![]).await
+	pub async fn get_register_price(&self) -> Result<i64, ContractError> {
+		self.call_function_returning_i64("getRegisterPrice", vec![]).await
 	}
 
-	async fn set_register_price(
+	/// Builds a transaction setting the GAS fractions paid to register a candidate. It must be
+	/// signed by the committee.
+	pub async fn set_register_price(
 		&self,
-		register_price: i32,
+		register_price: i64,
 	) -> Result<TransactionBuilder<P>, ContractError> {
-		self.invoke_function("setRegisterPrice", vec![register_price.into()]).await
+		self.invoke_function("setRegisterPrice", vec![ContractParameter::integer(register_price)])
+			.await
 	}
 
-	async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
-		let result = self
+	pub async fn get_account_state(&self, account: &H160) -> Result<AccountState, ContractError> {
+		let output = self
 			.call_invoke_function("getAccountState", vec![account.into()], vec![])
-			.await
-			.unwrap()
-			.stack
-			.first()
-			.unwrap()
-			.clone();
+			.await?;
+		self.throw_if_fault_state(&output)?;
+		let malformed = || ContractError::UnexpectedReturnType("Account state".to_string());
 
-		match result {
+		match output.stack.first().ok_or_else(malformed)? {
 			StackItem::Any => Ok(AccountState::with_no_balance()),
-			StackItem::Array { value: items } if items.len() >= 3 => {
-				let balance = items[0].as_int().unwrap();
-				let update_height = items[1].as_int();
-				let public_key = items[2].clone();
-
-				if let StackItem::Any = public_key {
-					return Ok(AccountState {
-						balance,
-						balance_height: update_height,
-						public_key: None,
-					})
-				} else {
-					let pubkey =
-						Secp256r1PublicKey::from_bytes(public_key.as_bytes().unwrap().as_slice())
-							.unwrap();
-					Ok(AccountState {
-						balance,
-						balance_height: update_height,
-						public_key: Some(pubkey),
-					})
-				}
+			item => {
+				let items =
+					item.as_array().filter(|items| items.len() >= 3).ok_or_else(malformed)?;
+				let public_key = match &items[2] {
+					StackItem::Any => None,
+					key => Some(key.as_public_key().ok_or_else(malformed)?),
+				};
+				Ok(AccountState {
+					balance: items[0].as_int().ok_or_else(malformed)?,
+					balance_height: items[1].as_int(),
+					public_key,
+					last_gas_per_vote: items.get(3).and_then(StackItem::as_int),
+				})
 			},
-			_ => Err(ContractError::InvalidNeoName("Account state malformed".to_string())),
 		}
 	}
 
+	async fn call_function_returning_i64(
+		&self,
+		function: &str,
+		params: Vec<ContractParameter>,
+	) -> Result<i64, ContractError> {
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		output
+			.stack
+			.first()
+			.and_then(StackItem::as_int)
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Int".to_string()))
+	}
+
 	async fn call_function_returning_list_of_public_keys(
 		&self,
 		function: &str,
 	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		let result = self.call_invoke_function(function, vec![], vec![]).await.unwrap();
-		let stack_item = result.stack.first().unwrap();
+		let output = self.call_invoke_function(function, vec![], vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
-		if let StackItem::Array { value: array } = stack_item {
-			let keys = array
-				.iter()
-				.map(|item| {
-					if let StackItem::ByteString { value: bytes } = item {
-						Secp256r1PublicKey::from_bytes(bytes.as_bytes()).unwrap()
-					} else {
-						panic!("Unexpected stack item type")
-					}
-				})
-				.collect::<Vec<Secp256r1PublicKey>>();
-
-			Ok(keys)
-		} else {
-			Err(ContractError::UnexpectedReturnType("UnexpectedReturnType".to_string()))
-		}
+		output
+			.stack
+			.first()
+			.and_then(StackItem::as_array)
+			.ok_or_else(|| ContractError::UnexpectedReturnType("Array".to_string()))?
+			.iter()
+			.map(|item| {
+				item.as_public_key()
+					.ok_or_else(|| ContractError::UnexpectedReturnType("Public key".to_string()))
+			})
+			.collect()
 	}
 }
 
@@ -256,10 +533,6 @@ impl<'a, P: JsonRpcClient> TokenTrait<'a, P> for NeoToken<'a, P> {
 	fn set_symbol(&mut self, symbol: String) {
 		self.symbol = Some(symbol)
 	}
-
-	async fn resolve_nns_text_record(&self, _name: &NNSName) -> Result<H160, ContractError> {
-		todo!()
-	}
 }
 
 #[async_trait]
@@ -282,15 +555,210 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NeoToken<'a, P> {
 #[async_trait]
 impl<'a, P: JsonRpcClient> FungibleTokenTrait<'a, P> for NeoToken<'a, P> {}
 
+/// A registered candidate and the NEO voted for it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Candidate {
 	pub public_key: Secp256r1PublicKey,
-	pub votes: i32,
+	pub votes: i64,
 }
 
 impl Candidate {
-	fn from(items: Vec<StackItem>) -> Result<Self, ContractError> {
-		let key = items[0].as_public_key().unwrap();
-		let votes = items[1].as_int().unwrap() as i32;
-		Ok(Self { public_key: key, votes })
+	/// Parses a `[public key, votes]` struct returned by `getCandidates`.
+	pub fn from_stack_item(item: &StackItem) -> Result<Self, ContractError> {
+		let malformed = || ContractError::UnexpectedReturnType("Candidate".to_string());
+		let items = item.as_array().filter(|items| items.len() == 2).ok_or_else(malformed)?;
+		Ok(Self {
+			public_key: items[0].as_public_key().ok_or_else(malformed)?,
+			votes: items[1].as_int().ok_or_else(malformed)?,
+		})
+	}
+}
+
+/// The position of a candidate in the election of the committee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteStanding {
+	/// The 1-based rank, by votes and then by public key.
+	pub rank: usize,
+	pub public_key: Secp256r1PublicKey,
+	pub votes: i64,
+	/// The share of all votes cast for candidates.
+	pub share: f64,
+	/// Whether the candidate is a member of the current committee.
+	pub in_committee: bool,
+	/// Whether the candidate validates the next block.
+	pub is_validator: bool,
+}
+
+/// The committee and validators elected at the next committee refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitteeProjection {
+	/// The projected committee, ranked by votes.
+	pub committee: Vec<Secp256r1PublicKey>,
+	/// The projected validators, sorted by public key as in the consensus.
+	pub validators: Vec<Secp256r1PublicKey>,
+	/// Members of the projected committee that are not in the current one.
+	pub joining: Vec<Secp256r1PublicKey>,
+	/// Members of the current committee that are not in the projected one.
+	pub leaving: Vec<Secp256r1PublicKey>,
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		Account, AccountTrait, Candidate, ContractError, JsonRpcError, KeyPair, MockProvider,
+		MockResponse, NeoToken, Provider, Secp256r1PublicKey, StackItem, Validator,
+	};
+
+	fn keys(count: usize) -> Vec<Secp256r1PublicKey> {
+		let mut keys = (0..count).map(|_| KeyPair::new_random().public_key()).collect::<Vec<_>>();
+		keys.sort();
+		keys
+	}
+
+	fn key_item(key: &Secp256r1PublicKey) -> StackItem {
+		StackItem::ByteString { value: key.get_encoded_compressed_hex() }
+	}
+
+	fn candidates_item(candidates: &[(Secp256r1PublicKey, i64)]) -> StackItem {
+		StackItem::Array {
+			value: candidates
+				.iter()
+				.map(|(key, votes)| StackItem::Struct {
+					value: vec![key_item(key), StackItem::Integer { value: *votes }],
+				})
+				.collect(),
+		}
+	}
+
+	/// Queues the responses to `getnextblockvalidators`, `getCommittee` and `getCandidates`, the
+	/// last of which is requested first.
+	fn mocked(
+		candidates: &[(Secp256r1PublicKey, i64)],
+		committee: &[Secp256r1PublicKey],
+		validators: &[Secp256r1PublicKey],
+	) -> Provider<MockProvider> {
+		let (provider, mock) = Provider::mocked();
+		let validators = validators
+			.iter()
+			.map(|key| Validator {
+				public_key: key.get_encoded_compressed_hex(),
+				votes: "0".to_string(),
+				active: true,
+			})
+			.collect::<Vec<_>>();
+		mock.push(validators).unwrap();
+		mock.push_invocation(StackItem::Array { value: committee.iter().map(key_item).collect() })
+			.unwrap();
+		mock.push_invocation(candidates_item(candidates)).unwrap();
+		provider
+	}
+
+	#[test]
+	fn test_candidate_from_stack_item() {
+		let key = keys(1).remove(0);
+		let item = StackItem::Struct {
+			value: vec![key_item(&key), StackItem::Integer { value: 3_000_000 }],
+		};
+		assert_eq!(
+			Candidate::from_stack_item(&item).unwrap(),
+			Candidate { public_key: key.clone(), votes: 3_000_000 }
+		);
+		assert!(
+			Candidate::from_stack_item(&StackItem::Struct { value: vec![key_item(&key)] }).is_err()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_get_vote_standings() {
+		let keys = keys(3);
+		let candidates = [(keys[0].clone(), 100), (keys[1].clone(), 300), (keys[2].clone(), 100)];
+		let provider = mocked(&candidates, &keys[1..2], &keys[1..2]);
+		let neo = NeoToken::new(Some(&provider));
+
+		let standings = neo.get_vote_standings().await.unwrap();
+		let ranked = standings.iter().map(|s| s.public_key.clone()).collect::<Vec<_>>();
+		// Ties are broken by public key.
+		assert_eq!(ranked, vec![keys[1].clone(), keys[0].clone(), keys[2].clone()]);
+		assert_eq!(standings[0].rank, 1);
+		assert_eq!(standings[0].share, 0.6);
+		assert!(standings[0].in_committee && standings[0].is_validator);
+		assert!(!standings[1].in_committee);
+	}
+
+	#[tokio::test]
+	async fn test_project_committee() {
+		let keys = keys(3);
+		let candidates = [
+			(keys[0].clone(), 1_000_000),
+			(keys[1].clone(), 5_000_000),
+			(keys[2].clone(), 20_000_000),
+		];
+		let provider = mocked(&candidates, &keys[0..2], &keys[0..1]);
+		let neo = NeoToken::new(Some(&provider));
+
+		let projection = neo.project_committee().await.unwrap();
+		assert_eq!(projection.committee, vec![keys[2].clone(), keys[1].clone()]);
+		assert_eq!(projection.validators, vec![keys[2].clone()]);
+		assert_eq!(projection.joining, vec![keys[2].clone()]);
+		assert_eq!(projection.leaving, vec![keys[0].clone()]);
+
+		// Without enough votes the standby committee stays in office.
+		let candidates = [(keys[2].clone(), 1_000_000), (keys[1].clone(), 1)];
+		let provider = mocked(&candidates, &keys[0..2], &keys[0..1]);
+		let neo = NeoToken::new(Some(&provider));
+		let projection = neo.project_committee().await.unwrap();
+		assert_eq!(projection.committee, keys[0..2].to_vec());
+		assert!(projection.joining.is_empty() && projection.leaving.is_empty());
+	}
+
+	#[test]
+	fn test_voter_reward() {
+		// 5 GAS per block, 21 members and 7 validators: the voters of a validator share
+		// 5 * 0.8 * 2 * 21 / 28 = 6 GAS per block.
+		let reward = NeoToken::<MockProvider>::voter_reward(500_000_000, 21, 7, true, 1_000, 10, 1);
+		assert_eq!(reward, 6_000_000);
+		let reward =
+			NeoToken::<MockProvider>::voter_reward(500_000_000, 21, 7, false, 1_000, 10, 1);
+		assert_eq!(reward, 3_000_000);
+		assert_eq!(NeoToken::<MockProvider>::voter_reward(500_000_000, 21, 7, false, 0, 10, 1), 0);
+	}
+
+	#[tokio::test]
+	async fn test_claim_gas() {
+		let accounts = vec![Account::create().unwrap(), Account::create().unwrap()];
+		let neo = NeoToken::<MockProvider>::new(None);
+
+		let builder = neo.claim_gas(&accounts).await.unwrap();
+		assert_eq!(builder.signers().len(), 2);
+		assert!(matches!(neo.claim_gas(&[]).await, Err(ContractError::InvalidArgError(_))));
+	}
+
+	#[tokio::test]
+	async fn test_latest_gas_per_vote_without_storage_entry() {
+		let candidate = keys(1).remove(0);
+		let (provider, mock) = Provider::mocked();
+		mock.push_response(MockResponse::Error(JsonRpcError {
+			code: -100,
+			message: "Unknown storage".to_string(),
+			data: None,
+		}));
+		mock.push_response(MockResponse::Error(JsonRpcError {
+			code: -104,
+			message: "Unknown storage item".to_string(),
+			data: None,
+		}));
+		mock.push("AQI=").unwrap();
+		let neo = NeoToken::new(Some(&provider));
+
+		assert_eq!(neo.get_latest_gas_per_vote(&candidate).await.unwrap(), 0x0201);
+		assert_eq!(neo.get_latest_gas_per_vote(&candidate).await.unwrap(), 0);
+		assert_eq!(neo.get_latest_gas_per_vote(&candidate).await.unwrap(), 0);
+
+		mock.push_response(MockResponse::Error(JsonRpcError {
+			code: -32603,
+			message: "Internal error".to_string(),
+			data: None,
+		}));
+		assert!(neo.get_latest_gas_per_vote(&candidate).await.is_err());
 	}
 }
@@ -2,9 +2,26 @@ use getset::{Getters, Setters};
 use neo::prelude::*;
 use primitive_types::H160;
 use reqwest::Url;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+/// A NEP-9 payment request, e.g. `neo:NZNovt...?asset=gas&amount=1.5&description=Coffee`.
+///
+/// Besides NEO and GAS the `asset` can be the script hash of any NEP-17 token, in which case the
+/// `amount` is checked against the decimals of the token when building the transfer, or of an
+/// NEP-11 token, whose `tokenId` is given in hex. `description` is shown to the payer and `data`
+/// is passed to the `transfer` method of the token.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let uri = NeoURI::from_uri("neo:NZNovtDJKFqKK8LGBfBUcnFDCC1xpu7wGe?asset=gas&amount=1.5")?
+///     .with_provider(&provider);
+/// let tx = uri.build_transfer_from(&account).await?;
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize, Getters, Setters)]
 pub struct NeoURI<'a, P: JsonRpcClient> {
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -22,61 +39,134 @@ pub struct NeoURI<'a, P: JsonRpcClient> {
 	#[serde(serialize_with = "serialize_script_hash_option")]
 	#[getset(get = "pub", set = "pub")]
 	token: Option<ScriptHash>,
+	/// The amount in token units, e.g. `1.5` GAS.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	#[getset(get = "pub", set = "pub")]
-	amount: Option<u64>,
+	amount: Option<Decimal>,
+	/// The id of the NEP-11 token to transfer.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[getset(get = "pub", set = "pub")]
+	token_id: Option<Bytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[getset(get = "pub", set = "pub")]
+	description: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[getset(get = "pub", set = "pub")]
+	data: Option<String>,
 	#[serde(skip)]
 	provider: Option<&'a Provider<P>>,
 }
 
 impl<'a, P: JsonRpcClient> NeoURI<'a, P> {
 	const NEO_SCHEME: &'static str = "neo";
-	const MIN_NEP9_URI_LENGTH: usize = 38;
 	const NEO_TOKEN_STRING: &'static str = "neo";
 	const GAS_TOKEN_STRING: &'static str = "gas";
 
+	const ASSET_PARAM: &'static str = "asset";
+	const AMOUNT_PARAM: &'static str = "amount";
+	const TOKEN_ID_PARAM: &'static str = "tokenId";
+	const DESCRIPTION_PARAM: &'static str = "description";
+	const DATA_PARAM: &'static str = "data";
+	/// Parameters with this prefix must be understood, as in BIP-21. Other unknown parameters
+	/// are ignored.
+	const REQUIRED_PREFIX: &'static str = "req-";
+
+	const NEO_DECIMALS: u32 = 0;
+	const GAS_DECIMALS: u32 = 8;
+	const MAX_TOKEN_ID_LENGTH: usize = 64;
+	const TRANSFER: &'static str = "transfer";
+
 	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
-		Self { uri: None, recipient: None, token: None, amount: None, provider }
+		Self {
+			uri: None,
+			recipient: None,
+			token: None,
+			amount: None,
+			token_id: None,
+			description: None,
+			data: None,
+			provider,
+		}
 	}
 
+	pub fn with_provider(mut self, provider: &'a Provider<P>) -> Self {
+		self.provider = Some(provider);
+		self
+	}
+
+	/// Parses and validates a NEP-9 URI.
 	pub fn from_uri(uri_string: &str) -> Result<Self, ContractError> {
-		let parts: Vec<&str> = uri_string.split(".unwrap()").collect();
-		let base = parts[0];
-		let query = if parts.len() > 1 { Some(parts[1]) } else { None };
-
-		let base_parts: Vec<&str> = base.split(":").collect();
-		if base_parts.len() != 2
-			|| base_parts[0] != Self::NEO_SCHEME
-			|| uri_string.len() < Self::MIN_NEP9_URI_LENGTH
-		{
-			return Err(ContractError::InvalidNeoName("Invalid NEP-9 URI".to_string()))
+		let uri = Url::parse(uri_string.trim())
+			.map_err(|e| ContractError::InvalidNeoUri(format!("Malformed URI: {}", e)))?;
+		if uri.scheme() != Self::NEO_SCHEME {
+			return Err(ContractError::InvalidNeoUri(format!(
+				"The scheme must be '{}', not '{}'",
+				Self::NEO_SCHEME,
+				uri.scheme()
+			)))
 		}
+		let recipient = H160::from_address(uri.path()).map_err(|_| {
+			ContractError::InvalidNeoUri(format!("Invalid recipient address '{}'", uri.path()))
+		})?;
 
 		let mut neo_uri = Self::new(None);
-		neo_uri.set_recipient(ScriptHash::from_address(base_parts[1]).ok());
-
-		if let Some(query_str) = query {
-			for part in query_str.split("&") {
-				let kv: Vec<&str> = part.split("=").collect();
-				if kv.len() != 2 {
-					return Err(ContractError::InvalidNeoName("Invalid query".to_string()))
-				}
+		neo_uri.recipient = Some(recipient);
 
-				match kv[0] {
-					"asset" if neo_uri.token().is_none() => {
-						&neo_uri.set_token(H160::from_str(kv[1]).ok());
-					},
-					"amount" if neo_uri.amount.is_none() => {
-						neo_uri.amount = Some(kv[1].parse().unwrap());
-					},
-					_ => {},
-				}
+		let mut seen = Vec::new();
+		for (key, value) in uri.query_pairs() {
+			if seen.contains(&key) {
+				return Err(ContractError::InvalidNeoUri(format!("Duplicate parameter '{}'", key)))
 			}
+			match key.as_ref() {
+				Self::ASSET_PARAM => neo_uri.token = Some(Self::parse_asset(&value)?),
+				Self::AMOUNT_PARAM => neo_uri.amount = Some(Self::parse_amount(&value)?),
+				Self::TOKEN_ID_PARAM => neo_uri.token_id = Some(Self::parse_token_id(&value)?),
+				Self::DESCRIPTION_PARAM => neo_uri.description = Some(value.to_string()),
+				Self::DATA_PARAM => neo_uri.data = Some(value.to_string()),
+				key if key.starts_with(Self::REQUIRED_PREFIX) =>
+					return Err(ContractError::InvalidNeoUri(format!(
+						"Unsupported required parameter '{}'",
+						key
+					))),
+				_ => {},
+			}
+			seen.push(key);
 		}
 
+		neo_uri.validate()?;
+		neo_uri.uri = Some(uri);
 		Ok(neo_uri)
 	}
 
+	/// Checks that the parameters form a payment request that can be fulfilled.
+	pub fn validate(&self) -> Result<(), ContractError> {
+		if self.recipient.is_none() {
+			return Err(ContractError::InvalidNeoUri("No recipient set".to_string()))
+		}
+		let token = match self.token {
+			Some(token) => token,
+			None if self.amount.is_some() || self.token_id.is_some() =>
+				return Err(ContractError::InvalidNeoUri(
+					"An amount or token id requires an asset".to_string(),
+				)),
+			None => return Ok(()),
+		};
+
+		if self.token_id.is_some() && (Self::is_neo_token(&token) || Self::is_gas_token(&token)) {
+			return Err(ContractError::InvalidNeoUri(
+				"NEO and GAS are not NEP-11 tokens and have no token ids".to_string(),
+			))
+		}
+		if let Some(amount) = self.amount {
+			if Self::is_neo_token(&token) {
+				Self::check_scale(&amount, Self::NEO_DECIMALS)?;
+			} else if Self::is_gas_token(&token) {
+				Self::check_scale(&amount, Self::GAS_DECIMALS)?;
+			}
+		}
+		Ok(())
+	}
+
 	// Getters
 
 	pub fn uri_string(&self) -> Option<String> {
@@ -87,124 +177,398 @@ impl<'a, P: JsonRpcClient> NeoURI<'a, P> {
 		self.recipient.as_ref().map(H160::to_address)
 	}
 
+	/// Returns `neo`, `gas` or the hex script hash of the token.
 	pub fn token_string(&self) -> Option<String> {
-		self.token.as_ref().map(|token| match token {
-			token if *token == NeoToken::<P>::new(None).script_hash() =>
-				Self::NEO_TOKEN_STRING.to_owned(),
-			token if *token == GasToken::<P>::new(None).script_hash() =>
-				Self::GAS_TOKEN_STRING.to_owned(),
-			_ => ScriptHashExtension::to_bs58_string(token),
-		})
+		self.token.as_ref().map(Self::asset_string)
 	}
 
 	// Builders
 
+	/// Builds the transfer the URI requests, signed by `sender` with the `CalledByEntry` scope.
+	///
+	/// NEP-17 amounts are converted with the decimals of the token, which are fetched for tokens
+	/// other than NEO and GAS. With a token id, the transfer is an NEP-11 transfer, of the whole
+	/// token if no amount is given.
 	pub async fn build_transfer_from(
 		&self,
 		sender: &Account,
 	) -> Result<TransactionBuilder<P>, ContractError> {
+		self.validate()?;
 		let recipient = self
 			.recipient
-			.ok_or(ContractError::InvalidStateError("Recipient not set".to_string()))
-			.unwrap();
-		let amount = self
-			.amount
-			.ok_or(ContractError::InvalidStateError("Amount not set".to_string()))
-			.unwrap();
+			.ok_or_else(|| ContractError::InvalidStateError("Recipient not set".to_string()))?;
 		let token_hash = self
 			.token
-			.ok_or(ContractError::InvalidStateError("Token not set".to_string()))
-			.unwrap();
+			.ok_or_else(|| ContractError::InvalidStateError("Token not set".to_string()))?;
+		let data = match &self.data {
+			Some(data) => ContractParameter::string(data.clone()),
+			None => ContractParameter::new(ContractParameterType::Any),
+		};
+
+		let mut builder = match &self.token_id {
+			Some(token_id) => {
+				let mut token = NftContract::new(&token_hash, self.provider);
+				let params = match self.amount {
+					Some(amount) => {
+						let decimals = token.get_decimals().await? as u32;
+						let fractions = Self::to_fractions(&amount, decimals)?;
+						vec![
+							sender.get_script_hash().into(),
+							recipient.into(),
+							ContractParameter::integer(fractions),
+							token_id.clone().into(),
+							data,
+						]
+					},
+					None => vec![recipient.into(), token_id.clone().into(), data],
+				};
+				token.invoke_function(Self::TRANSFER, params).await?
+			},
+			None => {
+				let amount = self.amount.ok_or_else(|| {
+					ContractError::InvalidStateError("Amount not set".to_string())
+				})?;
+				let mut token = FungibleTokenContract::new(&token_hash, self.provider);
+				let decimals = if Self::is_neo_token(&token_hash) {
+					Self::NEO_DECIMALS
+				} else if Self::is_gas_token(&token_hash) {
+					Self::GAS_DECIMALS
+				} else {
+					token.get_decimals().await? as u32
+				};
+				let fractions = Self::to_fractions(&amount, decimals)?;
+				let params = vec![
+					sender.get_script_hash().into(),
+					recipient.into(),
+					ContractParameter::integer(fractions),
+					data,
+				];
+				token.invoke_function(Self::TRANSFER, params).await?
+			},
+		};
 
-		let token = &mut FungibleTokenContract::new(&token_hash, self.provider);
+		let signer = AccountSigner::called_by_entry(sender)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?;
+		builder.set_signers(vec![signer.into()]);
+		Ok(builder)
+	}
 
-		// Validate amount precision
-		let amount_scale = (amount as f64).log10().floor() as u32 + 1; //amount.scale() as u8; //.scale();
+	// Helpers
 
-		if Self::is_neo_token(&token_hash) && amount_scale > 0 {
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"NEO does not support decimals".to_string(),
+	fn is_neo_token(token: &H160) -> bool {
+		token == &NeoToken::<P>::new(None).script_hash()
+	}
+
+	fn is_gas_token(token: &H160) -> bool {
+		token == &GasToken::<P>::new(None).script_hash()
+	}
+
+	fn asset_string(token: &H160) -> String {
+		if Self::is_neo_token(token) {
+			Self::NEO_TOKEN_STRING.to_owned()
+		} else if Self::is_gas_token(token) {
+			Self::GAS_TOKEN_STRING.to_owned()
+		} else {
+			hex::encode(token.as_bytes())
+		}
+	}
+
+	fn parse_asset(value: &str) -> Result<H160, ContractError> {
+		match value.to_lowercase().as_str() {
+			Self::NEO_TOKEN_STRING => Ok(NeoToken::<P>::new(None).script_hash()),
+			Self::GAS_TOKEN_STRING => Ok(GasToken::<P>::new(None).script_hash()),
+			hash => {
+				let hash = hash.strip_prefix("0x").unwrap_or(hash);
+				if hash.len() != 40 {
+					return Err(ContractError::InvalidNeoUri(format!(
+						"The asset '{}' is neither neo, gas nor a script hash",
+						value
+					)))
+				}
+				H160::from_str(hash).map_err(|_| {
+					ContractError::InvalidNeoUri(format!("Invalid asset script hash '{}'", value))
+				})
+			},
+		}
+	}
+
+	fn parse_amount(value: &str) -> Result<Decimal, ContractError> {
+		let amount = Decimal::from_str(value)
+			.map_err(|_| ContractError::InvalidNeoUri(format!("Invalid amount '{}'", value)))?;
+		if amount <= Decimal::ZERO {
+			return Err(ContractError::InvalidNeoUri(format!(
+				"The amount must be positive, not {}",
+				value
 			)))
 		}
+		Ok(amount.normalize())
+	}
 
-		if Self::is_gas_token(&token_hash)
-			&& amount_scale > GasToken::<P>::new(None).decimals().unwrap() as u32
-		{
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"Too many decimal places for GAS".to_string(),
+	fn parse_token_id(value: &str) -> Result<Bytes, ContractError> {
+		let token_id = hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(|_| {
+			ContractError::InvalidNeoUri(format!("Invalid hex token id '{}'", value))
+		})?;
+		if token_id.is_empty() || token_id.len() > Self::MAX_TOKEN_ID_LENGTH {
+			return Err(ContractError::InvalidNeoUri(format!(
+				"A token id has 1 to {} bytes, not {}",
+				Self::MAX_TOKEN_ID_LENGTH,
+				token_id.len()
 			)))
 		}
+		Ok(token_id)
+	}
 
-		let decimals = token.get_decimals().await.unwrap();
-		if amount_scale > decimals as u32 {
-			return Err(ContractError::from(ContractError::InvalidArgError(
-				"Too many decimal places for token".to_string(),
+	fn check_scale(amount: &Decimal, decimals: u32) -> Result<(), ContractError> {
+		if amount.normalize().scale() > decimals {
+			return Err(ContractError::InvalidNeoUri(format!(
+				"The amount {} has more than the {} decimals of the token",
+				amount, decimals
 			)))
 		}
+		Ok(())
+	}
 
-		let amt = token.to_fractions(amount, 0).unwrap();
-		token
-			.transfer_from_account(sender, &recipient, amt, None)
-			.await
-			.map_err(|e| ContractError::from(e))
+	fn to_fractions(amount: &Decimal, decimals: u32) -> Result<i64, ContractError> {
+		Self::check_scale(amount, decimals)?;
+		let factor = 10_i64.checked_pow(decimals).ok_or_else(|| {
+			ContractError::InvalidNeoUri(format!(
+				"A token with {} decimals is not supported",
+				decimals
+			))
+		})?;
+		Decimal::from(factor)
+			.checked_mul(*amount)
+			.and_then(|fractions| fractions.to_i64())
+			.ok_or_else(|| {
+				ContractError::InvalidNeoUri(format!("The amount {} is too large", amount))
+			})
 	}
 
-	// Helpers
+	// Setters
 
-	fn is_neo_token(token: &H160) -> bool {
-		token == &NeoToken::<P>::new(None).script_hash()
+	/// Sets the token from `neo`, `gas` or a hex script hash.
+	pub fn token_str(&mut self, token_str: &str) -> Result<(), ContractError> {
+		self.token = Some(Self::parse_asset(token_str)?);
+		Ok(())
 	}
 
-	fn is_gas_token(token: &H160) -> bool {
-		token == &GasToken::<P>::new(None).script_hash()
+	// URI builder
+
+	pub fn build_uri(&mut self) -> Result<Url, ContractError> {
+		self.validate()?;
+		let recipient = self
+			.recipient
+			.ok_or_else(|| ContractError::InvalidStateError("No recipient set".to_string()))?;
+
+		let mut uri = Url::parse(&format!("{}:{}", Self::NEO_SCHEME, recipient.to_address()))
+			.map_err(|e| ContractError::InvalidNeoUri(e.to_string()))?;
+		{
+			let mut query = uri.query_pairs_mut();
+			if let Some(token) = &self.token {
+				query.append_pair(Self::ASSET_PARAM, &Self::asset_string(token));
+			}
+			if let Some(amount) = &self.amount {
+				query.append_pair(Self::AMOUNT_PARAM, &amount.normalize().to_string());
+			}
+			if let Some(token_id) = &self.token_id {
+				query.append_pair(Self::TOKEN_ID_PARAM, &hex::encode(token_id));
+			}
+			if let Some(description) = &self.description {
+				query.append_pair(Self::DESCRIPTION_PARAM, description);
+			}
+			if let Some(data) = &self.data {
+				query.append_pair(Self::DATA_PARAM, data);
+			}
+		}
+		if uri.query() == Some("") {
+			uri.set_query(None);
+		}
+
+		self.uri = Some(uri.clone());
+		Ok(uri)
 	}
+}
 
-	// Setters
+#[cfg(test)]
+mod tests {
+	use primitive_types::H160;
+	use rust_decimal::Decimal;
 
-	pub fn token_str(&mut self, token_str: &str) {
-		self.token = match token_str {
-			Self::NEO_TOKEN_STRING => Some(NeoToken::new(self.provider).script_hash()),
-			Self::GAS_TOKEN_STRING => Some(GasToken::new(self.provider).script_hash()),
-			_ => Some(token_str.parse().unwrap()),
-		};
+	use neo::prelude::{
+		Account, AccountTrait, Bytes, CallFlags, ContractError, ContractParameter,
+		ContractParameterType, GasToken, Http, MockProvider, NeoToken, NeoURI, Provider,
+		ScriptBuilder, ScriptHashExtension, SmartContractTrait, StackItem,
+	};
+
+	fn recipient() -> H160 {
+		H160::repeat_byte(0x42)
 	}
 
-	// URI builder
+	fn uri(query: &str) -> String {
+		format!("neo:{}{}", recipient().to_address(), query)
+	}
 
-	fn build_query(&self) -> String {
-		let mut parts = Vec::new();
+	fn transfer_script(token: &H160, params: &[ContractParameter]) -> Bytes {
+		ScriptBuilder::new()
+			.contract_call(token, "transfer", params, Some(CallFlags::None))
+			.unwrap()
+			.to_bytes()
+	}
 
-		if let Some(token) = &self.token {
-			let token_str = match token {
-				token if *token == NeoToken::new(self.provider).script_hash() =>
-					Self::NEO_TOKEN_STRING.to_owned(),
-				token if *token == GasToken::new(self.provider).script_hash() =>
-					Self::GAS_TOKEN_STRING.to_owned(),
-				_ => ScriptHashExtension::to_bs58_string(token),
-			};
+	#[test]
+	fn test_from_uri() {
+		let neo_uri = NeoURI::<Http>::from_uri(&uri(
+			"?asset=gas&amount=1.50&description=Two%20coffees&data=order-42",
+		))
+		.unwrap();
+		assert_eq!(*neo_uri.recipient(), Some(recipient()));
+		assert_eq!(*neo_uri.token(), Some(GasToken::<Http>::new(None).script_hash()));
+		assert_eq!(*neo_uri.amount(), Some(Decimal::new(15, 1)));
+		assert_eq!(neo_uri.description().as_deref(), Some("Two coffees"));
+		assert_eq!(neo_uri.data().as_deref(), Some("order-42"));
+		assert_eq!(neo_uri.token_string().as_deref(), Some("gas"));
+
+		let neo_uri = NeoURI::<Http>::from_uri(&uri("")).unwrap();
+		assert_eq!(*neo_uri.token(), None);
+	}
 
-			parts.push(format!("asset={}", token_str));
-		}
+	#[test]
+	fn test_from_uri_with_custom_tokens() {
+		let token = "0x".to_string() + &"ab".repeat(20);
+		let neo_uri =
+			NeoURI::<Http>::from_uri(&uri(&format!("?asset={}&amount=0.000001", token))).unwrap();
+		assert_eq!(*neo_uri.token(), Some(H160::repeat_byte(0xab)));
+		assert_eq!(neo_uri.token_string(), Some("ab".repeat(20)));
+
+		let neo_uri =
+			NeoURI::<Http>::from_uri(&uri(&format!("?asset={}&tokenId=0102ff", token))).unwrap();
+		assert_eq!(*neo_uri.token_id(), Some(vec![0x01, 0x02, 0xff]));
+		assert_eq!(*neo_uri.amount(), None);
+	}
 
-		if let Some(amount) = &self.amount {
-			parts.push(format!("amount={}", amount));
+	#[test]
+	fn test_from_uri_rejects_malformed_uris() {
+		let invalid = [
+			"bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+			"neo:NotAnAddress".to_string(),
+			uri("?asset=btc&amount=1"),
+			uri("?asset=gas&amount=1&amount=2"),
+			uri("?asset=gas&amount=-1"),
+			uri("?asset=gas&amount=one"),
+			uri("?asset=gas&amount=0.123456789"),
+			uri("?asset=neo&amount=1.5"),
+			uri("?amount=1"),
+			uri("?asset=gas&tokenId=01"),
+			uri(&format!("?asset={}&tokenId=zz", "ab".repeat(20))),
+			uri("?asset=gas&req-expiry=100"),
+		];
+		for invalid in invalid {
+			assert!(
+				matches!(NeoURI::<Http>::from_uri(&invalid), Err(ContractError::InvalidNeoUri(_))),
+				"{} was accepted",
+				invalid
+			);
 		}
 
-		parts.join("&")
+		assert!(matches!(
+			NeoURI::<Http>::to_fractions(&Decimal::ONE, 19),
+			Err(ContractError::InvalidNeoUri(_))
+		));
+		assert_eq!(NeoURI::<Http>::to_fractions(&Decimal::ONE, 18).unwrap(), 10_i64.pow(18));
+
+		// Unknown optional parameters are ignored.
+		assert!(NeoURI::<Http>::from_uri(&uri("?asset=neo&amount=1&label=shop")).is_ok());
 	}
 
-	pub fn build_uri(&mut self) -> Result<Url, ContractError> {
-		let recipient = self
-			.recipient
-			.ok_or(ContractError::InvalidStateError("No recipient set".to_string()))
-			.unwrap();
+	#[test]
+	fn test_build_uri() {
+		let mut neo_uri = NeoURI::<Http>::new(None);
+		neo_uri.set_recipient(Some(recipient()));
+		neo_uri.set_token(Some(NeoToken::<Http>::new(None).script_hash()));
+		neo_uri.set_amount(Some(Decimal::new(10, 0)));
+		neo_uri.set_description(Some("Rent & fees".to_string()));
+
+		let built = neo_uri.build_uri().unwrap();
+		assert_eq!(built.as_str(), uri("?asset=neo&amount=10&description=Rent+%26+fees").as_str());
 
-		let base = format!("{}:{}", Self::NEO_SCHEME, recipient.to_address());
-		let query = self.build_query();
-		let uri_str = if query.is_empty() { base } else { format!("{}.unwrap(){}", base, query) };
+		let parsed = NeoURI::<Http>::from_uri(built.as_str()).unwrap();
+		assert_eq!(parsed.token(), neo_uri.token());
+		assert_eq!(parsed.amount(), neo_uri.amount());
+		assert_eq!(parsed.description(), neo_uri.description());
 
-		self.uri = Some(uri_str.parse().unwrap());
+		neo_uri.set_amount(Some(Decimal::new(15, 1)));
+		assert!(neo_uri.build_uri().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_build_nep17_transfer_with_token_decimals() {
+		let sender = Account::create().unwrap();
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocation(StackItem::Integer { value: 2 }).unwrap();
+		let neo_uri = NeoURI::<MockProvider>::from_uri(&uri(&format!(
+			"?asset={}&amount=1.25",
+			"ab".repeat(20)
+		)))
+		.unwrap()
+		.with_provider(&provider);
+
+		let builder = neo_uri.build_transfer_from(&sender).await.unwrap();
+		let params = [
+			sender.get_script_hash().into(),
+			recipient().into(),
+			ContractParameter::integer(125),
+			ContractParameter::new(ContractParameterType::Any),
+		];
+		assert_eq!(*builder.script(), Some(transfer_script(&H160::repeat_byte(0xab), &params)));
+		assert_eq!(builder.signers().len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_build_nep11_transfer() {
+		let sender = Account::create().unwrap();
+		let token = H160::repeat_byte(0xcd);
+		let query = format!("?asset={}&tokenId=0102&data=gift", "cd".repeat(20));
+
+		// Without an amount the whole token is transferred and no decimals are fetched.
+		let neo_uri = NeoURI::<MockProvider>::from_uri(&uri(&query)).unwrap();
+		let builder = neo_uri.build_transfer_from(&sender).await.unwrap();
+		let params = [
+			recipient().into(),
+			vec![0x01u8, 0x02].into(),
+			ContractParameter::string("gift".to_string()),
+		];
+		assert_eq!(*builder.script(), Some(transfer_script(&token, &params)));
+
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocation(StackItem::Integer { value: 1 }).unwrap();
+		let neo_uri = NeoURI::<MockProvider>::from_uri(&uri(&format!("{}&amount=0.5", query)))
+			.unwrap()
+			.with_provider(&provider);
+		let builder = neo_uri.build_transfer_from(&sender).await.unwrap();
+		let params = [
+			sender.get_script_hash().into(),
+			recipient().into(),
+			ContractParameter::integer(5),
+			vec![0x01u8, 0x02].into(),
+			ContractParameter::string("gift".to_string()),
+		];
+		assert_eq!(*builder.script(), Some(transfer_script(&token, &params)));
+	}
 
-		Ok(self.uri.clone().unwrap())
+	#[tokio::test]
+	async fn test_build_transfer_rejects_fractions_of_indivisible_tokens() {
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocation(StackItem::Integer { value: 0 }).unwrap();
+		let neo_uri = NeoURI::<MockProvider>::from_uri(&uri(&format!(
+			"?asset={}&tokenId=01&amount=0.5",
+			"cd".repeat(20)
+		)))
+		.unwrap()
+		.with_provider(&provider);
+
+		assert!(matches!(
+			neo_uri.build_transfer_from(&Account::create().unwrap()).await,
+			Err(ContractError::InvalidNeoUri(_))
+		));
 	}
 }
@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use primitive_types::H160;
+use serde::{Deserialize, Serialize};
+
+use neo::prelude::*;
+
+/// The native `Notary` contract of the P2P notary service.
+///
+/// Fees of fallback transactions are paid from the GAS its depositors lock in this contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotaryContract<'a, P: JsonRpcClient> {
+	#[serde(deserialize_with = "deserialize_script_hash")]
+	#[serde(serialize_with = "serialize_script_hash")]
+	script_hash: ScriptHash,
+	#[serde(skip)]
+	provider: Option<&'a Provider<P>>,
+}
+
+impl<'a, P: JsonRpcClient> NotaryContract<'a, P> {
+	pub const NAME: &'static str = "Notary";
+
+	pub fn new(provider: Option<&'a Provider<P>>) -> Self {
+		Self { script_hash: *NOTARY_CONTRACT_HASH, provider }
+	}
+
+	pub async fn balance_of(&self, account: &H160) -> Result<i64, ContractError> {
+		self.call_function_returning_i64("balanceOf", vec![account.into()]).await
+	}
+
+	/// The height until which the deposit of `account` is locked.
+	pub async fn expiration_of(&self, account: &H160) -> Result<u32, ContractError> {
+		Ok(self.call_function_returning_int("expirationOf", vec![account.into()]).await? as u32)
+	}
+
+	pub async fn get_max_not_valid_before_delta(&self) -> Result<u32, ContractError> {
+		Ok(self.call_function_returning_int("getMaxNotValidBeforeDelta", vec![]).await? as u32)
+	}
+
+	pub async fn get_notary_service_fee_per_key(&self) -> Result<i64, ContractError> {
+		self.call_function_returning_i64("getNotaryServiceFeePerKey", vec![]).await
+	}
+
+	/// Returns the GAS `account` still has to deposit so that the notary service accepts
+	/// `fallback`, or zero if its deposit already covers it.
+	///
+	/// The deposit must cover the fees of the fallback transaction, and a first deposit must be
+	/// at least twice the notary service fee per key. Whether the deposit is locked long enough
+	/// is checked by `check_deposit_expiration`.
+	pub async fn required_deposit<T: JsonRpcClient + 'static>(
+		&self,
+		account: &H160,
+		fallback: &Transaction<T>,
+	) -> Result<i64, ContractError> {
+		let balance = self.balance_of(account).await?;
+		let fee_per_key = self.get_notary_service_fee_per_key().await?;
+		Ok(Self::deposit_shortfall(balance, fee_per_key, fallback))
+	}
+
+	/// Fails if the deposit of `account` is unlocked before `fallback` expires.
+	pub async fn check_deposit_expiration<T: JsonRpcClient + 'static>(
+		&self,
+		account: &H160,
+		fallback: &Transaction<T>,
+	) -> Result<(), ContractError> {
+		let till = self.expiration_of(account).await?;
+		if till < fallback.valid_until_block as u32 {
+			return Err(ContractError::InvalidStateError(format!(
+				"The notary deposit is locked until block {} but the fallback transaction is valid until block {}",
+				till, fallback.valid_until_block
+			)))
+		}
+		Ok(())
+	}
+
+	/// The offline part of `required_deposit`, for a known deposit `balance`.
+	pub fn deposit_shortfall<T: JsonRpcClient + 'static>(
+		balance: i64,
+		fee_per_key: i64,
+		fallback: &Transaction<T>,
+	) -> i64 {
+		let mut required = fallback.sys_fee + fallback.net_fee;
+		if balance == 0 {
+			required = required.max(2 * fee_per_key);
+		}
+		(required - balance).max(0)
+	}
+
+	// State modifying methods
+
+	/// Deposits GAS of `from` to the notary contract, locked until block `till`.
+	///
+	/// The deposit is made for `to`, or for `from` if no beneficiary is given. It is a GAS
+	/// transfer to the notary contract, so `from` has to sign the transaction.
+	pub async fn deposit(
+		&self,
+		from: &H160,
+		amount: i64,
+		to: Option<&H160>,
+		till: u32,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		if amount <= 0 {
+			return Err(ContractError::InvalidArgError(
+				"The deposit amount must be greater than 0.".to_string(),
+			))
+		}
+
+		let to = match to {
+			Some(to) => to.into(),
+			None => ContractParameter::new(ContractParameterType::Any),
+		};
+		let data = ContractParameter::array(vec![to, till.into()]);
+		let script = ScriptBuilder::new()
+			.contract_call(
+				&GAS_TOKEN_HASH,
+				"transfer",
+				&[from.into(), self.script_hash.into(), ContractParameter::integer(amount), data],
+				Some(CallFlags::All),
+			)
+			.map_err(|e| ContractError::RuntimeError(e.to_string()))?
+			.to_bytes();
+
+		let mut builder = TransactionBuilder::new();
+		builder.set_script(script);
+		Ok(builder)
+	}
+
+	/// Extends the lock of the deposit of `account` until block `till`.
+	pub async fn lock_deposit_until(
+		&self,
+		account: &H160,
+		till: u32,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		self.invoke_function("lockDepositUntil", vec![account.into(), till.into()])
+			.await
+	}
+
+	/// Withdraws the expired deposit of `from` to `to`.
+	pub async fn withdraw(
+		&self,
+		from: &H160,
+		to: &H160,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		self.invoke_function("withdraw", vec![from.into(), to.into()]).await
+	}
+
+	pub async fn set_max_not_valid_before_delta(
+		&self,
+		value: u32,
+	) -> Result<TransactionBuilder<P>, ContractError> {
+		self.invoke_function("setMaxNotValidBeforeDelta", vec![value.into()]).await
+	}
+
+	async fn call_function_returning_i64(
+		&self,
+		function: &str,
+		params: Vec<ContractParameter>,
+	) -> Result<i64, ContractError> {
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
+
+		output.stack[0]
+			.as_int()
+			.ok_or(ContractError::UnexpectedReturnType("Int".to_string()))
+	}
+}
+
+#[async_trait]
+impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for NotaryContract<'a, P> {
+	type P = P;
+
+	fn script_hash(&self) -> H160 {
+		self.script_hash
+	}
+
+	fn set_script_hash(&mut self, script_hash: H160) {
+		self.script_hash = script_hash;
+	}
+
+	fn provider(&self) -> Option<&Provider<P>> {
+		self.provider
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{Http, NotaryContract, Transaction};
+
+	fn fallback(net_fee: i64) -> Transaction<Http> {
+		let mut tx = Transaction::new();
+		tx.net_fee = net_fee;
+		tx
+	}
+
+	#[test]
+	fn test_first_deposit_covers_twice_the_fee_per_key() {
+		let shortfall = NotaryContract::<Http>::deposit_shortfall(0, 10_000_000, &fallback(1000));
+		assert_eq!(shortfall, 20_000_000);
+
+		let shortfall =
+			NotaryContract::<Http>::deposit_shortfall(0, 10_000_000, &fallback(30_000_000));
+		assert_eq!(shortfall, 30_000_000);
+	}
+
+	#[test]
+	fn test_deposit_shortfall() {
+		let shortfall =
+			NotaryContract::<Http>::deposit_shortfall(5_000_000, 10_000_000, &fallback(8_000_000));
+		assert_eq!(shortfall, 3_000_000);
+
+		let shortfall =
+			NotaryContract::<Http>::deposit_shortfall(9_000_000, 10_000_000, &fallback(8_000_000));
+		assert_eq!(shortfall, 0);
+	}
+}
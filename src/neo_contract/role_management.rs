@@ -26,7 +26,7 @@ impl<'a, P: JsonRpcClient> RoleManagement<'a, P> {
 		role: Role,
 		block_index: i32,
 	) -> Result<Vec<Secp256r1PublicKey>, ContractError> {
-		self.check_block_index_validity(block_index).await.unwrap();
+		self.check_block_index_validity(block_index).await?;
 
 		let invocation = self
 			.call_invoke_function(
@@ -34,30 +34,46 @@ impl<'a, P: JsonRpcClient> RoleManagement<'a, P> {
 				vec![role.into(), block_index.into()],
 				vec![],
 			)
-			.await
-			.unwrap();
+			.await?;
+		self.throw_if_fault_state(&invocation)?;
 
-		let designated = invocation.stack[0]
+		invocation.stack[0]
 			.as_array()
-			.unwrap()
+			.ok_or(ContractError::UnexpectedReturnType("Array".to_string()))?
 			.into_iter()
 			.map(|item| {
-				Secp256r1PublicKey::from_bytes(item.as_bytes().unwrap().as_slice()).unwrap()
+				item.as_bytes()
+					.and_then(|bytes| Secp256r1PublicKey::from_bytes(&bytes).ok())
+					.ok_or(ContractError::UnexpectedReturnType("PublicKey".to_string()))
 			})
-			.collect();
+			.collect()
+	}
 
-		Ok(designated)
+	/// Checks that `state_root` is signed by the state validators designated for its block.
+	pub async fn verify_state_root(&self, state_root: &StateRoot) -> Result<(), ContractError> {
+		let provider = self
+			.provider
+			.ok_or(ContractError::InvalidStateError("No provider set".to_string()))?;
+		let validators = self
+			.get_designated_by_role(Role::StateValidator, state_root.index as i32)
+			.await?;
+		state_root
+			.verify_witness(&validators, provider.network().await)
+			.map_err(|e| ContractError::InvalidStateError(e.to_string()))
 	}
 
 	async fn check_block_index_validity(&self, block_index: i32) -> Result<(), ContractError> {
 		if block_index < 0 {
-			return Err(ContractError::InvalidNeoName("Block index must be positive".to_string()))
+			return Err(ContractError::InvalidBlockIndex("Block index must be positive".to_string()))
 		}
 
-		let current_block_count = self.provider.unwrap().get_block_count().await.unwrap();
+		let provider = self
+			.provider
+			.ok_or(ContractError::InvalidStateError("No provider set".to_string()))?;
+		let current_block_count = provider.get_block_count().await?;
 
 		if block_index > current_block_count as i32 {
-			return Err(ContractError::InvalidNeoName(format!(
+			return Err(ContractError::InvalidBlockIndex(format!(
 				"Block index {} exceeds current block count {}",
 				block_index, current_block_count
 			)))
@@ -100,19 +116,22 @@ impl<'a, P: JsonRpcClient> SmartContractTrait<'a> for RoleManagement<'a, P> {
 	}
 }
 
+/// The node roles the `RoleManagement` contract designates.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Role {
-	Oracle,
-	Policy,
-	Validator,
-	StateRootValidator,
-	PriceFeedOracle,
-	FeeCollector,
-	ComplianceOfficer,
+	StateValidator = 0x04,
+	Oracle = 0x08,
+	NeoFSAlphabetNode = 0x10,
+	P2PNotary = 0x20,
 }
 
 impl Role {
+	/// The former name of [`Role::StateValidator`].
+	#[deprecated(note = "Use Role::StateValidator")]
+	#[allow(non_upper_case_globals)]
+	pub const StateRootValidator: Role = Role::StateValidator;
+
 	pub const fn byte(self) -> u8 {
 		self as u8
 	}
@@ -129,3 +148,49 @@ impl Into<ContractParameter> for Role {
 		ContractParameter::integer(self.byte() as i64)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		ContractError, KeyPair, MockProvider, Provider, Role, RoleManagement, StackItem,
+	};
+
+	#[tokio::test]
+	async fn test_get_designated_by_role() {
+		let key = KeyPair::new_random().public_key();
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocation(StackItem::Array {
+			value: vec![StackItem::ByteString { value: key.get_encoded_compressed_hex() }],
+		})
+		.unwrap();
+		mock.push(10u32).unwrap();
+		let role_management = RoleManagement::new(Some(&provider));
+
+		assert_eq!(
+			role_management.get_designated_by_role(Role::Oracle, 10).await.unwrap(),
+			vec![key]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_get_designated_by_role_checks_block_index() {
+		let (provider, mock) = Provider::mocked();
+		mock.push(10u32).unwrap();
+		let role_management = RoleManagement::new(Some(&provider));
+
+		assert!(matches!(
+			role_management.get_designated_by_role(Role::Oracle, -1).await,
+			Err(ContractError::InvalidBlockIndex(_))
+		));
+		assert!(matches!(
+			role_management.get_designated_by_role(Role::Oracle, 11).await,
+			Err(ContractError::InvalidBlockIndex(_))
+		));
+		assert!(matches!(
+			RoleManagement::<MockProvider>::new(None)
+				.get_designated_by_role(Role::Oracle, 1)
+				.await,
+			Err(ContractError::InvalidStateError(_))
+		));
+	}
+}
@@ -32,13 +32,12 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 
 	async fn tokens_of(&mut self, owner: H160) -> Result<NeoIterator<Bytes, P>, ContractError> {
 		let mapper_fn = Arc::new(|item: StackItem| item.as_bytes().unwrap());
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS_OF,
-				vec![owner.into()],
-				mapper_fn,
-			)
-			.await)
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS_OF,
+			vec![owner.into()],
+			mapper_fn,
+		)
+		.await
 	}
 
 	// Non-divisible NFT methods
@@ -254,13 +253,12 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	) -> Result<NeoIterator<Address, P>, ContractError> {
 		self.throw_if_non_divisible_nft().await.unwrap();
 
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
-				vec![token_id.into()],
-				Arc::new(|item: StackItem| item.as_address().unwrap()),
-			)
-			.await)
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::OWNER_OF,
+			vec![token_id.into()],
+			Arc::new(|item: StackItem| item.as_address().unwrap()),
+		)
+		.await
 	}
 
 	async fn throw_if_non_divisible_nft(&mut self) -> Result<(), ContractError> {
@@ -290,13 +288,12 @@ pub trait NonFungibleTokenTrait<'a, P: JsonRpcClient>: TokenTrait<'a, P> + Send
 	// Optional methods
 
 	async fn tokens(&mut self) -> Result<NeoIterator<Bytes, P>, ContractError> {
-		Ok(self
-			.call_function_returning_iterator(
-				<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS,
-				vec![],
-				Arc::new(|item: StackItem| item.as_bytes().unwrap()),
-			)
-			.await)
+		self.call_function_returning_iterator(
+			<NftContract<P> as NonFungibleTokenTrait<P>>::TOKENS,
+			vec![],
+			Arc::new(|item: StackItem| item.as_bytes().unwrap()),
+		)
+		.await
 	}
 
 	async fn properties(
@@ -60,8 +60,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<String, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_string() {
@@ -75,8 +75,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<i32, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_int() {
@@ -90,8 +90,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<bool, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = output.stack[0].clone();
 		match item.as_bool() {
@@ -126,7 +126,7 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 
 	fn throw_if_fault_state(&self, output: &InvocationResult) -> Result<(), ContractError> {
 		if output.has_state_fault() {
-			Err(ContractError::UnexpectedReturnType(output.exception.clone().unwrap()))
+			Err(ContractError::UnexpectedReturnType(output.exception.clone().unwrap_or_default()))
 		} else {
 			Ok(())
 		}
@@ -138,8 +138,8 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 	) -> Result<H160, ContractError> {
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
 		let item = &output.stack[0];
 		item.as_bytes()
@@ -153,22 +153,23 @@ pub trait SmartContractTrait<'a>: Send + Sync {
 		function: &str,
 		params: Vec<ContractParameter>,
 		mapper: Arc<dyn Fn(StackItem) -> U + Send + Sync>,
-	) -> NeoIterator<U, Self::P>
+	) -> Result<NeoIterator<U, Self::P>, ContractError>
 	where
 		U: Send + Sync, // Adding this bound if necessary
 	{
-		let output = self.call_invoke_function(function, params, vec![]).await.unwrap();
-		self.throw_if_fault_state(&output).unwrap();
+		let output = self.call_invoke_function(function, params, vec![]).await?;
+		self.throw_if_fault_state(&output)?;
 
-		let item = &output.stack[0];
-		let StackItem::InteropInterface { id, interface: _ } = item else { panic!("") };
+		let Some(StackItem::InteropInterface { id, interface: _ }) = output.stack.first() else {
+			return Err(ContractError::UnexpectedReturnType("InteropInterface".to_string()))
+		};
 
-		let session_id = output
-			.session_id
-			.ok_or(ContractError::InvalidNeoNameServiceRoot("No session ID".to_string()))
-			.unwrap();
+		// Iterators are only returned by nodes with sessions enabled
+		let session_id = output.session_id.clone().ok_or_else(|| {
+			ContractError::InvalidStateError("The node returned no session id".to_string())
+		})?;
 
-		NeoIterator::new(session_id, id.clone(), mapper, None)
+		Ok(NeoIterator::new(session_id, id.clone(), mapper, self.provider()))
 	}
 
 	async fn call_function_and_unwrap_iterator<U>(
@@ -104,26 +104,14 @@ pub trait TokenTrait<'a, P: JsonRpcClient>: SmartContractTrait<'a, P = P> {
 		}
 	}
 
-	async fn resolve_nns_text_record(&self, name: &NNSName) -> Result<H160, ContractError>;
-	// {
-	// 	let req = {
-	// 		self.provider()
-	// 			.unwrap()
-	// 			.invoke_function(
-	// 				&NeoNameService::new().script_hash(),
-	// 				"resolve".to_string(),
-	// 				vec![
-	// 					ContractParameter::from(name.name()),
-	// 					ContractParameter::from(RecordType::TXT.byte_repr()),
-	// 				],
-	// 				(),
-	// 			)
-	// 			.clone()
-	// 	};
-	//
-	// 	let address = req.await.unwrap().stack.first().unwrap().clone();
-	//
-	//
-	// 	Ok(H160::from_slice(&address.as_bytes().unwrap()))
-	// }
+	/// Resolves the address an NNS name points to with its TXT record.
+	async fn resolve_nns_text_record(&self, name: &NNSName) -> Result<H160, ContractError> {
+		let provider = self.provider().ok_or_else(|| {
+			ContractError::RuntimeError("A provider is required to resolve NNS names".to_string())
+		})?;
+		NnsMiddleware::new(provider)
+			.resolve_name(name)
+			.await
+			.map_err(|e| ContractError::UnresolvableDomainName(e.to_string()))
+	}
 }
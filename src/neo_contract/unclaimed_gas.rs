@@ -0,0 +1,162 @@
+use neo::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The GAS generated per block from `index` on, as set by the committee with `setGasPerBlock`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GasPerBlockRecord {
+	pub index: u32,
+	/// The GAS fractions generated per block.
+	pub gas_per_block: i64,
+}
+
+impl GasPerBlockRecord {
+	pub fn new(index: u32, gas_per_block: i64) -> Self {
+		Self { index, gas_per_block }
+	}
+}
+
+/// Calculates unclaimed GAS locally, the way the `unclaimedGas` method of the NEO contract does.
+///
+/// The reward of a NEO holder consists of its share of [`Self::NEO_HOLDER_REWARD_RATIO`] percent
+/// of the GAS generated since the balance last changed, and the reward for the votes of the
+/// balance, which accumulates per committee member as GAS per vote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnclaimedGasCalculator {
+	records: Vec<GasPerBlockRecord>,
+}
+
+impl UnclaimedGasCalculator {
+	/// The share of the GAS generated per block that goes to the NEO holders, in percent.
+	pub const NEO_HOLDER_REWARD_RATIO: i128 = 10;
+	/// The GAS per vote is scaled by this factor to keep its precision.
+	pub const GAS_PER_VOTE_FACTOR: i128 = 100_000_000;
+	/// The GAS generated per block since the genesis block unless changed by the committee.
+	pub const INITIAL_GAS_PER_BLOCK: i64 = 500_000_000;
+
+	/// Creates a calculator from the GAS per block history of the network. The records do not
+	/// have to be sorted.
+	pub fn new(mut records: Vec<GasPerBlockRecord>) -> Result<Self, ContractError> {
+		records.sort_by_key(|record| record.index);
+		records.dedup_by_key(|record| record.index);
+		match records.first() {
+			Some(first) if first.index == 0 => Ok(Self { records }),
+			_ => Err(ContractError::InvalidArgError(
+				"The GAS per block history must start at block 0".to_string(),
+			)),
+		}
+	}
+
+	pub fn records(&self) -> &[GasPerBlockRecord] {
+		&self.records
+	}
+
+	/// Returns the GAS fractions the holder of `balance` NEO since `balance_height` can claim when
+	/// claiming in the block `end`, which is the current block count.
+	pub fn holder_reward(&self, balance: i64, balance_height: u32, end: u32) -> i64 {
+		if balance <= 0 || balance_height >= end {
+			return 0
+		}
+
+		let mut sum: i128 = 0;
+		let mut end = end;
+		for record in self.records.iter().rev().skip_while(|record| record.index >= end) {
+			if record.index > balance_height {
+				sum += record.gas_per_block as i128 * (end - record.index) as i128;
+				end = record.index;
+			} else {
+				sum += record.gas_per_block as i128 * (end - balance_height) as i128;
+				break
+			}
+		}
+
+		let reward = balance as i128 * sum * Self::NEO_HOLDER_REWARD_RATIO
+			/ 100
+			/ NeoToken::<Http>::TOTAL_SUPPLY as i128;
+		reward.min(i64::MAX as i128) as i64
+	}
+
+	/// Returns the GAS fractions earned by voting with `balance` NEO, from the GAS per vote of the
+	/// candidate when the vote or balance last changed and its latest GAS per vote.
+	pub fn voter_reward(balance: i64, last_gas_per_vote: i64, latest_gas_per_vote: i64) -> i64 {
+		if balance <= 0 || latest_gas_per_vote <= last_gas_per_vote {
+			return 0
+		}
+		let reward = balance as i128 * (latest_gas_per_vote - last_gas_per_vote) as i128
+			/ Self::GAS_PER_VOTE_FACTOR;
+		reward.min(i64::MAX as i128) as i64
+	}
+
+	/// Returns the GAS fractions the account can claim in the block `end`.
+	///
+	/// `latest_gas_per_vote` is the GAS per vote of the candidate the account votes for, and is
+	/// ignored if it does not vote.
+	pub fn calculate(
+		&self,
+		state: &AccountState,
+		end: u32,
+		latest_gas_per_vote: Option<i64>,
+	) -> i64 {
+		let balance_height = state.balance_height.unwrap_or_default().max(0) as u32;
+		if balance_height >= end {
+			return 0
+		}
+		let holder_reward = self.holder_reward(state.balance, balance_height, end);
+		let voter_reward = match (&state.public_key, latest_gas_per_vote) {
+			(Some(_), Some(latest)) => Self::voter_reward(
+				state.balance,
+				state.last_gas_per_vote.unwrap_or_default(),
+				latest,
+			),
+			_ => 0,
+		};
+		holder_reward.saturating_add(voter_reward)
+	}
+}
+
+impl Default for UnclaimedGasCalculator {
+	/// The history of MainNet and TestNet, where the GAS per block has not been changed.
+	fn default() -> Self {
+		Self { records: vec![GasPerBlockRecord::new(0, Self::INITIAL_GAS_PER_BLOCK)] }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{AccountState, GasPerBlockRecord, UnclaimedGasCalculator};
+
+	#[test]
+	fn test_holder_reward() {
+		let calculator = UnclaimedGasCalculator::default();
+		// 100 NEO over 1000 blocks earn 100 / 100M of 10% of 5000 GAS.
+		assert_eq!(calculator.holder_reward(100, 10, 1010), 50_000);
+		assert_eq!(calculator.holder_reward(100, 1010, 1010), 0);
+		assert_eq!(calculator.holder_reward(0, 10, 1010), 0);
+	}
+
+	#[test]
+	fn test_holder_reward_with_changed_gas_per_block() {
+		let calculator = UnclaimedGasCalculator::new(vec![
+			GasPerBlockRecord::new(500, 100_000_000),
+			GasPerBlockRecord::new(0, 500_000_000),
+			GasPerBlockRecord::new(2000, 0),
+		])
+		.unwrap();
+		// 490 blocks at 5 GAS and 510 blocks at 1 GAS; the record at 2000 is not reached yet.
+		let sum = 490 * 500_000_000_i64 + 510 * 100_000_000;
+		assert_eq!(calculator.holder_reward(1_000_000, 10, 1010), sum / 1000);
+
+		assert!(UnclaimedGasCalculator::new(vec![GasPerBlockRecord::new(1, 1)]).is_err());
+	}
+
+	#[test]
+	fn test_calculate() {
+		let calculator = UnclaimedGasCalculator::default();
+		let mut state = AccountState::with_no_vote(100, 10);
+		assert_eq!(calculator.calculate(&state, 1010, Some(1_000_000_000)), 50_000);
+
+		state.public_key = Some(neo::prelude::KeyPair::new_random().public_key());
+		state.last_gas_per_vote = Some(400_000_000);
+		// 100 NEO * 6 GAS per vote, scaled by 10^8.
+		assert_eq!(calculator.calculate(&state, 1010, Some(1_000_000_000)), 50_000 + 600);
+	}
+}
@@ -30,6 +30,28 @@ pub enum Nep2Error {
 	InvalidPassphrase(String),
 	#[error("Invalid format: {0}")]
 	InvalidFormat(String),
+	#[error("Invalid scrypt parameters: {0}")]
+	InvalidScryptParams(String),
+	#[error("Invalid private key: {0}")]
+	InvalidPrivateKey(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VanityError {
+	#[error("Invalid pattern: {0}")]
+	InvalidPattern(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ShamirError {
+	#[error("Invalid parameters: {0}")]
+	InvalidParameters(String),
+	#[error("Invalid share: {0}")]
+	InvalidShare(String),
+	#[error("Not enough shares: got {0}, need {1}")]
+	NotEnoughShares(usize, u8),
+	#[error("The shares belong to different splits")]
+	MismatchedShares,
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Hash, Clone)]
@@ -1,93 +1,169 @@
-use crypto::{
-	digest::Digest,
-	hmac::Hmac,
-	mac::Mac,
-	ripemd160::Ripemd160,
-	sha2::{Sha256, Sha512},
-};
-use rustc_serialize::hex::FromHex;
+use std::io;
+
+use hmac::{Hmac, Mac};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Keccak256;
 
 pub trait HashableForVec {
-	fn hash256(&self) -> Vec<u8>;
-	fn ripemd160(&self) -> Vec<u8>;
-	fn sha256_ripemd160(&self) -> Vec<u8>;
-	fn hmac_sha512(&self, key: &[u8]) -> Vec<u8>;
+	/// Returns the SHA-256 hash.
+	fn hash256(&self) -> [u8; 32];
+	/// Returns the RIPEMD-160 hash.
+	fn ripemd160(&self) -> [u8; 20];
+	/// Returns the RIPEMD-160 hash of the SHA-256 hash, as used for script hashes.
+	fn sha256_ripemd160(&self) -> [u8; 20];
+	/// Returns the HMAC-SHA512 with `key`.
+	fn hmac_sha512(&self, key: &[u8]) -> [u8; 64];
+	/// Returns the Keccak-256 hash, as computed by `CryptoLib.keccak256`.
+	fn keccak256(&self) -> [u8; 32];
+	/// Returns the 32-bit MurmurHash3 (x86) with `seed`, as computed by `CryptoLib.murmur32`,
+	/// which returns it as 4 little-endian bytes.
+	fn murmur32(&self, seed: u32) -> u32;
 }
 
 impl HashableForVec for [u8] {
-	fn hash256(&self) -> Vec<u8> {
-		let mut hasher = Sha256::new();
-		hasher.input(self);
-		let mut res = vec![0u8; 32];
-		hasher.result(&mut res);
-		res
+	fn hash256(&self) -> [u8; 32] {
+		Sha256::digest(self).into()
+	}
+
+	fn ripemd160(&self) -> [u8; 20] {
+		Ripemd160::digest(self).into()
 	}
 
-	fn ripemd160(&self) -> Vec<u8> {
-		let mut hasher = Ripemd160::new();
-		hasher.input(self);
-		let mut res = vec![0u8; 20];
-		hasher.result(&mut res);
+	fn sha256_ripemd160(&self) -> [u8; 20] {
+		Ripemd160::digest(Sha256::digest(self)).into()
+	}
 
-		res
+	fn hmac_sha512(&self, key: &[u8]) -> [u8; 64] {
+		let mut hmac =
+			<Hmac<Sha512> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+		hmac.update(self);
+		let mut code = [0u8; 64];
+		code.copy_from_slice(&hmac.finalize().into_bytes());
+		code
 	}
 
-	fn sha256_ripemd160(&self) -> Vec<u8> {
-		let mut sha256 = Sha256::new();
-		sha256.input(self);
-		let mut res = vec![0u8; 32];
-		sha256.result(&mut res);
-		let mut hasher = Ripemd160::new();
-		hasher.input(&res);
-		let mut res = vec![0u8; 20];
-		hasher.result(&mut res);
-		res
+	fn keccak256(&self) -> [u8; 32] {
+		Keccak256::digest(self).into()
 	}
 
-	fn hmac_sha512(&self, key: &[u8]) -> Vec<u8> {
-		let mut hmac = Hmac::new(Sha512::new(), key);
+	fn murmur32(&self, seed: u32) -> u32 {
+		const C1: u32 = 0xcc9e2d51;
+		const C2: u32 = 0x1b873593;
+		let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+		let mut hash = seed;
+		let mut blocks = self.chunks_exact(4);
+		for block in &mut blocks {
+			hash ^= mix(u32::from_le_bytes(block.try_into().unwrap()));
+			hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+		}
+		let tail = blocks.remainder();
+		if !tail.is_empty() {
+			let mut k = [0u8; 4];
+			k[..tail.len()].copy_from_slice(tail);
+			hash ^= mix(u32::from_le_bytes(k));
+		}
 
-		hmac.input(self);
-		let res = hmac.result();
-		res.code().to_vec()
+		hash ^= self.len() as u32;
+		hash ^= hash >> 16;
+		hash = hash.wrapping_mul(0x85ebca6b);
+		hash ^= hash >> 13;
+		hash = hash.wrapping_mul(0xc2b2ae35);
+		hash ^ (hash >> 16)
 	}
 }
 
 impl HashableForVec for Vec<u8> {
-	fn hash256(&self) -> Vec<u8> {
-		let mut hasher = Sha256::new();
-		hasher.input(self);
-		let mut res = vec![0u8; 32];
-		hasher.result(&mut res);
-		res
+	fn hash256(&self) -> [u8; 32] {
+		self.as_slice().hash256()
+	}
+
+	fn ripemd160(&self) -> [u8; 20] {
+		self.as_slice().ripemd160()
+	}
+
+	fn sha256_ripemd160(&self) -> [u8; 20] {
+		self.as_slice().sha256_ripemd160()
+	}
+
+	fn hmac_sha512(&self, key: &[u8]) -> [u8; 64] {
+		self.as_slice().hmac_sha512(key)
 	}
 
-	fn ripemd160(&self) -> Vec<u8> {
-		let mut hasher = Ripemd160::new();
-		hasher.input(self);
-		let mut res = vec![0u8; 20];
-		hasher.result(&mut res);
-		res
+	fn keccak256(&self) -> [u8; 32] {
+		self.as_slice().keccak256()
 	}
 
-	fn sha256_ripemd160(&self) -> Vec<u8> {
-		let mut sha256 = Sha256::new();
-		sha256.input(self);
-		let mut res = vec![0u8; 32];
-		sha256.result(&mut res);
-		let mut hasher = Ripemd160::new();
-		hasher.input(&res);
-		let mut res = vec![0u8; 20];
-		hasher.result(&mut res);
-		res
+	fn murmur32(&self, seed: u32) -> u32 {
+		self.as_slice().murmur32(seed)
 	}
+}
+
+/// Computes a SHA-256 hash over data fed in parts, e.g. a file read in chunks, without
+/// concatenating it first.
+///
+/// The hasher implements [`io::Write`], so a reader can be hashed with [`io::copy`].
+#[derive(Clone, Debug, Default)]
+pub struct Sha256Hasher(Sha256);
 
-	fn hmac_sha512(&self, key: &[u8]) -> Vec<u8> {
-		let mut hmac = Hmac::new(Sha512::new(), key);
+impl Sha256Hasher {
+	pub fn new() -> Self {
+		Self::default()
+	}
 
-		hmac.input(self);
-		let res = hmac.result();
-		res.code().to_vec()
+	/// Feeds `data` to the hasher.
+	pub fn update(&mut self, data: impl AsRef<[u8]>) -> &mut Self {
+		self.0.update(data);
+		self
+	}
+
+	/// Returns the hash of all data fed to the hasher.
+	pub fn finalize(self) -> [u8; 32] {
+		self.0.finalize().into()
+	}
+}
+
+impl io::Write for Sha256Hasher {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+/// Computes a Keccak-256 hash over data fed in parts, like [`Sha256Hasher`].
+#[derive(Clone, Debug, Default)]
+pub struct Keccak256Hasher(Keccak256);
+
+impl Keccak256Hasher {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Feeds `data` to the hasher.
+	pub fn update(&mut self, data: impl AsRef<[u8]>) -> &mut Self {
+		self.0.update(data);
+		self
+	}
+
+	/// Returns the hash of all data fed to the hasher.
+	pub fn finalize(self) -> [u8; 32] {
+		self.0.finalize().into()
+	}
+}
+
+impl io::Write for Keccak256Hasher {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.update(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
 	}
 }
 
@@ -95,12 +171,15 @@ fn hex_encode(bytes: &[u8]) -> String {
 	hex::encode(bytes)
 }
 
+/// Hashes the UTF-8 bytes of a string and returns the hex encoded hash, or the Base58 encoded
+/// hash for `hash160`.
 pub trait HashableForString {
 	fn hash256(&self) -> String;
 	fn ripemd160(&self) -> String;
 	fn sha256_ripemd160(&self) -> String;
 	fn hmac_sha512(&self, key: &str) -> String;
 	fn hash160(&self) -> String;
+	fn keccak256(&self) -> String;
 }
 impl HashableForString for String {
 	fn hash256(&self) -> String {
@@ -112,7 +191,7 @@ impl HashableForString for String {
 	}
 
 	fn sha256_ripemd160(&self) -> String {
-		hex_encode(&self.from_hex().unwrap().sha256_ripemd160())
+		hex_encode(&self.as_bytes().sha256_ripemd160())
 	}
 
 	fn hmac_sha512(&self, key: &str) -> String {
@@ -123,6 +202,10 @@ impl HashableForString for String {
 		let hash = self.as_bytes().sha256_ripemd160();
 		bs58::encode(&hash[..]).into_string()
 	}
+
+	fn keccak256(&self) -> String {
+		hex_encode(&self.as_bytes().keccak256())
+	}
 }
 
 #[cfg(test)]
@@ -164,7 +247,7 @@ mod tests {
 	fn test_sha256_ripemd160_for_bytes() {
 		let data = b"hello world";
 		// Use the expected hash value for "hello world" using SHA256 followed by RIPEMD160
-		let expected = "..."; // fill this in
+		let expected = "d7d5ee7824ff93f94c3055af9382c86c68b5ca92";
 		let result = data.sha256_ripemd160();
 		assert_eq!(hex_encode(&result), expected);
 	}
@@ -172,8 +255,12 @@ mod tests {
 	#[test]
 	fn test_sha256_ripemd160_for_string() {
 		let data = String::from("hello world");
-		let expected = "..."; // fill this in
+		let expected = "d7d5ee7824ff93f94c3055af9382c86c68b5ca92";
 		assert_eq!(data.sha256_ripemd160(), expected);
+
+		// Not hex decoded
+		let data = String::from("not hex");
+		assert_eq!(data.sha256_ripemd160(), hex_encode(&b"not hex".sha256_ripemd160()));
 	}
 
 	#[test]
@@ -181,7 +268,7 @@ mod tests {
 		let data = b"hello world";
 		let key = b"secret";
 		// Use the expected HMAC-SHA512 value for "hello world" with key "secret"
-		let expected = "..."; // fill this in
+		let expected = "6d32239b01dd1750557211629313d95e4f4fcb8ee517e443990ac1afc7562bfd74ffa6118387efd9e168ff86d1da5cef4a55edc63cc4ba289c4c3a8b4f7bdfc2";
 		let result = data.hmac_sha512(key);
 		assert_eq!(hex_encode(&result), expected);
 	}
@@ -190,7 +277,7 @@ mod tests {
 	fn test_hmac_sha512_for_string() {
 		let data = String::from("hello world");
 		let key = "secret";
-		let expected = "..."; // fill this in
+		let expected = "6d32239b01dd1750557211629313d95e4f4fcb8ee517e443990ac1afc7562bfd74ffa6118387efd9e168ff86d1da5cef4a55edc63cc4ba289c4c3a8b4f7bdfc2";
 		assert_eq!(data.hmac_sha512(key), expected);
 	}
 
@@ -233,6 +320,41 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_keccak256() {
+		assert_eq!(
+			hex_encode(&b"".keccak256()),
+			"c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+		);
+		assert_eq!(
+			String::from("hello world").keccak256(),
+			"47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+		);
+	}
+
+	#[test]
+	fn test_murmur32() {
+		assert_eq!(b"".murmur32(0), 0);
+		assert_eq!(b"".murmur32(1), 0x514e28b7);
+		assert_eq!(b"Hello, world!".murmur32(1234), 0xfaf6cdb3);
+		assert_eq!(b"The quick brown fox jumps over the lazy dog".murmur32(0), 0x2e4ff723);
+	}
+
+	#[test]
+	fn test_incremental_hashers() {
+		let data = "a".repeat(100_000);
+
+		let mut sha256 = Sha256Hasher::new();
+		for chunk in data.as_bytes().chunks(4096) {
+			sha256.update(chunk);
+		}
+		assert_eq!(sha256.finalize(), data.as_bytes().hash256());
+
+		let mut keccak256 = Keccak256Hasher::new();
+		io::copy(&mut data.as_bytes(), &mut keccak256).unwrap();
+		assert_eq!(keccak256.finalize(), data.as_bytes().keccak256());
+	}
+
 	// Helper function to convert bytes to hex string
 	// Define this or replace it with your actual hex string conversion function
 	fn to_hex_string(bytes: &[u8]) -> String {
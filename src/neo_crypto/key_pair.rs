@@ -6,18 +6,23 @@
 //! including generating new pairs, importing them from raw bytes,
 //! and converting them to various formats.
 
+use std::fmt;
+
 use rand::rngs::OsRng;
+use zeroize::{ZeroizeOnDrop, Zeroizing};
 
 use neo::prelude::{
 	wif_from_private_key, CryptoError, PublicKeyExtension, Secp256r1PrivateKey, Secp256r1PublicKey,
 };
 
 /// Represents an Elliptic Curve Key Pair containing both a private and a public key.
-
-#[derive(Debug, Clone)]
+///
+/// The private key is zeroized when the key pair is dropped and is not shown by `Debug`. It is
+/// only reachable through [`KeyPair::expose_secret`] and [`KeyPair::private_key_bytes`].
+#[derive(Clone)]
 pub struct KeyPair {
 	/// The private key component of the key pair.
-	pub private_key: Secp256r1PrivateKey,
+	private_key: Secp256r1PrivateKey,
 
 	/// The public key component of the key pair.
 	pub public_key: Secp256r1PublicKey,
@@ -34,8 +39,9 @@ impl KeyPair {
 		Self { private_key, public_key }
 	}
 
-	pub fn private_key(&self) -> Secp256r1PrivateKey {
-		self.private_key.clone()
+	/// Returns the private key, e.g. to sign with it.
+	pub fn expose_secret(&self) -> &Secp256r1PrivateKey {
+		&self.private_key
 	}
 
 	pub fn public_key(&self) -> Secp256r1PublicKey {
@@ -53,8 +59,8 @@ impl KeyPair {
 		Self::new(private_key.clone(), public_key)
 	}
 
-	/// Returns the 32-byte representation of the private key.
-	pub fn private_key_bytes(&self) -> [u8; 32] {
+	/// Returns the 32-byte representation of the private key, which is zeroized when dropped.
+	pub fn private_key_bytes(&self) -> Zeroizing<[u8; 32]> {
 		self.private_key.to_raw_bytes()
 	}
 
@@ -103,10 +109,20 @@ impl KeyPair {
 	///
 	/// Returns: The WIF encoding of this key pair
 	pub fn export_as_wif(&self) -> String {
-		wif_from_private_key(&self.private_key())
+		wif_from_private_key(&self.private_key)
+	}
+}
+
+impl fmt::Debug for KeyPair {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("KeyPair")
+			.field("public_key", &self.public_key)
+			.finish_non_exhaustive()
 	}
 }
 
+impl ZeroizeOnDrop for KeyPair {}
+
 #[cfg(test)]
 mod tests {
 	use rustc_serialize::hex::FromHex;
@@ -125,4 +141,17 @@ mod tests {
 			"L3tgppXLgdaeqSGSFw1Go3skBiy8vQAM7YMXvTHsKQtE16PBncSU"
 		);
 	}
+
+	#[test]
+	fn test_debug_redacts_private_key() {
+		let key_pair = KeyPair::from_private_key(&[1u8; 32]).unwrap();
+		let private_key = hex::encode([1u8; 32]);
+
+		let debug = format!("{:?}", key_pair);
+		assert!(debug.contains("public_key"));
+		assert!(!debug.contains(&private_key));
+		assert!(!format!("{:?}", key_pair.expose_secret()).contains(&private_key));
+		assert!(!key_pair.expose_secret().to_string().contains(&private_key));
+		assert_eq!(*key_pair.private_key_bytes(), [1u8; 32]);
+	}
 }
@@ -8,7 +8,8 @@
 //!
 //! - Generation of public and private keys.
 //! - Conversion between different formats and representations of keys and signatures.
-//! - Signing data with a private key and verifying signatures with a public key.
+//! - Deterministic (RFC 6979) signing of messages and digests with low-`s` signatures, and
+//!   verifying signatures with a public key.
 //! - Integration with external libraries like `neo-codec`, `p256`, and `rand_core` for cryptographic operations.
 //!
 //! ## Usage
@@ -40,7 +41,7 @@
 //!
 //! // Sign a message
 //! let message = b"Example message";
-//! let signature = private_key.sign_tx(message).expect("Failed to sign message");
+//! let signature = private_key.sign_message(message).expect("Failed to sign message");
 //!
 //! // Obtain the public key
 //! let public_key = private_key.to_public_key();
@@ -59,7 +60,13 @@ use std::{
 };
 
 use p256::{
-	ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey},
+	ecdsa::{
+		signature::{
+			hazmat::{PrehashSigner, PrehashVerifier},
+			Signer,
+		},
+		Signature, SigningKey, VerifyingKey,
+	},
 	elliptic_curve::{
 		sec1::{FromEncodedPoint, ToEncodedPoint},
 		Field,
@@ -71,6 +78,7 @@ use rand_core::OsRng;
 use rustc_serialize::hex::{FromHex, ToHex};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use signature::{SignerMut, Verifier};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
 
 use neo::prelude::{CryptoError, Decoder, Encoder, NeoConstants, NeoSerializable};
 
@@ -80,7 +88,12 @@ pub struct Secp256r1PublicKey {
 	inner: PublicKey,
 }
 
-#[derive(Debug, Clone)]
+/// A secp256r1 private key.
+///
+/// The key is zeroized when dropped, including every clone of it. `Debug` and `Display` do not
+/// show the key and it does not implement `Serialize`, use
+/// [`Secp256r1PrivateKey::to_raw_bytes`] to get its bytes.
+#[derive(Clone)]
 pub struct Secp256r1PrivateKey {
 	inner: SecretKey,
 }
@@ -153,7 +166,9 @@ impl Secp256r1PublicKey {
 
 	/// Verifies a digital signature against a message using this public key.
 	///
-	/// This method checks if the provided signature is valid for the given message under this public key.
+	/// This method checks if the provided signature is valid for the SHA-256 hash of the given
+	/// message under this public key, as created by [`Secp256r1PrivateKey::sign_message`].
+	/// Signatures with a high `s` are accepted as well, like Neo nodes do.
 	/// Returns a `CryptoError` if the signature verification fails.
 	///
 	/// - Parameters:
@@ -173,6 +188,28 @@ impl Secp256r1PublicKey {
 			.map_err(|_| CryptoError::SignatureVerificationError)
 	}
 
+	/// Verifies a digital signature against a 32-byte message digest using this public key.
+	///
+	/// The digest is used as is, as created by [`Secp256r1PrivateKey::sign_prehash`].
+	/// Signatures with a high `s` are accepted as well.
+	///
+	/// - Parameters:
+	///     - prehash: The digest that was signed.
+	///     - signature: The signature to verify.
+	///
+	/// - Returns: A `Result<(), CryptoError>`.
+	pub fn verify_prehash(
+		&self,
+		prehash: &[u8],
+		signature: &Secp256r1Signature,
+	) -> Result<(), CryptoError> {
+		let verifying_key = VerifyingKey::from(&self.inner);
+
+		verifying_key
+			.verify_prehash(prehash, &signature.inner)
+			.map_err(|_| CryptoError::SignatureVerificationError)
+	}
+
 	/// Gets this public key's elliptic curve point encoded as defined in section 2.3.3 of [SEC1](http://www.secg.org/sec1-v2.pdf).
 	///
 	/// - Parameter compressed: If the EC point should be encoded in compressed or uncompressed format
@@ -242,9 +279,11 @@ impl Secp256r1PrivateKey {
 
 	/// Returns the raw byte representation of the private key.
 	///
+	/// The returned copy is zeroized when dropped.
+	///
 	/// - Returns: A 32-byte array representing the private key.
-	pub fn to_raw_bytes(&self) -> [u8; 32] {
-		self.inner.clone().to_bytes().as_slice().try_into().unwrap()
+	pub fn to_raw_bytes(&self) -> Zeroizing<[u8; 32]> {
+		Zeroizing::new(self.inner.to_bytes().into())
 	}
 
 	/// Converts the private key to its corresponding public key.
@@ -254,21 +293,55 @@ impl Secp256r1PrivateKey {
 		Secp256r1PublicKey::from_public_key(self.inner.public_key())
 	}
 
-	/// Signs a transaction with the private key.
+	/// Signs a message with the private key.
 	///
-	/// This method signs the provided message (transaction) using the private key
-	/// and returns the signature.
+	/// The message is hashed with SHA-256 before signing, so `message` is the data to sign and
+	/// not a digest of it. This is the same as [`Secp256r1PrivateKey::sign_message`].
 	///
 	/// - Parameter message: A byte slice representing the message to be signed.
 	///
 	/// - Returns: A `Result` with the `Secp256r1Signature` or a `CryptoError`.
 	pub fn sign_tx(&self, message: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
-		let signing_key = SigningKey::from_slice(&self.inner.to_bytes().as_slice())
-			.map_err(|_| CryptoError::InvalidPrivateKey)?;
-		let (signature, _) =
+		self.sign_message(message)
+	}
+
+	/// Signs the SHA-256 hash of a message with the private key.
+	///
+	/// The nonce is derived deterministically from the key and the digest as specified by
+	/// [RFC 6979](https://www.rfc-editor.org/rfc/rfc6979), so signing the same message twice
+	/// gives the same signature. The signature is normalized to a low `s`.
+	///
+	/// - Parameter message: A byte slice representing the message to be signed.
+	///
+	/// - Returns: A `Result` with the `Secp256r1Signature` or a `CryptoError`.
+	pub fn sign_message(&self, message: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
+		let signing_key = self.signing_key()?;
+		let signature: Signature =
 			signing_key.try_sign(message).map_err(|_| CryptoError::SigningError)?;
 
-		Ok(Secp256r1Signature { inner: signature })
+		Ok(Secp256r1Signature { inner: signature }.normalize_s())
+	}
+
+	/// Signs a message digest with the private key.
+	///
+	/// The digest is signed as is, e.g. a SHA-256 hash computed elsewhere. Like
+	/// [`Secp256r1PrivateKey::sign_message`], the nonce is derived as specified by RFC 6979 and the
+	/// signature is normalized to a low `s`.
+	///
+	/// - Parameter prehash: The digest to be signed, at least 16 bytes long.
+	///
+	/// - Returns: A `Result` with the `Secp256r1Signature` or a `CryptoError`.
+	pub fn sign_prehash(&self, prehash: &[u8]) -> Result<Secp256r1Signature, CryptoError> {
+		let signing_key = self.signing_key()?;
+		let signature: Signature =
+			signing_key.sign_prehash(prehash).map_err(|_| CryptoError::SigningError)?;
+
+		Ok(Secp256r1Signature { inner: signature }.normalize_s())
+	}
+
+	fn signing_key(&self) -> Result<SigningKey, CryptoError> {
+		SigningKey::from_slice(&self.inner.to_bytes().as_slice())
+			.map_err(|_| CryptoError::InvalidPrivateKey)
 	}
 }
 
@@ -325,7 +398,37 @@ impl Secp256r1Signature {
 		if bytes.len() != 64 {
 			return Err(CryptoError::InvalidFormat("Invalid signature length".to_string()))
 		}
-		Ok(Secp256r1Signature { inner: Signature::from_slice(bytes).unwrap() })
+		Signature::from_slice(bytes)
+			.map(|inner| Secp256r1Signature { inner })
+			.map_err(|_| CryptoError::InvalidFormat("Invalid signature".to_string()))
+	}
+
+	/// Constructs a `Secp256r1Signature` from an ASN.1 DER encoded `SEQUENCE { r, s }`, the
+	/// format returned by hardware wallets, HSMs and key management services.
+	///
+	/// - Parameter der: The DER encoded signature.
+	///
+	/// - Returns: A `Result<Secp256r1Signature, CryptoError>`.
+	pub fn from_der(der: &[u8]) -> Result<Self, CryptoError> {
+		Signature::from_der(der)
+			.map(|inner| Secp256r1Signature { inner })
+			.map_err(|_| CryptoError::InvalidFormat("Invalid DER signature".to_string()))
+	}
+
+	/// Returns `true` if `s` is at most half the order of the curve.
+	///
+	/// Both `s` and its negation give a valid signature, signatures created by this crate always
+	/// use the low one.
+	pub fn is_low_s(&self) -> bool {
+		self.inner.normalize_s().is_none()
+	}
+
+	/// Returns the signature with a low `s`, negating `s` if it is high.
+	pub fn normalize_s(&self) -> Self {
+		match self.inner.normalize_s() {
+			Some(inner) => Secp256r1Signature { inner },
+			None => self.clone(),
+		}
 	}
 
 	/// Converts the signature into a 64-byte array.
@@ -348,10 +451,19 @@ impl Secp256r1Signature {
 
 impl fmt::Display for Secp256r1PrivateKey {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "Secp256r1PrivateKey: {}\n", hex::encode(self.inner.to_bytes().as_slice()))
+		write!(f, "Secp256r1PrivateKey: [REDACTED]\n")
 	}
 }
 
+impl fmt::Debug for Secp256r1PrivateKey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Secp256r1PrivateKey").finish_non_exhaustive()
+	}
+}
+
+// `SecretKey` zeroizes the scalar when dropped
+impl ZeroizeOnDrop for Secp256r1PrivateKey {}
+
 impl fmt::Display for Secp256r1PublicKey {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		write!(
@@ -378,15 +490,6 @@ impl Serialize for Secp256r1PublicKey {
 	}
 }
 
-impl Serialize for Secp256r1PrivateKey {
-	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-	where
-		S: Serializer,
-	{
-		serializer.serialize_bytes(&self.to_raw_bytes())
-	}
-}
-
 impl Serialize for Secp256r1Signature {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -461,7 +564,7 @@ impl Hash for Secp256r1PublicKey {
 
 impl Hash for Secp256r1PrivateKey {
 	fn hash<H: Hasher>(&self, state: &mut H) {
-		self.to_raw_bytes().hash(state);
+		(*self.to_raw_bytes()).hash(state);
 	}
 }
 
@@ -496,14 +599,15 @@ pub trait PrivateKeyExtension
 where
 	Self: Sized,
 {
-	fn to_vec(&self) -> Vec<u8>;
+	/// Returns a copy of the key bytes, which is zeroized when dropped.
+	fn to_vec(&self) -> Zeroizing<Vec<u8>>;
 
 	fn from_slice(slice: &[u8]) -> Result<Self, CryptoError>;
 }
 
 impl PrivateKeyExtension for Secp256r1PrivateKey {
-	fn to_vec(&self) -> Vec<u8> {
-		self.to_raw_bytes().to_vec()
+	fn to_vec(&self) -> Zeroizing<Vec<u8>> {
+		Zeroizing::new(self.to_raw_bytes().to_vec())
 	}
 
 	fn from_slice(slice: &[u8]) -> Result<Self, CryptoError> {
@@ -550,7 +654,9 @@ impl NeoSerializable for Secp256r1PublicKey {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let bytes = reader.read_bytes(NeoConstants::PUBLIC_KEY_SIZE_COMPRESSED as usize).unwrap();
+		let bytes = reader
+			.read_bytes(NeoConstants::PUBLIC_KEY_SIZE_COMPRESSED as usize)
+			.map_err(|e| CryptoError::InvalidFormat(e.to_string()))?;
 		Secp256r1PublicKey::from_bytes(&bytes).map_err(|_| CryptoError::InvalidPublicKey)
 	}
 
@@ -673,4 +779,66 @@ mod tests {
 		// TODO: check this verification
 		// assert!(public_key.verify(&hashed_msg, &expected_signature).is_ok());
 	}
+
+	#[test]
+	fn test_sign_message_rfc6979() {
+		// RFC 6979, A.2.5, P-256 with SHA-256 and the message "sample"
+		let private_key = Secp256r1PrivateKey::from_bytes(
+			&hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+				.unwrap(),
+		)
+		.unwrap();
+		let r = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716";
+		let high_s = "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+		let low_s = "0834e36ad29a83bf2bc9385e491d6099c8fdf9d1ed67aa7ea5f51f93782857a9";
+
+		let signature = private_key.sign_message(b"sample").unwrap();
+		assert_eq!(signature.to_bytes().to_vec(), hex::decode(format!("{}{}", r, low_s)).unwrap());
+		assert!(signature.is_low_s());
+		assert_eq!(private_key.sign_message(b"sample").unwrap(), signature);
+		assert_eq!(private_key.sign_tx(b"sample").unwrap(), signature);
+
+		let public_key = private_key.to_public_key();
+		public_key.verify(b"sample", &signature).unwrap();
+		public_key.verify_prehash(&b"sample".hash256(), &signature).unwrap();
+
+		// The signature of the RFC has a high s, which verifies too
+		let rfc_signature =
+			Secp256r1Signature::from_bytes(&hex::decode(format!("{}{}", r, high_s)).unwrap())
+				.unwrap();
+		assert!(!rfc_signature.is_low_s());
+		assert_eq!(rfc_signature.normalize_s(), signature);
+		public_key.verify(b"sample", &rfc_signature).unwrap();
+		assert!(public_key.verify(b"other", &signature).is_err());
+	}
+
+	#[test]
+	fn test_sign_prehash() {
+		let private_key = Secp256r1PrivateKey::random(&mut rand_core::OsRng);
+		let public_key = private_key.to_public_key();
+		let prehash = b"A test message".hash256();
+
+		let signature = private_key.sign_prehash(&prehash).unwrap();
+		assert!(signature.is_low_s());
+		assert_eq!(private_key.sign_prehash(&prehash).unwrap(), signature);
+		assert_eq!(private_key.sign_message(b"A test message").unwrap(), signature);
+
+		public_key.verify_prehash(&prehash, &signature).unwrap();
+		public_key.verify(b"A test message", &signature).unwrap();
+		assert!(public_key.verify(&prehash, &signature).is_err());
+	}
+
+	#[test]
+	fn test_signature_from_der() {
+		// r has its high bit set and is padded with a zero byte, s is shorter than 32 bytes.
+		let der = [&[0x30, 0x26, 0x02, 0x21, 0x00][..], &[0x80; 32], &[0x02, 0x01, 0x07]].concat();
+
+		let signature = Secp256r1Signature::from_der(&der).unwrap();
+		let bytes = signature.to_bytes();
+		assert_eq!(bytes[..32], [0x80; 32]);
+		assert_eq!(bytes[32..63], [0; 31]);
+		assert_eq!(bytes[63], 0x07);
+
+		assert!(Secp256r1Signature::from_der(&der[..der.len() - 1]).is_err());
+	}
 }
@@ -0,0 +1,81 @@
+use primitive_types::H256;
+
+use neo::prelude::HashableForVec;
+
+/// The merkle tree over the transaction hashes of a block.
+///
+/// Parent nodes hash the concatenation of their children with double SHA-256. A node without a
+/// right sibling is paired with itself, as in the reference implementation.
+pub struct MerkleTree;
+
+impl MerkleTree {
+	/// Computes the merkle root of `hashes`, which are in the big-endian form used for display and
+	/// RPC calls. The root of an empty list is the zero hash.
+	pub fn compute_root(hashes: &[H256]) -> H256 {
+		if hashes.is_empty() {
+			return H256::zero()
+		}
+
+		let mut level: Vec<Vec<u8>> = hashes
+			.iter()
+			.map(|hash| {
+				let mut bytes = hash.as_bytes().to_vec();
+				bytes.reverse();
+				bytes
+			})
+			.collect();
+		while level.len() > 1 {
+			level = level
+				.chunks(2)
+				.map(|pair| {
+					let mut data = pair[0].clone();
+					data.extend_from_slice(pair.get(1).unwrap_or(&pair[0]));
+					data.hash256().hash256().to_vec()
+				})
+				.collect();
+		}
+
+		let mut root = level.remove(0);
+		root.reverse();
+		H256::from_slice(&root)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use primitive_types::H256;
+
+	use neo::prelude::{HashableForVec, MerkleTree};
+
+	fn hash(hex: &str) -> H256 {
+		H256::from_str(hex).unwrap()
+	}
+
+	#[test]
+	fn test_compute_root_of_empty_and_single_leaf() {
+		assert_eq!(MerkleTree::compute_root(&[]), H256::zero());
+
+		let leaf = hash("0x9ac7ddbf6f5e2ab4b0b5e0a9c3b4d7e5f3e0c4d9a7f2b5e1c6d8a3f0b2e4c7d9");
+		assert_eq!(MerkleTree::compute_root(&[leaf]), leaf);
+	}
+
+	#[test]
+	fn test_compute_root_duplicates_odd_leaf() {
+		let a = hash("0x0000000000000000000000000000000000000000000000000000000000000001");
+		let b = hash("0x0000000000000000000000000000000000000000000000000000000000000002");
+		let c = hash("0x0000000000000000000000000000000000000000000000000000000000000003");
+
+		let parent = |left: &H256, right: &H256| {
+			let mut data = left.as_bytes().iter().rev().copied().collect::<Vec<u8>>();
+			data.extend(right.as_bytes().iter().rev());
+			let mut hash = data.hash256().hash256();
+			hash.reverse();
+			H256::from_slice(&hash)
+		};
+
+		assert_eq!(MerkleTree::compute_root(&[a, b]), parent(&a, &b));
+		assert_eq!(MerkleTree::compute_root(&[a, b, c]), parent(&parent(&a, &b), &parent(&c, &c)));
+	}
+}
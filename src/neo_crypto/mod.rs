@@ -3,7 +3,10 @@ pub use error::*;
 pub use hash::*;
 pub use key_pair::*;
 pub use keys::*;
+pub use merkle_tree::*;
+pub use shamir::*;
 pub use utils::*;
+pub use vanity::*;
 pub use wif::*;
 
 mod base58_helper;
@@ -11,7 +14,10 @@ mod error;
 mod hash;
 mod key_pair;
 mod keys;
+mod merkle_tree;
+mod shamir;
 mod utils;
+mod vanity;
 mod wif;
 
 pub(crate) fn add(left: usize, right: usize) -> usize {
@@ -0,0 +1,317 @@
+//! Shamir's secret sharing of private keys, for backups split across several places
+//!
+//! Every byte of the private key is shared separately over GF(2^8) with the AES polynomial
+//! `x^8 + x^4 + x^3 + x + 1`. For a threshold of `k`, each byte is the constant term of a random
+//! polynomial of degree `k - 1`, and the share with index `x` (1 to 255) holds the values of the
+//! 32 polynomials at `x`. Any `k` shares determine the polynomials by Lagrange interpolation, while
+//! `k - 1` shares are consistent with every possible key.
+//!
+//! Shares are encoded as base58check strings of 37 bytes: the format version `0x01`, a random
+//! 2-byte identifier shared by all shares of a split, the threshold, the index and the 32 bytes
+//! of values. The checksum detects mistyped shares, and the identifier detects shares of
+//! different splits.
+use std::fmt;
+
+use rand::{rngs::OsRng, RngCore};
+use zeroize::Zeroizing;
+
+use neo::prelude::{
+	base58check_decode, base58check_encode, KeyPair, Secp256r1PrivateKey, ShamirError,
+};
+
+/// A share of a private key split with [`SecretShare::split`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretShare {
+	id: u16,
+	threshold: u8,
+	index: u8,
+	value: Zeroizing<[u8; 32]>,
+}
+
+impl SecretShare {
+	const VERSION: u8 = 0x01;
+	const ENCODED_SIZE: usize = 37;
+
+	/// Splits `private_key` into `count` shares, any `threshold` of which recover it.
+	pub fn split(
+		private_key: &Secp256r1PrivateKey,
+		threshold: u8,
+		count: u8,
+	) -> Result<Vec<SecretShare>, ShamirError> {
+		if threshold == 0 || threshold > count {
+			return Err(ShamirError::InvalidParameters(format!(
+				"The threshold must be between 1 and the number of shares, got {} of {}",
+				threshold, count
+			)))
+		}
+
+		let secret = private_key.to_raw_bytes();
+		// The coefficients of the polynomial of byte i are coefficients[i * threshold..]
+		let mut coefficients = Zeroizing::new(vec![0u8; 32 * threshold as usize]);
+		OsRng.fill_bytes(&mut coefficients);
+		for (i, byte) in secret.iter().enumerate() {
+			coefficients[i * threshold as usize] = *byte;
+		}
+		let id = OsRng.next_u32() as u16;
+
+		Ok((1..=count)
+			.map(|index| {
+				let mut value = Zeroizing::new([0u8; 32]);
+				for (i, polynomial) in coefficients.chunks(threshold as usize).enumerate() {
+					value[i] = Self::evaluate(polynomial, index);
+				}
+				SecretShare { id, threshold, index, value }
+			})
+			.collect())
+	}
+
+	/// Recovers the private key from at least `threshold` shares of the same split.
+	pub fn combine(shares: &[SecretShare]) -> Result<Secp256r1PrivateKey, ShamirError> {
+		let first = shares.first().ok_or(ShamirError::NotEnoughShares(0, 1))?;
+		if shares
+			.iter()
+			.any(|share| share.id != first.id || share.threshold != first.threshold)
+		{
+			return Err(ShamirError::MismatchedShares)
+		}
+		let mut indexes = shares.iter().map(|share| share.index).collect::<Vec<_>>();
+		indexes.sort_unstable();
+		indexes.dedup();
+		if indexes.len() != shares.len() {
+			return Err(ShamirError::InvalidShare("The shares contain an index twice".to_string()))
+		}
+		if shares.len() < first.threshold as usize {
+			return Err(ShamirError::NotEnoughShares(shares.len(), first.threshold))
+		}
+
+		let shares = &shares[..first.threshold as usize];
+		let mut secret = Zeroizing::new([0u8; 32]);
+		let mut points = Zeroizing::new(vec![(0u8, 0u8); shares.len()]);
+		for i in 0..32 {
+			for (point, share) in points.iter_mut().zip(shares) {
+				*point = (share.index, share.value[i]);
+			}
+			secret[i] = Self::interpolate(&points, 0);
+		}
+		Secp256r1PrivateKey::from_bytes(secret.as_slice())
+			.map_err(|_| ShamirError::InvalidShare("The shares recover an invalid key".to_string()))
+	}
+
+	/// Recovers the key pair from at least `threshold` shares of the same split.
+	pub fn combine_key_pair(shares: &[SecretShare]) -> Result<KeyPair, ShamirError> {
+		Ok(KeyPair::from_secret_key(&Self::combine(shares)?))
+	}
+
+	/// Encodes the share as a base58check string.
+	pub fn to_base58(&self) -> String {
+		let mut bytes = Zeroizing::new(Vec::with_capacity(Self::ENCODED_SIZE));
+		bytes.push(Self::VERSION);
+		bytes.extend(self.id.to_be_bytes());
+		bytes.push(self.threshold);
+		bytes.push(self.index);
+		bytes.extend(self.value.iter());
+		base58check_encode(&bytes)
+	}
+
+	/// Decodes a share encoded with [`SecretShare::to_base58`].
+	pub fn from_base58(encoded: &str) -> Result<Self, ShamirError> {
+		let bytes = Zeroizing::new(base58check_decode(encoded.trim()).ok_or_else(|| {
+			ShamirError::InvalidShare("Invalid base58 encoding or checksum".to_string())
+		})?);
+		if bytes.len() != Self::ENCODED_SIZE || bytes[0] != Self::VERSION {
+			return Err(ShamirError::InvalidShare("Unsupported share format".to_string()))
+		}
+		let (threshold, index) = (bytes[3], bytes[4]);
+		if threshold == 0 || index == 0 {
+			return Err(ShamirError::InvalidShare("Invalid threshold or index".to_string()))
+		}
+
+		let mut value = Zeroizing::new([0u8; 32]);
+		value.copy_from_slice(&bytes[5..]);
+		Ok(Self { id: u16::from_be_bytes([bytes[1], bytes[2]]), threshold, index, value })
+	}
+
+	/// The identifier shared by all shares of a split.
+	pub fn id(&self) -> u16 {
+		self.id
+	}
+
+	/// The number of shares needed to recover the key.
+	pub fn threshold(&self) -> u8 {
+		self.threshold
+	}
+
+	/// The index of the share, from 1 to the number of shares.
+	pub fn index(&self) -> u8 {
+		self.index
+	}
+
+	/// Evaluates the polynomial with the coefficients `polynomial`, constant term first, at `x`.
+	fn evaluate(polynomial: &[u8], x: u8) -> u8 {
+		polynomial
+			.iter()
+			.rev()
+			.fold(0, |result, coefficient| gf_mul(result, x) ^ coefficient)
+	}
+
+	/// Evaluates the polynomial through `points` at `x`.
+	fn interpolate(points: &[(u8, u8)], x: u8) -> u8 {
+		points.iter().enumerate().fold(0, |result, (i, (xi, yi))| {
+			let basis = points
+				.iter()
+				.enumerate()
+				.filter(|(j, _)| *j != i)
+				.fold(1, |basis, (_, (xj, _))| gf_mul(basis, gf_mul(x ^ xj, gf_inverse(xi ^ xj))));
+			result ^ gf_mul(*yi, basis)
+		})
+	}
+}
+
+// do not log the value
+impl fmt::Debug for SecretShare {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SecretShare")
+			.field("id", &self.id)
+			.field("threshold", &self.threshold)
+			.field("index", &self.index)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Multiplies in GF(2^8) without lookup tables, so that the time does not depend on the values.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+	let mut product = 0;
+	for _ in 0..8 {
+		product ^= a & 0u8.wrapping_sub(b & 1);
+		let carry = 0u8.wrapping_sub(a >> 7);
+		a = (a << 1) ^ (carry & 0x1b);
+		b >>= 1;
+	}
+	product
+}
+
+/// Inverts in GF(2^8) as `a^254`. The inverse of 0 is 0.
+fn gf_inverse(a: u8) -> u8 {
+	let mut result = 1;
+	let mut power = a;
+	for bit in 0..8 {
+		if (254 >> bit) & 1 == 1 {
+			result = gf_mul(result, power);
+		}
+		power = gf_mul(power, power);
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{Account, AccountTrait};
+
+	use super::*;
+
+	#[test]
+	fn test_gf_arithmetic() {
+		// The example of FIPS 197, section 4.2
+		assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+		assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+		for a in 1..=255u8 {
+			assert_eq!(gf_mul(a, gf_inverse(a)), 1);
+		}
+	}
+
+	#[test]
+	fn test_any_threshold_shares_recover_the_key() {
+		let key_pair = KeyPair::new_random();
+		let shares = SecretShare::split(key_pair.expose_secret(), 3, 5).unwrap();
+		assert_eq!(shares.len(), 5);
+		assert!(shares
+			.iter()
+			.all(|share| share.id() == shares[0].id() && share.threshold() == 3));
+
+		for a in 0..5 {
+			for b in a + 1..5 {
+				for c in b + 1..5 {
+					let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+					assert_eq!(&SecretShare::combine(&subset).unwrap(), key_pair.expose_secret());
+				}
+			}
+		}
+		assert_eq!(&SecretShare::combine(&shares).unwrap(), key_pair.expose_secret());
+
+		let recovered = SecretShare::combine_key_pair(&shares[1..4]).unwrap();
+		let account = Account::from_key_pair(recovered, None, None).unwrap();
+		assert_eq!(
+			account.get_script_hash(),
+			Account::from_key_pair(key_pair.clone(), None, None).unwrap().get_script_hash()
+		);
+	}
+
+	#[test]
+	fn test_fewer_shares_reveal_nothing() {
+		let key_pair = KeyPair::new_random();
+		let shares = SecretShare::split(key_pair.expose_secret(), 3, 5).unwrap();
+		assert_eq!(
+			SecretShare::combine(&shares[..2]).unwrap_err(),
+			ShamirError::NotEnoughShares(2, 3)
+		);
+
+		// Two shares are consistent with any key: a third share exists that recovers it
+		let other = KeyPair::new_random();
+		let other_secret = other.private_key_bytes();
+		let mut value = Zeroizing::new([0u8; 32]);
+		for i in 0..32 {
+			let points = [
+				(0, other_secret[i]),
+				(shares[0].index, shares[0].value[i]),
+				(shares[1].index, shares[1].value[i]),
+			];
+			value[i] = SecretShare::interpolate(&points, 6);
+		}
+		let forged = SecretShare { id: shares[0].id, threshold: 3, index: 6, value };
+		let recovered =
+			SecretShare::combine(&[shares[0].clone(), shares[1].clone(), forged]).unwrap();
+		assert_eq!(&recovered, other.expose_secret());
+	}
+
+	#[test]
+	fn test_base58() {
+		let key_pair = KeyPair::new_random();
+		let shares = SecretShare::split(key_pair.expose_secret(), 2, 3).unwrap();
+
+		let encoded = shares.iter().map(SecretShare::to_base58).collect::<Vec<_>>();
+		let decoded = encoded
+			.iter()
+			.map(|share| SecretShare::from_base58(share).unwrap())
+			.collect::<Vec<_>>();
+		assert_eq!(decoded, shares);
+		assert_eq!(&SecretShare::combine(&decoded[1..]).unwrap(), key_pair.expose_secret());
+
+		let mut mistyped = encoded[0].clone().into_bytes();
+		mistyped[10] = if mistyped[10] == b'2' { b'3' } else { b'2' };
+		assert!(SecretShare::from_base58(&String::from_utf8(mistyped).unwrap()).is_err());
+		assert!(!format!("{:?}", shares[0]).contains(&hex::encode(shares[0].value.as_slice())));
+	}
+
+	#[test]
+	fn test_invalid_splits_and_combinations() {
+		let key_pair = KeyPair::new_random();
+		assert!(SecretShare::split(key_pair.expose_secret(), 0, 3).is_err());
+		assert!(SecretShare::split(key_pair.expose_secret(), 4, 3).is_err());
+
+		let shares = SecretShare::split(key_pair.expose_secret(), 1, 1).unwrap();
+		assert_eq!(&SecretShare::combine(&shares).unwrap(), key_pair.expose_secret());
+
+		let first = SecretShare::split(key_pair.expose_secret(), 2, 3).unwrap();
+		let mut second = SecretShare::split(key_pair.expose_secret(), 2, 3).unwrap();
+		second[1].id = first[0].id.wrapping_add(1);
+		assert_eq!(
+			SecretShare::combine(&[first[0].clone(), second[1].clone()]).unwrap_err(),
+			ShamirError::MismatchedShares
+		);
+		assert!(matches!(
+			SecretShare::combine(&[first[0].clone(), first[0].clone()]),
+			Err(ShamirError::InvalidShare(_))
+		));
+		assert_eq!(SecretShare::combine(&[]).unwrap_err(), ShamirError::NotEnoughShares(0, 1));
+	}
+}
@@ -1,4 +1,5 @@
 use rustc_serialize::hex::ToHex;
+use zeroize::Zeroizing;
 
 use neo::prelude::{
 	CryptoError, PrivateKeyExtension, PublicKeyExtension, Secp256r1PrivateKey, Secp256r1PublicKey,
@@ -11,9 +12,9 @@ pub fn private_key_to_public_key(private_key: &Secp256r1PrivateKey) -> Secp256r1
 
 /// Convert a private key to hex format.
 ///
-/// Returns the private key as a hex encoded string.
-pub fn private_key_to_hex(private_key: &Secp256r1PrivateKey) -> String {
-	private_key.to_raw_bytes().to_vec().to_hex()
+/// Returns the private key as a hex encoded string, which is zeroized when dropped.
+pub fn private_key_to_hex(private_key: &Secp256r1PrivateKey) -> Zeroizing<String> {
+	Zeroizing::new(private_key.to_raw_bytes().as_slice().to_hex())
 }
 
 /// Convert a private key in hex format to a Secp256r1PrivateKey.
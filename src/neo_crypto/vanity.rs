@@ -0,0 +1,303 @@
+//! Generation of vanity addresses, i.e. of keys whose Neo address matches a pattern
+use std::{
+	fmt,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc,
+	},
+};
+
+use primitive_types::U256;
+use rayon::prelude::*;
+use regex::Regex;
+
+use neo::prelude::{
+	public_key_to_address, Address, KeyPair, Nep2Error, ScryptParamsDef, VanityError,
+	DEFAULT_ADDRESS_VERSION, NEP2,
+};
+
+/// The pattern a vanity address has to match.
+#[derive(Debug, Clone)]
+pub enum VanityPattern {
+	/// The address starts with the string, which includes the leading `N` of Neo addresses.
+	Prefix(String),
+	/// The address ends with the string.
+	Suffix(String),
+	/// The address matches the regular expression.
+	Regex(Regex),
+}
+
+impl VanityPattern {
+	const ALPHABET: &'static str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+	const ADDRESS_LENGTH: usize = 34;
+
+	/// Creates a prefix pattern, e.g. `NNeo`. Fails if no address can start with `prefix`, e.g.
+	/// because the second character of Neo addresses is always between `K` and `j`.
+	pub fn prefix(prefix: &str) -> Result<Self, VanityError> {
+		Self::check_alphabet(prefix)?;
+		if Self::prefix_count(prefix).is_zero() {
+			return Err(VanityError::InvalidPattern(format!(
+				"No Neo address starts with {:?}",
+				prefix
+			)))
+		}
+		Ok(Self::Prefix(prefix.to_string()))
+	}
+
+	/// Creates a suffix pattern.
+	pub fn suffix(suffix: &str) -> Result<Self, VanityError> {
+		Self::check_alphabet(suffix)?;
+		Ok(Self::Suffix(suffix.to_string()))
+	}
+
+	/// Creates a pattern matching addresses with the regular expression `regex`.
+	pub fn regex(regex: &str) -> Result<Self, VanityError> {
+		Regex::new(regex)
+			.map(Self::Regex)
+			.map_err(|e| VanityError::InvalidPattern(e.to_string()))
+	}
+
+	/// Returns `true` if `address` matches the pattern.
+	pub fn matches(&self, address: &str) -> bool {
+		match self {
+			Self::Prefix(prefix) => address.starts_with(prefix.as_str()),
+			Self::Suffix(suffix) => address.ends_with(suffix.as_str()),
+			Self::Regex(regex) => regex.is_match(address),
+		}
+	}
+
+	/// Returns the expected number of keys to try until an address matches the pattern, or
+	/// `None` for a regular expression.
+	pub fn difficulty(&self) -> Option<f64> {
+		match self {
+			Self::Prefix(prefix) => Some(
+				Self::to_f64(Self::address_end() - Self::address_start())
+					/ Self::to_f64(Self::prefix_count(prefix)),
+			),
+			Self::Suffix(suffix) => Some(58f64.powi(suffix.len() as i32)),
+			Self::Regex(_) => None,
+		}
+	}
+
+	fn check_alphabet(pattern: &str) -> Result<(), VanityError> {
+		if pattern.is_empty() || pattern.len() > Self::ADDRESS_LENGTH {
+			return Err(VanityError::InvalidPattern(format!(
+				"The pattern must have between 1 and {} characters",
+				Self::ADDRESS_LENGTH
+			)))
+		}
+		match pattern.chars().find(|c| !Self::ALPHABET.contains(*c)) {
+			Some(c) =>
+				Err(VanityError::InvalidPattern(format!("{:?} is not a base58 character", c))),
+			None => Ok(()),
+		}
+	}
+
+	// Addresses are the base58 encoding of the version, 20 bytes of script hash and 4 bytes of
+	// checksum, so their values are in [version << 192, (version + 1) << 192).
+	fn address_start() -> U256 {
+		U256::from(DEFAULT_ADDRESS_VERSION) << 192
+	}
+
+	fn address_end() -> U256 {
+		U256::from(DEFAULT_ADDRESS_VERSION + 1) << 192
+	}
+
+	/// The number of address values starting with `prefix`.
+	fn prefix_count(prefix: &str) -> U256 {
+		let value = prefix.chars().fold(U256::zero(), |value, c| {
+			value * U256::from(58) + U256::from(Self::ALPHABET.find(c).unwrap())
+		});
+		let scale = U256::from(58).pow(U256::from(Self::ADDRESS_LENGTH - prefix.len()));
+		let start = (value * scale).max(Self::address_start());
+		let end = (value * scale + scale).min(Self::address_end());
+		end.saturating_sub(start)
+	}
+
+	fn to_f64(value: U256) -> f64 {
+		value
+			.0
+			.iter()
+			.rev()
+			.fold(0.0, |result, limb| result * 2f64.powi(64) + *limb as f64)
+	}
+}
+
+/// A key pair whose address matches a [`VanityPattern`].
+#[derive(Debug, Clone)]
+pub struct VanityAddress {
+	pub key_pair: KeyPair,
+	pub address: Address,
+	/// The number of keys tried by all threads until the address was found.
+	pub attempts: u64,
+}
+
+impl VanityAddress {
+	/// Exports the private key in Wallet Import Format.
+	pub fn to_wif(&self) -> String {
+		self.key_pair.export_as_wif()
+	}
+
+	/// Exports the private key encrypted with `password` as NEP-2.
+	pub fn to_nep2(&self, password: &str, params: &ScryptParamsDef) -> Result<String, Nep2Error> {
+		NEP2::encrypt(password, &self.key_pair, params)
+	}
+}
+
+/// Searches for a key whose address matches a [`VanityPattern`] on all threads of the rayon
+/// thread pool.
+///
+/// ```no_run
+/// use neo_rs::prelude::{VanityPattern, VanitySearch};
+///
+/// let pattern = VanityPattern::prefix("NNeo").unwrap();
+/// println!("Expecting {:.0} attempts", pattern.difficulty().unwrap());
+/// let search = VanitySearch::new(pattern)
+/// 	.on_progress(100_000, |attempts| println!("{} keys tried", attempts));
+/// let found = search.run().unwrap();
+/// println!("{} {}", found.address, found.to_wif());
+/// ```
+pub struct VanitySearch {
+	pattern: VanityPattern,
+	cancelled: Arc<AtomicBool>,
+	progress: Option<(u64, Box<dyn Fn(u64) + Send + Sync>)>,
+}
+
+impl VanitySearch {
+	pub fn new(pattern: VanityPattern) -> Self {
+		Self { pattern, cancelled: Arc::new(AtomicBool::new(false)), progress: None }
+	}
+
+	/// Calls `callback` with the number of keys tried so far every `interval` keys. The callback
+	/// is called from the search threads.
+	pub fn on_progress(
+		mut self,
+		interval: u64,
+		callback: impl Fn(u64) + Send + Sync + 'static,
+	) -> Self {
+		self.progress = Some((interval.max(1), Box::new(callback)));
+		self
+	}
+
+	/// Returns a flag which cancels the search when set, e.g. from another thread or a progress
+	/// callback.
+	pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+		self.cancelled.clone()
+	}
+
+	pub fn pattern(&self) -> &VanityPattern {
+		&self.pattern
+	}
+
+	/// Searches until an address matches the pattern. Returns `None` if the search is cancelled.
+	pub fn run(&self) -> Option<VanityAddress> {
+		let attempts = AtomicU64::new(0);
+		let (key_pair, address) = rayon::iter::repeat(()).find_map_any(|_| {
+			if self.cancelled.load(Ordering::Relaxed) {
+				return Some(None)
+			}
+
+			let key_pair = KeyPair::new_random();
+			let count = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+			if let Some((interval, callback)) = &self.progress {
+				if count % interval == 0 {
+					callback(count);
+				}
+			}
+
+			let address = public_key_to_address(&key_pair.public_key());
+			self.pattern.matches(&address).then(|| Some((key_pair, address)))
+		})??;
+
+		Some(VanityAddress { key_pair, address, attempts: attempts.load(Ordering::Relaxed) })
+	}
+}
+
+// do not log the callback
+impl fmt::Debug for VanitySearch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("VanitySearch")
+			.field("pattern", &self.pattern)
+			.field("cancelled", &self.cancelled)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::private_key_from_wif;
+
+	use super::*;
+
+	#[test]
+	fn test_patterns() {
+		assert!(VanityPattern::prefix("N")
+			.unwrap()
+			.matches("NTrezR3C4X8aMLVg7vozt5wguyNfFhwuFx"));
+		assert!(VanityPattern::suffix("Fx")
+			.unwrap()
+			.matches("NTrezR3C4X8aMLVg7vozt5wguyNfFhwuFx"));
+		assert!(VanityPattern::regex("^NT.*x$")
+			.unwrap()
+			.matches("NTrezR3C4X8aMLVg7vozt5wguyNfFhwuFx"));
+		assert!(!VanityPattern::prefix("NN")
+			.unwrap()
+			.matches("NTrezR3C4X8aMLVg7vozt5wguyNfFhwuFx"));
+
+		assert!(VanityPattern::prefix("NO").is_err());
+		assert!(VanityPattern::prefix("A").is_err());
+		assert!(VanityPattern::prefix("NA").is_err());
+		assert!(VanityPattern::prefix("Nk").is_err());
+		assert!(VanityPattern::suffix("").is_err());
+		assert!(VanityPattern::regex("(").is_err());
+	}
+
+	#[test]
+	fn test_difficulty() {
+		assert_eq!(VanityPattern::prefix("N").unwrap().difficulty(), Some(1.0));
+		assert_eq!(VanityPattern::suffix("ab").unwrap().difficulty(), Some(58.0 * 58.0));
+		assert_eq!(VanityPattern::regex("^N").unwrap().difficulty(), None);
+
+		// The second character is one of the characters from K to j, which are not equally likely
+		let second = VanityPattern::prefix("NN").unwrap().difficulty().unwrap();
+		assert!((second - 23.34).abs() < 0.01, "{}", second);
+		let rare = VanityPattern::prefix("NK").unwrap().difficulty().unwrap();
+		assert!((rare - 269.04).abs() < 0.01, "{}", rare);
+		let third = VanityPattern::prefix("NNe").unwrap().difficulty().unwrap();
+		assert!((third / second - 58.0).abs() < 1.0, "{}", third);
+	}
+
+	#[test]
+	fn test_search() {
+		let search = VanitySearch::new(VanityPattern::suffix("a").unwrap());
+		let found = search.run().unwrap();
+		assert!(found.address.ends_with('a'));
+		assert_eq!(found.address, public_key_to_address(&found.key_pair.public_key()));
+		assert!(found.attempts > 0);
+
+		let private_key = private_key_from_wif(&found.to_wif()).unwrap();
+		assert_eq!(public_key_to_address(&private_key.to_public_key()), found.address);
+
+		let params = ScryptParamsDef { log_n: 8, r: 8, p: 1 };
+		let nep2 = found.to_nep2("pw", &params).unwrap();
+		let decrypted = NEP2::decrypt("pw", &nep2, &params).unwrap();
+		assert_eq!(public_key_to_address(&decrypted.public_key()), found.address);
+	}
+
+	#[test]
+	fn test_cancel_from_progress() {
+		let search = VanitySearch::new(VanityPattern::suffix("zzzzzzzzzz").unwrap());
+		let cancelled = search.cancel_flag();
+		let reported = Arc::new(AtomicU64::new(0));
+		let progress = reported.clone();
+		let search = search.on_progress(10, move |attempts| {
+			progress.fetch_max(attempts, Ordering::Relaxed);
+			if attempts >= 100 {
+				cancelled.store(true, Ordering::Relaxed);
+			}
+		});
+
+		assert!(search.run().is_none());
+		assert!(reported.load(Ordering::Relaxed) >= 100);
+	}
+}
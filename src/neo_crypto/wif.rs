@@ -1,4 +1,5 @@
 use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
 
 use neo::prelude::*;
 
@@ -20,9 +21,11 @@ use neo::prelude::*;
 /// * The decoded data does not have the correct length, prefix, or suffix expected for a WIF.
 /// * The checksum of the WIF does not match the expected value.
 pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoError> {
-	let data = bs58::decode(wif)
-		.into_vec()
-		.map_err(|_| CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))?;
+	let data = Zeroizing::new(
+		bs58::decode(wif)
+			.into_vec()
+			.map_err(|_| CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))?,
+	);
 	if data.len() != 38 || data[0] != 0x80 || data[33] != 0x01 {
 		return Err(CryptoError::InvalidFormat("Incorrect WIF format.".to_string()))
 	}
@@ -32,7 +35,7 @@ pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoErro
 		return Err(CryptoError::InvalidFormat("Incorrect WIF checksum.".to_string()))
 	}
 
-	Secp256r1PrivateKey::from_bytes(&data[1..33].to_vec())
+	Secp256r1PrivateKey::from_bytes(&data[1..33])
 }
 
 /// Converts a `Secp256r1PrivateKey` into a WIF (Wallet Import Format) string.
@@ -46,15 +49,15 @@ pub fn private_key_from_wif(wif: &str) -> Result<Secp256r1PrivateKey, CryptoErro
 /// # Returns
 /// A `String` containing the WIF representation of the provided private key.
 pub fn wif_from_private_key(private_key: &Secp256r1PrivateKey) -> String {
-	let mut extended_key: Vec<u8> = vec![0x80];
-	extended_key.extend(private_key.to_raw_bytes());
+	let mut extended_key = Zeroizing::new(vec![0x80u8]);
+	extended_key.extend(private_key.to_raw_bytes().iter());
 	extended_key.push(0x01);
 
-	let hash = Sha256::digest(&Sha256::digest(&extended_key));
+	let hash = Sha256::digest(&Sha256::digest(extended_key.as_slice()));
 	let checksum = &hash[0..4];
 	extended_key.extend_from_slice(checksum);
 
-	bs58::encode(extended_key).into_string()
+	bs58::encode(extended_key.as_slice()).into_string()
 }
 
 #[cfg(test)]
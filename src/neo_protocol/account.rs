@@ -152,6 +152,41 @@ impl Hash for Account {
 	}
 }
 
+impl Account {
+	/// Decrypts the NEP-2 encrypted private key with the scrypt parameters of the wallet it
+	/// belongs to. Does nothing if the key pair is already available.
+	pub fn decrypt_private_key_with_params(
+		&mut self,
+		password: &str,
+		params: &ScryptParamsDef,
+	) -> Result<(), ProviderError> {
+		if self.key_pair.is_some() {
+			return Ok(())
+		}
+
+		let encrypted_private_key = self.encrypted_private_key.as_ref().ok_or_else(|| {
+			ProviderError::IllegalState("No encrypted private key present".to_string())
+		})?;
+		self.key_pair = Some(NEP2::decrypt(password, encrypted_private_key, params)?);
+		Ok(())
+	}
+
+	/// Encrypts the private key to NEP-2 with the scrypt parameters of the wallet it belongs to,
+	/// and removes the key pair from the account.
+	pub fn encrypt_private_key_with_params(
+		&mut self,
+		password: &str,
+		params: &ScryptParamsDef,
+	) -> Result<(), ProviderError> {
+		let key_pair = self.key_pair.as_ref().ok_or_else(|| {
+			ProviderError::IllegalState("No decrypted key pair present".to_string())
+		})?;
+		self.encrypted_private_key = Some(NEP2::encrypt(password, key_pair, params)?);
+		self.key_pair = None;
+		Ok(())
+	}
+}
+
 impl AccountTrait for Account {
 	type Error = ProviderError;
 
@@ -290,35 +325,11 @@ impl AccountTrait for Account {
 	}
 
 	fn decrypt_private_key(&mut self, password: &str) -> Result<(), Self::Error> {
-		if self.key_pair.is_some() {
-			return Ok(())
-		}
-
-		let encrypted_private_key = self
-			.encrypted_private_key
-			.as_ref()
-			.ok_or(Self::Error::IllegalState("No encrypted private key present".to_string()))
-			.unwrap();
-		let key_pair = get_private_key_from_nep2(encrypted_private_key, password).unwrap();
-		self.key_pair =
-			Some(KeyPair::from_private_key(&vec_to_array32(key_pair).unwrap()).unwrap());
-		Ok(())
+		self.decrypt_private_key_with_params(password, &ScryptParamsDef::default())
 	}
 
 	fn encrypt_private_key(&mut self, password: &str) -> Result<(), Self::Error> {
-		let key_pair = self
-			.key_pair
-			.as_ref()
-			.ok_or(Self::Error::IllegalState("No decrypted key pair present".to_string()))
-			.unwrap();
-		let encrypted_private_key = get_nep2_from_private_key(
-			key_pair.private_key.to_raw_bytes().to_hex().as_str(),
-			password,
-		)
-		.unwrap();
-		self.encrypted_private_key = Some(encrypted_private_key);
-		self.key_pair = None;
-		Ok(())
+		self.encrypt_private_key_with_params(password, &ScryptParamsDef::default())
 	}
 
 	fn get_script_hash(&self) -> ScriptHash {
@@ -586,8 +597,8 @@ mod tests {
 			expected_key_pair.public_key.get_encoded(false)
 		);
 		assert_eq!(
-			account.key_pair.clone().unwrap().private_key.to_vec(),
-			expected_key_pair.private_key.to_vec()
+			account.key_pair.clone().unwrap().expose_secret().to_vec(),
+			expected_key_pair.expose_secret().to_vec()
 		);
 		let addr = account.address_or_scripthash();
 		assert_eq!(addr.address(), TestConstants::DEFAULT_ACCOUNT_ADDRESS);
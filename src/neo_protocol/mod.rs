@@ -3,8 +3,10 @@ mod nep2;
 mod protocol_error;
 mod responses;
 mod role;
+mod state_proof;
 
 pub use account::*;
 pub use nep2::*;
 pub use protocol_error::*;
 pub use responses::*;
+pub use state_proof::*;
@@ -8,29 +8,35 @@
 //!
 //! - Encrypt private keys using a password to produce a NEP2-formatted string.
 //! - Decrypt NEP2 strings back into private keys using the correct password.
-//! - Integration with AES encryption and scrypt key derivation for robust security.
+//! - Scrypt key derivation with the parameters of the wallet and AES-256-ECB encryption, both
+//!   implemented in pure Rust.
+//! - Encrypt and decrypt the keys of many accounts in parallel.
 //!
 //! ## Usage
 //!
 //! - Encrypt a private key to a NEP2 string:
-//!   - Use `NEP2::encrypt` with a password and a `KeyPair` containing the private key.
+//!   - Use `NEP2::encrypt` with a password, a `KeyPair` containing the private key and the scrypt
+//!     parameters of the wallet.
 //!
 //! - Decrypt a NEP2 string to obtain the private key:
-//!   - Use `NEP2::decrypt` with the password and the NEP2 string.
+//!   - Use `NEP2::decrypt` with the password, the NEP2 string and the scrypt parameters.
+//!
+//! - Encrypt or decrypt many keys at once:
+//!   - Use `NEP2::encrypt_all` or `NEP2::decrypt_all`, which derive the keys in parallel.
 //!
 //! ## Examples
 //!
 //! ```
 //! use p256::elliptic_curve::rand_core::OsRng;
-//! use scrypt::Params;
-//! use neo_rs::prelude::{KeyPair, NEP2, Secp256r1PrivateKey};
+//! use neo_rs::prelude::{KeyPair, NEP2, ScryptParamsDef, Secp256r1PrivateKey};
 //!
 //! // To encrypt a private key:
 //! let key_pair = KeyPair::from_secret_key(&Secp256r1PrivateKey::random(&mut OsRng));
-//! let encrypted = NEP2::encrypt("your-password", &key_pair, Params::new(14, 8, 8, 32).unwrap()).expect("Encryption failed");
+//! let params = ScryptParamsDef::default();
+//! let encrypted = NEP2::encrypt("your-password", &key_pair, &params).expect("Encryption failed");
 //!
 //! // To decrypt a NEP2 string:
-//! let decrypted_key_pair = NEP2::decrypt("your-password", &encrypted, Params::new(14, 8, 8, 32).unwrap()).expect("Decryption failed");
+//! let decrypted_key_pair = NEP2::decrypt("your-password", &encrypted, &params).expect("Decryption failed");
 //! ```
 //!
 //! ## Testing
@@ -40,21 +46,22 @@
 //!
 //! ## Error Handling
 //!
-//! Proper error handling is implemented to deal with common issues like incorrect password, invalid NEP2 format,
-//! and other cryptographic errors.
-
-use openssl;
+//! Failures are reported as [`Nep2Error`]s: a wrong passphrase is detected by comparing the
+//! address hash of the decrypted key with the one in the NEP2 string, and malformed strings,
+//! invalid scrypt parameters and invalid keys have variants of their own.
 
-use neo::prelude::{
-	base58check_decode, public_key_to_address, vec_to_array32, HashableForVec, KeyPair,
-	NeoConstants, ProviderError, Secp256r1PublicKey, ToBase58,
+use aes::{
+	cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit},
+	Aes256,
 };
-use openssl::symm::{Cipher, Crypter, Mode};
-use rustc_serialize::hex::FromHex;
+use rayon::prelude::*;
 use scrypt::{scrypt, Params};
+use zeroize::Zeroizing;
 
-type Aes256EcbEnc = ecb::Encryptor<aes::Aes256>;
-type Aes256EcbDec = ecb::Decryptor<aes::Aes256>;
+use neo::prelude::{
+	base58check_decode, base58check_encode, public_key_to_address, HashableForVec, KeyPair,
+	Nep2Error, ScryptParamsDef,
+};
 
 pub struct NEP2;
 
@@ -64,152 +71,145 @@ impl NEP2 {
 	const NEP2_PREFIX_1: u8 = 0x01;
 	const NEP2_PREFIX_2: u8 = 0x42;
 	const NEP2_FLAGBYTE: u8 = 0xE0;
-}
-
-fn encrypt_aes256_ecb(data: &[u8], key: &[u8]) -> Result<Vec<u8>, ProviderError> {
-	// Ensure key is the correct length for AES-256
-	assert_eq!(key.len(), 32);
-
-	let cipher = Cipher::aes_256_ecb();
-	let mut crypter = Crypter::new(cipher, Mode::Encrypt, key, None)
-		.map_err(|_| ProviderError::InvalidPassword)?;
-
-	let mut output = vec![0; data.len() + cipher.block_size()];
-	let count = crypter.update(data, &mut output).map_err(|_| ProviderError::InvalidPassword)?;
-	let rest = crypter
-		.finalize(&mut output[count..])
-		.map_err(|_| ProviderError::InvalidPassword)?;
-	output.truncate(count + rest);
-	Ok(output)
-}
-
-fn decrypt_aes256_ecb(encrypted_data: &[u8], key: &[u8]) -> Result<Vec<u8>, ProviderError> {
-	// Ensure key is the correct length for AES-256
-	assert_eq!(key.len(), 32);
-
-	let cipher = Cipher::aes_256_ecb();
-	let mut crypter = Crypter::new(cipher, Mode::Decrypt, key, None)
-		.map_err(|_| ProviderError::InvalidPassword)?;
-
-	let mut output = vec![0; encrypted_data.len() + cipher.block_size()];
-	let count = crypter
-		.update(encrypted_data, &mut output)
-		.map_err(|_| ProviderError::InvalidPassword)?;
-	let rest = crypter
-		.finalize(&mut output[count..])
-		.map_err(|_| ProviderError::InvalidPassword)?;
-	output.truncate(count + rest);
-	Ok(output)
-}
-
-pub fn get_nep2_from_private_key(pri_key: &str, passphrase: &str) -> Result<String, ProviderError> {
-	let private_key = pri_key.from_hex().unwrap();
-
-	let key_pair = KeyPair::from_private_key(&vec_to_array32(private_key.to_vec()).unwrap())?;
-
-	let addresshash: [u8; 4] = address_hash_from_pubkey(&key_pair.public_key.get_encoded(true));
-
-	let mut result = vec![0u8; NeoConstants::SCRYPT_DK_LEN];
-	let params =
-		Params::new(NeoConstants::SCRYPT_LOG_N, NeoConstants::SCRYPT_R, NeoConstants::SCRYPT_P, 32)
-			.unwrap();
-
-	scrypt(passphrase.as_bytes(), addresshash.to_vec().as_slice(), &params, &mut result).unwrap();
-
-	let half_1 = &result[0..32];
-	let _half_2 = &result[32..64];
-	let mut u8xor = [0u8; 32];
-
-	for i in 0..32 {
-		u8xor[i] = &private_key[i] ^ half_1[i];
+	const AES_BLOCK_SIZE: usize = 16;
+
+	/// Encrypts the private key of `key_pair` with `password`, deriving the encryption key with
+	/// the scrypt parameters `params`.
+	pub fn encrypt(
+		password: &str,
+		key_pair: &KeyPair,
+		params: &ScryptParamsDef,
+	) -> Result<String, Nep2Error> {
+		let private_key = key_pair.private_key_bytes();
+		let address_hash = Self::address_hash(key_pair);
+		let derived = Self::derive_key(password, &address_hash, params)?;
+
+		let mut xored = Zeroizing::new([0u8; 32]);
+		for i in 0..32 {
+			xored[i] = private_key[i] ^ derived[i];
+		}
+		let cipher = Aes256::new(GenericArray::from_slice(&derived[32..]));
+		for block in xored.chunks_mut(Self::AES_BLOCK_SIZE) {
+			cipher.encrypt_block(GenericArray::from_mut_slice(block));
+		}
+
+		let mut assembled = Vec::with_capacity(Self::NEP2_PRIVATE_KEY_LENGTH);
+		assembled.extend([Self::NEP2_PREFIX_1, Self::NEP2_PREFIX_2, Self::NEP2_FLAGBYTE]);
+		assembled.extend(address_hash);
+		assembled.extend_from_slice(xored.as_slice());
+		Ok(base58check_encode(&assembled))
 	}
 
-	let encrypted = encrypt_aes256_ecb(&u8xor.to_vec(), &private_key)?;
-
-	// # Assemble the final result
-	let mut assembled = Vec::new();
-
-	assembled.push(NeoConstants::NEP_HEADER_1);
-	assembled.push(NeoConstants::NEP_HEADER_2);
-	assembled.push(NeoConstants::NEP_FLAG);
-	assembled.extend(addresshash.to_vec());
-	assembled.extend(encrypted);
-
-	// # Finally, encode with Base58Check
-	Ok(assembled.to_base58())
-}
-
-pub fn get_private_key_from_nep2(nep2: &str, passphrase: &str) -> Result<Vec<u8>, ProviderError> {
-	if nep2.len() != 58 {
-		println!("Wrong Nep2");
-		()
+	/// Decrypts the NEP2 string `nep2` with `password` and the scrypt parameters it was encrypted
+	/// with.
+	///
+	/// Fails with [`Nep2Error::InvalidPassphrase`] if the address of the decrypted key does not
+	/// match the address hash in `nep2`, which is the case for a wrong password or wrong scrypt
+	/// parameters.
+	pub fn decrypt(
+		password: &str,
+		nep2: &str,
+		params: &ScryptParamsDef,
+	) -> Result<KeyPair, Nep2Error> {
+		let decoded = base58check_decode(nep2.trim()).ok_or_else(|| {
+			Nep2Error::InvalidFormat("Not a valid base58check string".to_string())
+		})?;
+		if decoded.len() != Self::NEP2_PRIVATE_KEY_LENGTH {
+			return Err(Nep2Error::InvalidFormat(format!(
+				"Expected {} bytes, got {}",
+				Self::NEP2_PRIVATE_KEY_LENGTH,
+				decoded.len()
+			)))
+		}
+		if decoded[..3] != [Self::NEP2_PREFIX_1, Self::NEP2_PREFIX_2, Self::NEP2_FLAGBYTE] {
+			return Err(Nep2Error::InvalidFormat("Invalid NEP2 prefix or flag".to_string()))
+		}
+
+		let address_hash = &decoded[3..7];
+		let derived = Self::derive_key(password, address_hash, params)?;
+		let mut private_key = Zeroizing::new([0u8; 32]);
+		private_key.copy_from_slice(&decoded[7..]);
+		let cipher = Aes256::new(GenericArray::from_slice(&derived[32..]));
+		for block in private_key.chunks_mut(Self::AES_BLOCK_SIZE) {
+			cipher.decrypt_block(GenericArray::from_mut_slice(block));
+		}
+		for i in 0..32 {
+			private_key[i] ^= derived[i];
+		}
+
+		// A wrong passphrase yields some other key, or no valid key at all.
+		let key_pair = KeyPair::from_private_key(&private_key)
+			.map_err(|_| Nep2Error::InvalidPassphrase("Address hash mismatch".to_string()))?;
+		if Self::address_hash(&key_pair) != address_hash {
+			return Err(Nep2Error::InvalidPassphrase("Address hash mismatch".to_string()))
+		}
+		Ok(key_pair)
 	}
-	let decoded_key: [u8; 39] = base58check_decode(nep2).unwrap().try_into().unwrap();
-
-	let address_hash: &[u8] = &decoded_key[3..7];
-	let encrypted: &[u8] = &decoded_key[7..39];
-
-	// pwd_normalized = bytes(unicodedata.normalize('NFC', passphrase), 'utf-8')
-	let mut result = vec![0u8; NeoConstants::SCRYPT_DK_LEN];
-	let params =
-		Params::new(NeoConstants::SCRYPT_LOG_N, NeoConstants::SCRYPT_R, NeoConstants::SCRYPT_P, 32)
-			.unwrap();
-
-	scrypt(passphrase.as_bytes(), &address_hash, &params, &mut result).unwrap();
 
-	// derived = scrypt.hash(pwd_normalized, address_hash,
-	//                       N=SCRYPT_ITERATIONS,
-	//                       r=SCRYPT_BLOCKSIZE,
-	//                       p=SCRYPT_PARALLEL_FACTOR,
-	//                       buflen=SCRYPT_KEY_LEN_BYTES)
-
-	let half_1 = &result[0..32];
-	let half_2 = &result[32..64];
-
-	// derived1 = derived[:32]
-	// derived2 = derived[32:]
-
-	let decrypted = encrypt_aes256_ecb(half_2, encrypted)?;
+	/// Encrypts many key pairs with the same password, deriving the keys in parallel.
+	pub fn encrypt_all(
+		password: &str,
+		key_pairs: &[KeyPair],
+		params: &ScryptParamsDef,
+	) -> Result<Vec<String>, Nep2Error> {
+		key_pairs
+			.par_iter()
+			.map(|key_pair| Self::encrypt(password, key_pair, params))
+			.collect()
+	}
 
-	let mut pri_key = [0u8; 32];
+	/// Decrypts many NEP2 strings with the same password, deriving the keys in parallel.
+	pub fn decrypt_all(
+		password: &str,
+		nep2s: &[String],
+		params: &ScryptParamsDef,
+	) -> Result<Vec<KeyPair>, Nep2Error> {
+		nep2s.par_iter().map(|nep2| Self::decrypt(password, nep2, params)).collect()
+	}
 
-	for i in 0..32 {
-		pri_key[i] = decrypted[i] ^ half_1[i];
+	fn derive_key(
+		password: &str,
+		address_hash: &[u8],
+		params: &ScryptParamsDef,
+	) -> Result<Zeroizing<[u8; 64]>, Nep2Error> {
+		let params = Params::new(params.log_n, params.r, params.p, Self::DKLEN)
+			.map_err(|e| Nep2Error::InvalidScryptParams(e.to_string()))?;
+		let mut derived = Zeroizing::new([0u8; 64]);
+		scrypt(password.as_bytes(), address_hash, &params, derived.as_mut_slice())
+			.map_err(|e| Nep2Error::InvalidScryptParams(e.to_string()))?;
+		Ok(derived)
 	}
-	// cipher = Aes.new(derived2, Aes.MODE_ECB)
-	// decrypted = cipher.decrypt(encrypted)
-	// private_key = xor_bytes(decrypted, derived1)
-
-	let key_pair = KeyPair::from_private_key(&pri_key)?;
-	let kp_addresshash: [u8; 4] = address_hash_from_pubkey(&key_pair.public_key.get_encoded(true));
-
-	// # Now check that the address hashes match. If they don't, the password was wrong.
-	// kp_new = KeyPair(priv_key=private_key)
-	// kp_new_address = kp_new.get_address()
-	// kp_new_address_hash_tmp = hashlib.sha256(kp_new_address.encode("utf-8")).digest()
-	// kp_new_address_hash_tmp2 = hashlib.sha256(kp_new_address_hash_tmp).digest()
-	// kp_new_address_hash = kp_new_address_hash_tmp2[:4]
-	if kp_addresshash != address_hash {
-		println!("Wrong Passphrase");
+
+	/// The first 4 bytes of the double SHA-256 of the address of `key_pair`.
+	fn address_hash(key_pair: &KeyPair) -> [u8; 4] {
+		let address = public_key_to_address(&key_pair.public_key());
+		let hash = address.as_bytes().hash256().hash256();
+		let mut result = [0u8; 4];
+		result.copy_from_slice(&hash[..4]);
+		result
 	}
+}
 
-	Ok(pri_key.to_vec())
+/// Encrypts the hex encoded private key `pri_key` with the default scrypt parameters.
+pub fn get_nep2_from_private_key(pri_key: &str, passphrase: &str) -> Result<String, Nep2Error> {
+	let bytes = Zeroizing::new(hex::decode(pri_key.trim_start_matches("0x")).unwrap_or_default());
+	let private_key: Zeroizing<[u8; 32]> =
+		Zeroizing::new(bytes.as_slice().try_into().map_err(|_| {
+			Nep2Error::InvalidPrivateKey("Expected 32 hex encoded bytes".to_string())
+		})?);
+	let key_pair = KeyPair::from_private_key(&private_key)
+		.map_err(|e| Nep2Error::InvalidPrivateKey(e.to_string()))?;
+	NEP2::encrypt(passphrase, &key_pair, &ScryptParamsDef::default())
 }
 
-/// Computes a hash from a public key and extracts the first 4 bytes.
-///
-/// # Arguments
-///
-/// * `pubkey` - The public key.
-///
-/// Returns the first 4 bytes of the hash.
-fn address_hash_from_pubkey(pubkey: &[u8]) -> [u8; 4] {
-	let addr = public_key_to_address(&Secp256r1PublicKey::from_bytes(pubkey).unwrap());
-	let hash = addr.as_bytes();
-	let hash = hash.hash256().hash256();
-	let mut result = [0u8; 4];
-	result.copy_from_slice(&hash[..4]);
-	result
+/// Decrypts `nep2` with the default scrypt parameters, returning the raw private key, which is
+/// zeroized when dropped.
+pub fn get_private_key_from_nep2(
+	nep2: &str,
+	passphrase: &str,
+) -> Result<Zeroizing<Vec<u8>>, Nep2Error> {
+	let key_pair = NEP2::decrypt(passphrase, nep2, &ScryptParamsDef::default())?;
+	Ok(Zeroizing::new(key_pair.private_key_bytes().to_vec()))
 }
 
 #[cfg(test)]
@@ -217,6 +217,11 @@ mod tests {
 	use super::*;
 	use neo::prelude::TestConstants;
 
+	// Cheap parameters to keep the tests fast.
+	fn light_params() -> ScryptParamsDef {
+		ScryptParamsDef { log_n: 8, r: 8, p: 1 }
+	}
+
 	#[test]
 	fn test_decrypt_with_default_scrypt_params() {
 		let decrypted_key_pair = match get_private_key_from_nep2(
@@ -227,7 +232,7 @@ mod tests {
 			Err(_) => panic!("Decryption failed"),
 		};
 		assert_eq!(
-			decrypted_key_pair,
+			*decrypted_key_pair,
 			hex::decode(TestConstants::DEFAULT_ACCOUNT_PRIVATE_KEY).unwrap()
 		);
 	}
@@ -241,4 +246,66 @@ mod tests {
 		.unwrap();
 		assert_eq!(encrypted, TestConstants::DEFAULT_ACCOUNT_ENCRYPTED_PRIVATE_KEY);
 	}
+
+	#[test]
+	fn test_encrypt_and_decrypt_with_custom_scrypt_params() {
+		let key_pair = KeyPair::new_random();
+		let encrypted = NEP2::encrypt("pw", &key_pair, &light_params()).unwrap();
+		let decrypted = NEP2::decrypt("pw", &encrypted, &light_params()).unwrap();
+		assert_eq!(decrypted.private_key_bytes(), key_pair.private_key_bytes());
+
+		// The parameters are part of the key derivation.
+		assert!(matches!(
+			NEP2::decrypt("pw", &encrypted, &ScryptParamsDef { log_n: 9, r: 8, p: 1 }),
+			Err(Nep2Error::InvalidPassphrase(_))
+		));
+	}
+
+	#[test]
+	fn test_decrypt_with_wrong_passphrase() {
+		let encrypted = NEP2::encrypt("pw", &KeyPair::new_random(), &light_params()).unwrap();
+		assert!(matches!(
+			NEP2::decrypt("wrong", &encrypted, &light_params()),
+			Err(Nep2Error::InvalidPassphrase(_))
+		));
+	}
+
+	#[test]
+	fn test_decrypt_invalid_format() {
+		assert!(matches!(
+			NEP2::decrypt("pw", "not base58", &light_params()),
+			Err(Nep2Error::InvalidFormat(_))
+		));
+		// A valid base58check string of the wrong length.
+		let short = base58check_encode(&[0x01, 0x42, 0xE0]);
+		assert!(matches!(
+			NEP2::decrypt("pw", &short, &light_params()),
+			Err(Nep2Error::InvalidFormat(_))
+		));
+		assert!(matches!(
+			get_nep2_from_private_key("0102", "pw"),
+			Err(Nep2Error::InvalidPrivateKey(_))
+		));
+	}
+
+	#[test]
+	fn test_invalid_scrypt_params() {
+		let params = ScryptParamsDef { log_n: 8, r: 0, p: 1 };
+		assert!(matches!(
+			NEP2::encrypt("pw", &KeyPair::new_random(), &params),
+			Err(Nep2Error::InvalidScryptParams(_))
+		));
+	}
+
+	#[test]
+	fn test_encrypt_and_decrypt_all() {
+		let key_pairs = (0..4).map(|_| KeyPair::new_random()).collect::<Vec<_>>();
+		let encrypted = NEP2::encrypt_all("pw", &key_pairs, &light_params()).unwrap();
+		assert_eq!(encrypted.len(), 4);
+
+		let decrypted = NEP2::decrypt_all("pw", &encrypted, &light_params()).unwrap();
+		for (decrypted, key_pair) in decrypted.iter().zip(&key_pairs) {
+			assert_eq!(decrypted.private_key_bytes(), key_pair.private_key_bytes());
+		}
+	}
 }
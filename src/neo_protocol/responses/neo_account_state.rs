@@ -12,22 +12,31 @@ pub struct AccountState {
 	#[serde(deserialize_with = "deserialize_public_key_option")]
 	#[serde(serialize_with = "serialize_public_key_option")]
 	pub public_key: Option<Secp256r1PublicKey>,
+	/// The GAS per vote of the voted candidate when the vote or balance last changed.
+	#[serde(default)]
+	pub last_gas_per_vote: Option<i64>,
 }
 
 impl Hash for AccountState {
 	fn hash<H: Hasher>(&self, state: &mut H) {
 		self.balance.hash(state);
 		self.balance_height.hash(state);
-		self.public_key.clone().unwrap().to_vec().hash(state);
+		self.public_key.as_ref().map(|key| key.to_vec()).hash(state);
+		self.last_gas_per_vote.hash(state);
 	}
 }
 
 impl AccountState {
 	pub fn with_no_vote(balance: i64, update_height: i64) -> Self {
-		Self { balance, balance_height: Some(update_height), public_key: None }
+		Self {
+			balance,
+			balance_height: Some(update_height),
+			public_key: None,
+			last_gas_per_vote: None,
+		}
 	}
 
 	pub fn with_no_balance() -> Self {
-		Self { balance: 0, balance_height: None, public_key: None }
+		Self { balance: 0, balance_height: None, public_key: None, last_gas_per_vote: None }
 	}
 }
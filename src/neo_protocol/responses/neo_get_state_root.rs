@@ -1,4 +1,7 @@
-use neo::prelude::{deserialize_h256, serialize_h256, Witness};
+use neo::prelude::{
+	deserialize_h256, serialize_h256, BuilderError, Bytes, Encoder, HashableForVec, NeoWitness,
+	Secp256r1PublicKey, VerificationScript, Witness,
+};
 use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 
@@ -10,5 +13,126 @@ pub struct StateRoot {
 	#[serde(serialize_with = "serialize_h256")]
 	#[serde(deserialize_with = "deserialize_h256")]
 	pub root_hash: H256,
-	pub witnesses: Vec<Witness>,
+	pub witnesses: Vec<NeoWitness>,
+}
+
+impl StateRoot {
+	/// Returns the data signed by the state validators, i.e. the network magic followed by the
+	/// hash of the state root.
+	pub fn get_hash_data_for_network(&self, network: u32) -> Bytes {
+		let mut writer = Encoder::new();
+		writer.write_u8(self.version as u8);
+		writer.write_u32(self.index);
+		let mut root_hash = self.root_hash.as_bytes().to_vec();
+		root_hash.reverse();
+		writer.write_bytes(&root_hash);
+
+		let mut data = network.to_le_bytes().to_vec();
+		data.extend(writer.to_bytes().hash256());
+		data
+	}
+
+	/// Checks that the state root is signed by the BFT multi-sig account of `validators`, the
+	/// keys designated as `Role::StateValidator` for its block index.
+	pub fn verify_witness(
+		&self,
+		validators: &[Secp256r1PublicKey],
+		network: u32,
+	) -> Result<(), BuilderError> {
+		let [witness] = self.witnesses.as_slice() else {
+			return Err(BuilderError::InvalidScript(format!(
+				"A state root must have one witness, got {}",
+				self.witnesses.len()
+			)))
+		};
+		if validators.is_empty() {
+			return Err(BuilderError::IllegalArgument("No state validators given".to_string()))
+		}
+		let witness = Witness::try_from(witness)?;
+
+		let threshold = validators.len() - (validators.len() - 1) / 3;
+		let expected =
+			VerificationScript::from_multi_sig(&mut validators.to_vec(), threshold as u8);
+		if witness.verification != expected {
+			return Err(BuilderError::InvalidScript(
+				"The state root is not signed by the state validators".to_string(),
+			))
+		}
+		witness.verify(&self.get_hash_data_for_network(network))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H256;
+
+	use neo::prelude::{
+		BuilderError, KeyPair, NeoWitness, Secp256r1PublicKey, StateRoot, VerificationScript,
+		Witness,
+	};
+
+	const NETWORK: u32 = 860833102;
+
+	fn validators() -> Vec<KeyPair> {
+		let mut validators = (0..4).map(|_| KeyPair::new_random()).collect::<Vec<_>>();
+		validators.sort_by_key(|validator| validator.public_key());
+		validators
+	}
+
+	fn public_keys(validators: &[KeyPair]) -> Vec<Secp256r1PublicKey> {
+		validators.iter().map(|validator| validator.public_key()).collect()
+	}
+
+	/// A state root signed by the validators at `signers`, with the 3-of-4 BFT account.
+	fn state_root(validators: &[KeyPair], signers: &[usize]) -> StateRoot {
+		let mut state_root = StateRoot {
+			version: 0,
+			index: 160,
+			root_hash: H256::repeat_byte(0xab),
+			witnesses: vec![],
+		};
+		let data = state_root.get_hash_data_for_network(NETWORK);
+		let signatures = signers
+			.iter()
+			.map(|i| validators[*i].expose_secret().sign_tx(&data).unwrap())
+			.collect();
+		let verification = VerificationScript::from_multi_sig(&mut public_keys(validators), 3);
+		let witness = Witness::create_multi_sig_witness_script(signatures, verification).unwrap();
+		state_root.witnesses = vec![NeoWitness::new(
+			base64::encode(witness.invocation.script()),
+			base64::encode(witness.verification.script()),
+		)];
+		state_root
+	}
+
+	#[test]
+	fn test_verify_witness() {
+		let validators = validators();
+		let keys = public_keys(&validators);
+
+		let state_root = state_root(&validators, &[0, 1, 3]);
+		assert!(state_root.verify_witness(&keys, NETWORK).is_ok());
+		assert!(state_root.verify_witness(&keys, NETWORK + 1).is_err());
+
+		let mut tampered = state_root.clone();
+		tampered.index += 1;
+		assert!(tampered.verify_witness(&keys, NETWORK).is_err());
+	}
+
+	#[test]
+	fn test_verify_witness_of_other_validators() {
+		let validators = validators();
+		let state_root = state_root(&validators, &[0, 1, 2]);
+
+		let others = public_keys(&self::validators());
+		assert!(matches!(
+			state_root.verify_witness(&others, NETWORK),
+			Err(BuilderError::InvalidScript(_))
+		));
+		assert!(state_root.verify_witness(&[], NETWORK).is_err());
+
+		let mut unsigned = state_root.clone();
+		unsigned.witnesses.clear();
+		assert!(unsigned.verify_witness(&public_keys(&validators), NETWORK).is_err());
+	}
 }
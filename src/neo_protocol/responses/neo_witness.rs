@@ -1,5 +1,8 @@
+use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
 
+use neo::prelude::{CodecError, Witness};
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
 pub struct NeoWitness {
 	pub invocation: String,
@@ -11,3 +14,16 @@ impl NeoWitness {
 		Self { invocation, verification }
 	}
 }
+
+impl TryFrom<&NeoWitness> for Witness {
+	type Error = CodecError;
+
+	fn try_from(witness: &NeoWitness) -> Result<Self, Self::Error> {
+		let decode = |script: &str| {
+			general_purpose::STANDARD
+				.decode(script)
+				.map_err(|e| CodecError::InvalidEncoding(e.to_string()))
+		};
+		Ok(Witness::from_scripts(decode(&witness.invocation)?, decode(&witness.verification)?))
+	}
+}
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose, Engine};
+use getset::Getters;
+use primitive_types::H256;
+
+use neo::prelude::{Bytes, CodecError, Decoder, HashableForVec, TypeError};
+
+/// A proof that a storage item is part of the state with a given `StateRoot::root_hash`, as
+/// returned by `getproof`.
+///
+/// The proof holds the nodes of the Merkle Patricia Trie on the path from the state root to the
+/// storage item, so `verify` does not need to trust the node that created it.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let state_root = provider.get_state_root(index).await?;
+/// let proof = provider.get_proof(state_root.root_hash, contract_hash, &key_base64).await?;
+/// let value = StateProof::from_base64(&proof)?.verify(&state_root.root_hash, contract_id, &key)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Getters)]
+pub struct StateProof {
+	/// The id of the contract the storage item belongs to.
+	#[getset(get = "pub")]
+	contract_id: i32,
+	/// The storage key within the contract.
+	#[getset(get = "pub")]
+	key: Bytes,
+	/// The serialized trie nodes, in no particular order.
+	#[getset(get = "pub")]
+	nodes: Vec<Bytes>,
+}
+
+impl StateProof {
+	const MAX_KEY_SIZE: usize = 64;
+	const MAX_VALUE_SIZE: usize = u16::MAX as usize;
+	const MAX_NODES: u64 = u8::MAX as u64;
+
+	pub fn new(contract_id: i32, key: Bytes, nodes: Vec<Bytes>) -> Self {
+		Self { contract_id, key, nodes }
+	}
+
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, TypeError> {
+		let mut reader = Decoder::new(bytes);
+		let storage_key = reader.read_var_bytes_max(4 + Self::MAX_KEY_SIZE)?;
+		if storage_key.len() < 4 {
+			return Err(CodecError::InvalidEncoding("Invalid storage key".to_string()).into())
+		}
+		let count = reader.read_var_int_max(Self::MAX_NODES)?;
+		let nodes = (0..count)
+			.map(|_| reader.read_var_bytes_max(MptNode::MAX_SIZE))
+			.collect::<Result<Vec<_>, _>>()?;
+		if reader.available() > 0 {
+			return Err(CodecError::InvalidEncoding(format!(
+				"{} unexpected bytes after the proof",
+				reader.available()
+			))
+			.into())
+		}
+
+		let contract_id = i32::from_le_bytes(storage_key[..4].try_into().unwrap());
+		Ok(Self::new(contract_id, storage_key[4..].to_vec(), nodes))
+	}
+
+	/// Decodes a base64 encoded proof, the form returned by `getproof`.
+	pub fn from_base64(base64: &str) -> Result<Self, TypeError> {
+		let bytes = general_purpose::STANDARD
+			.decode(base64.trim())
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Verifies the proof against the state root `root_hash` and returns the proven value of the
+	/// storage item `key` of the contract with id `contract_id`.
+	///
+	/// Fails if the proof is for another storage item or does not lead from the state root to the
+	/// item.
+	pub fn verify(
+		&self,
+		root_hash: &H256,
+		contract_id: i32,
+		key: &[u8],
+	) -> Result<Bytes, TypeError> {
+		if self.contract_id != contract_id || self.key != key {
+			return Err(TypeError::InvalidData(format!(
+				"The proof is for key {} of contract {}",
+				hex::encode(&self.key),
+				self.contract_id
+			)))
+		}
+
+		let nodes = self
+			.nodes
+			.iter()
+			.map(|node| (node.hash256().hash256(), node))
+			.collect::<HashMap<_, _>>();
+
+		let mut storage_key = contract_id.to_le_bytes().to_vec();
+		storage_key.extend_from_slice(key);
+		let nibbles = storage_key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect::<Vec<u8>>();
+
+		let mut root = root_hash.as_bytes().to_vec();
+		root.reverse();
+		let mut node = MptNode::Hash(root);
+		let mut path = nibbles.as_slice();
+		loop {
+			node = match node {
+				MptNode::Hash(hash) => {
+					let data = nodes.get(hash.as_slice()).ok_or_else(|| {
+						TypeError::InvalidData(format!(
+							"The proof is missing the trie node {}",
+							hex::encode(&hash)
+						))
+					})?;
+					MptNode::decode_stored(data)?
+				},
+				MptNode::Leaf(value) if path.is_empty() => return Ok(value),
+				MptNode::Branch(mut children) =>
+					if path.is_empty() {
+						children.swap_remove(MptNode::BRANCH_VALUE_INDEX)
+					} else {
+						let child = children.swap_remove(path[0] as usize);
+						path = &path[1..];
+						child
+					},
+				MptNode::Extension { key, next } if path.starts_with(&key) => {
+					path = &path[key.len()..];
+					*next
+				},
+				_ =>
+					return Err(TypeError::InvalidData(
+						"The proof does not contain the storage item".to_string(),
+					)),
+			};
+		}
+	}
+}
+
+/// A node of the Merkle Patricia Trie of the state, as serialized in proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MptNode {
+	/// 16 children for the next nibble of the path and one for the value at this path.
+	Branch(Vec<MptNode>),
+	/// A shared part of the paths of the nodes below, in nibbles.
+	Extension {
+		key: Bytes,
+		next: Box<MptNode>,
+	},
+	Leaf(Bytes),
+	/// A reference to a node by the double SHA-256 of its serialization.
+	Hash(Bytes),
+	Empty,
+}
+
+impl MptNode {
+	const BRANCH: u8 = 0x00;
+	const EXTENSION: u8 = 0x01;
+	const LEAF: u8 = 0x02;
+	const HASH: u8 = 0x03;
+	const EMPTY: u8 = 0x04;
+
+	const BRANCH_CHILD_COUNT: usize = 17;
+	const BRANCH_VALUE_INDEX: usize = 16;
+	const MAX_KEY_LENGTH: usize = (StateProof::MAX_KEY_SIZE + 4) * 2;
+	const MAX_SIZE: usize = 3 + StateProof::MAX_VALUE_SIZE + 1 + Self::MAX_KEY_LENGTH;
+
+	/// Decodes a node of the proof, whose children are references.
+	fn decode_stored(data: &[u8]) -> Result<Self, TypeError> {
+		let mut reader = Decoder::new(data);
+		let node = match reader.read_u8()? {
+			Self::BRANCH => {
+				let children = (0..Self::BRANCH_CHILD_COUNT)
+					.map(|_| Self::decode_child(&mut reader))
+					.collect::<Result<Vec<_>, _>>()?;
+				MptNode::Branch(children)
+			},
+			Self::EXTENSION => {
+				let key = reader.read_var_bytes_max(Self::MAX_KEY_LENGTH)?;
+				if key.is_empty() || key.iter().any(|nibble| *nibble > 0x0f) {
+					return Err(
+						CodecError::InvalidEncoding("Invalid extension key".to_string()).into()
+					)
+				}
+				MptNode::Extension { key, next: Box::new(Self::decode_child(&mut reader)?) }
+			},
+			Self::LEAF => MptNode::Leaf(reader.read_var_bytes_max(StateProof::MAX_VALUE_SIZE)?),
+			t =>
+				return Err(
+					CodecError::InvalidEncoding(format!("Invalid trie node type {}", t)).into()
+				),
+		};
+		if reader.available() > 0 {
+			return Err(CodecError::InvalidEncoding("Invalid trie node".to_string()).into())
+		}
+		Ok(node)
+	}
+
+	fn decode_child(reader: &mut Decoder) -> Result<Self, TypeError> {
+		match reader.read_u8()? {
+			Self::HASH => Ok(MptNode::Hash(reader.read_bytes(32)?)),
+			Self::EMPTY => Ok(MptNode::Empty),
+			t => Err(CodecError::InvalidEncoding(format!("Invalid trie child type {}", t)).into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::H256;
+
+	use neo::prelude::{Encoder, HashableForVec, StateProof, TypeError};
+
+	const CONTRACT_ID: i32 = 5;
+
+	fn hash(node: &[u8]) -> Vec<u8> {
+		node.hash256().hash256().to_vec()
+	}
+
+	fn leaf(value: &[u8]) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		writer.write_u8(0x02);
+		writer.write_var_bytes(value);
+		writer.to_bytes()
+	}
+
+	fn branch(children: &[(usize, &[u8])]) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		writer.write_u8(0x00);
+		for i in 0..17 {
+			match children.iter().find(|(index, _)| *index == i) {
+				Some((_, child)) => {
+					writer.write_u8(0x03);
+					writer.write_bytes(&hash(child));
+				},
+				None => writer.write_u8(0x04),
+			}
+		}
+		writer.to_bytes()
+	}
+
+	fn extension(key: &[u8], next: &[u8]) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		writer.write_u8(0x01);
+		writer.write_var_bytes(key);
+		writer.write_u8(0x03);
+		writer.write_bytes(&hash(next));
+		writer.to_bytes()
+	}
+
+	/// A trie holding the keys 0x01 and 0x02 of contract 5, i.e. the storage keys 0500000001 and
+	/// 0500000002, which share all nibbles but the last.
+	fn trie() -> (H256, Vec<Vec<u8>>) {
+		let leaf1 = leaf(b"first");
+		let leaf2 = leaf(b"second");
+		let branch = branch(&[(1, &leaf1), (2, &leaf2)]);
+		let root = extension(&[0, 5, 0, 0, 0, 0, 0, 0, 0], &branch);
+
+		let mut root_hash = hash(&root);
+		root_hash.reverse();
+		(H256::from_slice(&root_hash), vec![root, branch, leaf1, leaf2])
+	}
+
+	fn proof_bytes(key: &[u8], nodes: &[Vec<u8>]) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		let mut storage_key = CONTRACT_ID.to_le_bytes().to_vec();
+		storage_key.extend_from_slice(key);
+		writer.write_var_bytes(&storage_key);
+		writer.write_var_int(nodes.len() as i64);
+		for node in nodes {
+			writer.write_var_bytes(node);
+		}
+		writer.to_bytes()
+	}
+
+	#[test]
+	fn test_decode() {
+		let (_, nodes) = trie();
+		let bytes = proof_bytes(&[0x01], &nodes[..3]);
+
+		let proof = StateProof::from_base64(&base64::encode(&bytes)).unwrap();
+		assert_eq!(*proof.contract_id(), CONTRACT_ID);
+		assert_eq!(proof.key(), &vec![0x01]);
+		assert_eq!(proof.nodes().len(), 3);
+
+		assert!(StateProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+	}
+
+	#[test]
+	fn test_verify() {
+		let (root_hash, nodes) = trie();
+
+		let proof = StateProof::from_bytes(&proof_bytes(
+			&[0x01],
+			&[nodes[2].clone(), nodes[0].clone(), nodes[1].clone()],
+		))
+		.unwrap();
+		assert_eq!(proof.verify(&root_hash, CONTRACT_ID, &[0x01]).unwrap(), b"first".to_vec());
+
+		let proof = StateProof::new(
+			CONTRACT_ID,
+			vec![0x02],
+			vec![nodes[0].clone(), nodes[1].clone(), nodes[3].clone()],
+		);
+		assert_eq!(proof.verify(&root_hash, CONTRACT_ID, &[0x02]).unwrap(), b"second".to_vec());
+	}
+
+	#[test]
+	fn test_verify_rejects_invalid_proofs() {
+		let (root_hash, nodes) = trie();
+		let proof = StateProof::new(CONTRACT_ID, vec![0x01], nodes[..3].to_vec());
+
+		// Another storage item or state root.
+		assert!(matches!(
+			proof.verify(&root_hash, CONTRACT_ID, &[0x02]),
+			Err(TypeError::InvalidData(_))
+		));
+		assert!(proof.verify(&root_hash, CONTRACT_ID + 1, &[0x01]).is_err());
+		assert!(proof.verify(&H256::repeat_byte(0x01), CONTRACT_ID, &[0x01]).is_err());
+
+		// A missing or tampered node.
+		let missing = StateProof::new(CONTRACT_ID, vec![0x01], nodes[..2].to_vec());
+		assert!(missing.verify(&root_hash, CONTRACT_ID, &[0x01]).is_err());
+
+		let tampered = StateProof::new(
+			CONTRACT_ID,
+			vec![0x01],
+			vec![nodes[0].clone(), nodes[1].clone(), leaf(b"forged")],
+		);
+		assert!(tampered.verify(&root_hash, CONTRACT_ID, &[0x01]).is_err());
+
+		// A key the trie does not hold.
+		let absent = StateProof::new(CONTRACT_ID, vec![0x03], nodes[..2].to_vec());
+		assert!(absent.verify(&root_hash, CONTRACT_ID, &[0x03]).is_err());
+	}
+}
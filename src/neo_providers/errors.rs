@@ -3,7 +3,7 @@ use std::{error::Error, fmt::Debug};
 use thiserror::Error;
 
 use neo::{
-	prelude::{CryptoError, JsonRpcError, TypeError},
+	prelude::{CryptoError, JsonRpcError, NeoRpcErrorCode, Nep2Error, TypeError},
 	providers::middleware::MiddlewareError,
 };
 
@@ -51,6 +51,11 @@ pub trait RpcError: Error + Debug + Send + Sync {
 	fn is_serde_error(&self) -> bool {
 		self.as_serde_error().is_some()
 	}
+
+	/// Returns the Neo RPC error code of an underlying JSON-RPC error response (if any)
+	fn error_code(&self) -> Option<NeoRpcErrorCode> {
+		self.as_error_response()?.error_code()
+	}
 }
 
 #[derive(Debug, Error)]
@@ -96,6 +101,8 @@ pub enum ProviderError {
 	TypeError(#[from] TypeError),
 	#[error("Invalid password")]
 	InvalidPassword,
+	#[error(transparent)]
+	Nep2Error(#[from] Nep2Error),
 }
 
 impl PartialEq for ProviderError {
@@ -115,6 +122,7 @@ impl PartialEq for ProviderError {
 			(ProviderError::CryptoError(a), ProviderError::CryptoError(b)) => a == b,
 			(ProviderError::TypeError(a), ProviderError::TypeError(b)) => a == b,
 			(ProviderError::InvalidPassword, ProviderError::InvalidPassword) => true,
+			(ProviderError::Nep2Error(a), ProviderError::Nep2Error(b)) => a == b,
 			_ => false,
 		}
 	}
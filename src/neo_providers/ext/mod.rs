@@ -3,6 +3,7 @@ pub mod admin;
 pub use admin::{NodeInfo, PeerInfo};
 
 pub mod nns;
+pub use nns::{NnsMiddleware, NnsMiddlewareError};
 
 #[cfg(feature = "dev-rpc")]
 pub mod dev_rpc;
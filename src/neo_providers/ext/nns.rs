@@ -1 +1,534 @@
+//! A middleware resolving NNS names, e.g. to let users send assets to `alice.neo`.
+//!
+//! Resolved records are cached until a configurable time to live passes or the name expires,
+//! whichever comes first.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use neo_rs::prelude::*;
+//!
+//! # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = Provider::<Http>::try_from(NeoConstants::SEED_1)?;
+//! let client = NnsMiddleware::new(provider);
+//!
+//! let recipient = client.resolve_name(&NNSName::new("alice.neo")?).await?;
+//! let name = client.lookup_address(recipient).await?;
+//! assert_eq!(name.name(), "alice.neo");
+//! # Ok(()) }
+//! ```
 
+use std::{
+	collections::HashMap,
+	fmt::Debug,
+	str::FromStr,
+	sync::Mutex,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use primitive_types::H160;
+use thiserror::Error;
+
+use neo::prelude::{
+	AddressOrScriptHash, CallFlags, ContractParameter, InvocationResult, Middleware,
+	MiddlewareError, NNSName, NameOrAccount, NameState, ProviderError, RecordType, ScriptBuilder,
+	ScriptHashExtension, StackItem,
+};
+
+/// `NnsMiddleware`
+#[derive(Debug)]
+pub struct NnsMiddleware<M> {
+	inner: M,
+	max_redirects: usize,
+	ttl: Duration,
+	cache: Mutex<HashMap<(String, RecordType), CachedRecords>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedRecords {
+	records: Vec<String>,
+	/// Milliseconds since the Unix epoch.
+	expires_at: u64,
+}
+
+/// NnsMiddleware Errors
+#[derive(Error, Debug)]
+pub enum NnsMiddlewareError<M: Middleware> {
+	/// Internal Middleware error
+	#[error("{0}")]
+	MiddlewareError(M::Error),
+
+	/// Internal Provider error
+	#[error("{0}")]
+	ProviderError(ProviderError),
+
+	/// The name has no record of the requested type
+	#[error("NNS name {0} has no {1} record")]
+	RecordNotFound(String, RecordType),
+
+	/// The name is registered, but its registration has expired
+	#[error("NNS name {0} has expired")]
+	NameExpired(String),
+
+	/// The CNAME records of the name redirect more often than allowed
+	#[error("Too many CNAME redirects resolving {0}")]
+	TooManyRedirects(String),
+
+	/// A record or the result of the NNS contract is malformed
+	#[error("Invalid NNS record: {0}")]
+	InvalidRecord(String),
+
+	/// None of the names owned by the account resolves to it
+	#[error("No NNS name of {0:#x} resolves to it")]
+	NoReverseName(H160),
+}
+
+#[async_trait]
+impl<M: Middleware> Middleware for NnsMiddleware<M> {
+	type Error = NnsMiddlewareError<M>;
+	type Provider = M::Provider;
+	type Inner = M;
+
+	fn inner(&self) -> &M {
+		&self.inner
+	}
+
+	async fn network(&self) -> u32 {
+		self.inner.network().await
+	}
+}
+
+impl<M: Middleware> MiddlewareError for NnsMiddlewareError<M> {
+	type Inner = M::Error;
+
+	fn from_err(src: M::Error) -> NnsMiddlewareError<M> {
+		NnsMiddlewareError::MiddlewareError(src)
+	}
+
+	fn as_inner(&self) -> Option<&Self::Inner> {
+		match self {
+			NnsMiddlewareError::MiddlewareError(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl<M> From<ProviderError> for NnsMiddlewareError<M>
+where
+	M: Middleware,
+{
+	fn from(src: ProviderError) -> Self {
+		Self::ProviderError(src)
+	}
+}
+
+impl<M: Middleware> NnsMiddleware<M> {
+	/// The number of CNAME redirects the NNS contract itself follows.
+	pub const DEFAULT_MAX_REDIRECTS: usize = 2;
+	/// How long resolved records are cached, unless the name expires earlier.
+	pub const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+	/// The number of names of an account `lookup_address` considers.
+	const MAX_REVERSE_CANDIDATES: u32 = 100;
+
+	const RESOLVE: &'static str = "resolve";
+	const PROPERTIES: &'static str = "properties";
+	const TOKENS_OF: &'static str = "tokensOf";
+
+	/// Instantiate a new `NnsMiddleware`
+	pub fn new(inner: M) -> Self {
+		Self {
+			inner,
+			max_redirects: Self::DEFAULT_MAX_REDIRECTS,
+			ttl: Self::DEFAULT_TTL,
+			cache: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Sets the number of CNAME redirects followed before resolution fails.
+	pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+		self.max_redirects = max_redirects;
+		self
+	}
+
+	/// Sets how long resolved records are cached. A zero duration disables the cache.
+	pub fn with_ttl(mut self, ttl: Duration) -> Self {
+		self.ttl = ttl;
+		self
+	}
+
+	/// Removes all cached records, e.g. after changing the records of a name.
+	pub fn clear_cache(&self) {
+		self.cache.lock().unwrap().clear();
+	}
+
+	/// Resolves the address of `name` from its TXT records, which hold either an address or a
+	/// hex script hash.
+	pub async fn resolve_name(&self, name: &NNSName) -> Result<H160, NnsMiddlewareError<M>> {
+		let records = self.resolve_records(name, RecordType::TXT).await?;
+		records
+			.iter()
+			.find_map(|record| Self::parse_script_hash(record))
+			.ok_or_else(|| {
+				NnsMiddlewareError::InvalidRecord(format!(
+					"No TXT record of {} is an address: {:?}",
+					name, records
+				))
+			})
+	}
+
+	/// Returns the script hash of `account`, resolving it first if it is an NNS name.
+	pub async fn resolve_script_hash(
+		&self,
+		account: &NameOrAccount,
+	) -> Result<H160, NnsMiddlewareError<M>> {
+		match account {
+			NameOrAccount::Name(name) => self.resolve_name(name).await,
+			NameOrAccount::Account(AddressOrScriptHash::Address(address)) =>
+				H160::from_address(address).map_err(|e| NnsMiddlewareError::ProviderError(e.into())),
+			NameOrAccount::Account(AddressOrScriptHash::ScriptHash(script_hash)) =>
+				Ok(*script_hash),
+		}
+	}
+
+	/// Resolves `account` to the `AddressOrScriptHash` that APIs taking an account expect.
+	pub async fn resolve_account(
+		&self,
+		account: &NameOrAccount,
+	) -> Result<AddressOrScriptHash, NnsMiddlewareError<M>> {
+		match account {
+			NameOrAccount::Name(name) =>
+				Ok(AddressOrScriptHash::ScriptHash(self.resolve_name(name).await?)),
+			NameOrAccount::Account(account) => Ok(account.clone()),
+		}
+	}
+
+	/// Resolves the records of `name` of the given type, following CNAME records up to the
+	/// configured number of redirects.
+	pub async fn resolve_records(
+		&self,
+		name: &NNSName,
+		record_type: RecordType,
+	) -> Result<Vec<String>, NnsMiddlewareError<M>> {
+		let mut aliases = Vec::new();
+		let mut current = name.clone();
+		let (records, mut expires_at) = loop {
+			if let Some(cached) = self.cached(&current, record_type) {
+				break (cached.records, cached.expires_at)
+			}
+
+			let records = self.fetch_records(&current, record_type).await?;
+			if !records.is_empty() || record_type == RecordType::CNAME {
+				let expires_at = self.expires_at(&current).await?;
+				self.cache(&current, record_type, &records, expires_at);
+				break (records, expires_at)
+			}
+
+			let target = match self.fetch_records(&current, RecordType::CNAME).await?.pop() {
+				Some(target) => target,
+				None =>
+					return Err(NnsMiddlewareError::RecordNotFound(current.to_string(), record_type)),
+			};
+			if aliases.len() == self.max_redirects {
+				return Err(NnsMiddlewareError::TooManyRedirects(name.to_string()))
+			}
+			let target = NNSName::new(target.trim_end_matches('.'))
+				.map_err(|e| NnsMiddlewareError::InvalidRecord(e.to_string()))?;
+			aliases.push(std::mem::replace(&mut current, target));
+		};
+
+		// An alias is valid as long as both the alias and its target are.
+		for alias in aliases.iter().rev() {
+			expires_at = expires_at.min(self.expires_at(alias).await?);
+			self.cache(alias, record_type, &records, expires_at);
+		}
+		Ok(records)
+	}
+
+	/// Finds a name owned by `script_hash` that resolves back to it.
+	///
+	/// NNS has no reverse records, so only names whose TXT record points to the owner are
+	/// considered, the same check a forward resolution would do.
+	pub async fn lookup_address(
+		&self,
+		script_hash: H160,
+	) -> Result<NNSName, NnsMiddlewareError<M>> {
+		let script = ScriptBuilder::build_contract_call_and_unwrap_iterator(
+			&self.nns_resolver(),
+			Self::TOKENS_OF,
+			&[script_hash.into()],
+			Self::MAX_REVERSE_CANDIDATES,
+			Some(CallFlags::ReadOnly),
+		)
+		.map_err(|e| NnsMiddlewareError::InvalidRecord(e.to_string()))?;
+		let output = self
+			.inner
+			.invoke_script(hex::encode(&script), vec![])
+			.await
+			.map_err(NnsMiddlewareError::MiddlewareError)?;
+
+		for item in Self::first_item(&output)?.as_array().unwrap_or_default() {
+			let name = match item.as_bytes().and_then(|bytes| String::from_utf8(bytes).ok()) {
+				Some(name) => name,
+				None => continue,
+			};
+			let name = match NNSName::new(&name) {
+				Ok(name) => name,
+				Err(_) => continue,
+			};
+			match self.resolve_name(&name).await {
+				Ok(resolved) if resolved == script_hash => return Ok(name),
+				Ok(_)
+				| Err(NnsMiddlewareError::RecordNotFound(..))
+				| Err(NnsMiddlewareError::NameExpired(_))
+				| Err(NnsMiddlewareError::TooManyRedirects(_))
+				| Err(NnsMiddlewareError::InvalidRecord(_)) => continue,
+				Err(e) => return Err(e),
+			}
+		}
+		Err(NnsMiddlewareError::NoReverseName(script_hash))
+	}
+
+	async fn fetch_records(
+		&self,
+		name: &NNSName,
+		record_type: RecordType,
+	) -> Result<Vec<String>, NnsMiddlewareError<M>> {
+		let params = vec![
+			ContractParameter::from(name.name().as_str()),
+			ContractParameter::from(record_type.byte_repr()),
+		];
+		let output = self
+			.inner
+			.invoke_function(&self.nns_resolver(), Self::RESOLVE.to_string(), params, None)
+			.await
+			.map_err(NnsMiddlewareError::MiddlewareError)?;
+
+		// Depending on the contract version a name resolves to a single record or an array.
+		let item = Self::first_item(&output)?;
+		let items = match item {
+			StackItem::Any => vec![],
+			StackItem::Array { value } => value.clone(),
+			item => vec![item.clone()],
+		};
+		items
+			.iter()
+			.map(|item| {
+				item.as_bytes().and_then(|bytes| String::from_utf8(bytes).ok()).ok_or_else(|| {
+					NnsMiddlewareError::InvalidRecord(format!(
+						"{} record of {} is not a string",
+						record_type, name
+					))
+				})
+			})
+			.collect()
+	}
+
+	/// Returns when cached records of `name` expire, failing if the name already has.
+	async fn expires_at(&self, name: &NNSName) -> Result<u64, NnsMiddlewareError<M>> {
+		let domain = name.second_level_domain();
+		let output = self
+			.inner
+			.invoke_function(
+				&self.nns_resolver(),
+				Self::PROPERTIES.to_string(),
+				vec![domain.as_str().into()],
+				None,
+			)
+			.await
+			.map_err(NnsMiddlewareError::MiddlewareError)?;
+		let state = NameState::from_stack_item(Self::first_item(&output)?)
+			.map_err(|e| NnsMiddlewareError::InvalidRecord(e.to_string()))?;
+
+		let now = Self::now();
+		if state.is_expired(now) {
+			return Err(NnsMiddlewareError::NameExpired(domain))
+		}
+		Ok(state.expiration.min(now.saturating_add(self.ttl.as_millis() as u64)))
+	}
+
+	fn cached(&self, name: &NNSName, record_type: RecordType) -> Option<CachedRecords> {
+		let mut cache = self.cache.lock().unwrap();
+		let key = (name.name().clone(), record_type);
+		match cache.get(&key) {
+			Some(cached) if cached.expires_at > Self::now() => Some(cached.clone()),
+			Some(_) => {
+				cache.remove(&key);
+				None
+			},
+			None => None,
+		}
+	}
+
+	fn cache(&self, name: &NNSName, record_type: RecordType, records: &[String], expires_at: u64) {
+		if self.ttl.is_zero() {
+			return
+		}
+		self.cache.lock().unwrap().insert(
+			(name.name().clone(), record_type),
+			CachedRecords { records: records.to_vec(), expires_at },
+		);
+	}
+
+	fn first_item(output: &InvocationResult) -> Result<&StackItem, NnsMiddlewareError<M>> {
+		if output.has_state_fault() {
+			return Err(NnsMiddlewareError::InvalidRecord(format!(
+				"The NNS contract faulted: {}",
+				output.exception.clone().unwrap_or_default()
+			)))
+		}
+		output.stack.first().ok_or_else(|| {
+			NnsMiddlewareError::InvalidRecord("The result stack is empty".to_string())
+		})
+	}
+
+	fn parse_script_hash(record: &str) -> Option<H160> {
+		let record = record.trim();
+		H160::from_address(record)
+			.ok()
+			.or_else(|| H160::from_str(record.trim_start_matches("0x")).ok())
+	}
+
+	fn now() -> u64 {
+		SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		str::FromStr,
+		time::{Duration, SystemTime, UNIX_EPOCH},
+	};
+
+	use primitive_types::H160;
+
+	use neo::prelude::{
+		AddressOrScriptHash, MapEntry, MockProvider, NNSName, NameOrAccount, NnsMiddleware,
+		NnsMiddlewareError, Provider, RecordType, ScriptHashExtension, StackItem,
+	};
+
+	fn string(value: &str) -> StackItem {
+		StackItem::ByteString { value: hex::encode(value) }
+	}
+
+	fn properties(name: &str, expiration: u64) -> StackItem {
+		StackItem::Map {
+			value: vec![
+				MapEntry::new(string("name"), string(name)),
+				MapEntry::new(
+					string("expiration"),
+					StackItem::Integer { value: expiration as i64 },
+				),
+			],
+		}
+	}
+
+	fn in_a_year() -> u64 {
+		(SystemTime::now() + Duration::from_secs(365 * 24 * 60 * 60))
+			.duration_since(UNIX_EPOCH)
+			.unwrap()
+			.as_millis() as u64
+	}
+
+	fn mock(results: Vec<StackItem>) -> (NnsMiddleware<Provider<MockProvider>>, MockProvider) {
+		let (provider, mock) = Provider::mocked();
+		mock.push_invocations(results).unwrap();
+		(NnsMiddleware::new(provider), mock)
+	}
+
+	#[tokio::test]
+	async fn test_resolve_name_is_cached() {
+		let owner = H160::repeat_byte(0x42);
+		let (client, _mock) =
+			mock(vec![string(&owner.to_address()), properties("alice.neo", in_a_year())]);
+		let name = NNSName::new("alice.neo").unwrap();
+
+		assert_eq!(client.resolve_name(&name).await.unwrap(), owner);
+		// The mock has no more results, so this is served from the cache.
+		assert_eq!(client.resolve_name(&name).await.unwrap(), owner);
+
+		client.clear_cache();
+		assert!(client.resolve_name(&name).await.is_err());
+	}
+
+	#[tokio::test]
+	async fn test_resolve_name_or_account() {
+		let owner = H160::repeat_byte(0x42);
+		let (client, _mock) =
+			mock(vec![string(&owner.to_address()), properties("alice.neo", in_a_year())]);
+
+		let name = NameOrAccount::from_str("alice.neo").unwrap();
+		assert_eq!(client.resolve_script_hash(&name).await.unwrap(), owner);
+		assert_eq!(client.resolve_account(&name).await.unwrap().script_hash(), owner);
+
+		// Addresses and script hashes are returned without querying the node
+		let address = NameOrAccount::from_str(&owner.to_address()).unwrap();
+		assert_eq!(client.resolve_script_hash(&address).await.unwrap(), owner);
+		let script_hash = NameOrAccount::from(owner);
+		assert_eq!(
+			client.resolve_account(&script_hash).await.unwrap(),
+			AddressOrScriptHash::ScriptHash(owner)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_resolve_follows_cname() {
+		let owner = H160::repeat_byte(0x42);
+		let (client, _mock) = mock(vec![
+			StackItem::Any,
+			string("alice.neo"),
+			StackItem::Array { value: vec![string(&format!("{:#x}", owner))] },
+			properties("alice.neo", in_a_year()),
+			properties("alice.neo", in_a_year()),
+		]);
+		let name = NNSName::new("pay.alice.neo").unwrap();
+
+		assert_eq!(client.resolve_name(&name).await.unwrap(), owner);
+		assert_eq!(client.resolve_name(&name).await.unwrap(), owner);
+	}
+
+	#[tokio::test]
+	async fn test_resolve_limits_redirects() {
+		let (client, _mock) = mock(vec![StackItem::Any, string("alice.neo")]);
+		let client = client.with_max_redirects(0);
+
+		assert!(matches!(
+			client
+				.resolve_records(&NNSName::new("pay.alice.neo").unwrap(), RecordType::A)
+				.await,
+			Err(NnsMiddlewareError::TooManyRedirects(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_resolve_expired_or_missing_name() {
+		let (client, _mock) = mock(vec![string("10.0.0.1"), properties("alice.neo", 1)]);
+		assert!(matches!(
+			client.resolve_records(&NNSName::new("alice.neo").unwrap(), RecordType::A).await,
+			Err(NnsMiddlewareError::NameExpired(_))
+		));
+
+		let (client, _mock) = mock(vec![StackItem::Any, StackItem::Any]);
+		assert!(matches!(
+			client
+				.resolve_records(&NNSName::new("alice.neo").unwrap(), RecordType::AAAA)
+				.await,
+			Err(NnsMiddlewareError::RecordNotFound(_, RecordType::AAAA))
+		));
+	}
+
+	#[tokio::test]
+	async fn test_lookup_address() {
+		let owner = H160::repeat_byte(0x42);
+		let (client, _mock) = mock(vec![
+			StackItem::Array { value: vec![string("bob.neo"), string("alice.neo")] },
+			string(&H160::repeat_byte(0x01).to_address()),
+			properties("bob.neo", in_a_year()),
+			string(&owner.to_address()),
+			properties("alice.neo", in_a_year()),
+		]);
+
+		assert_eq!(client.lookup_address(owner).await.unwrap().name(), "alice.neo");
+	}
+}
@@ -90,6 +90,12 @@ pub(crate) trait MiddlewareError: Error + Sized + Send + Sync {
 	fn is_error_response(&self) -> bool {
 		self.as_error_response().is_some()
 	}
+
+	/// Returns the Neo RPC error code of an underlying JSON-RPC error response (if any), so
+	/// callers can branch on the kind of error instead of matching messages.
+	fn error_code(&self) -> Option<NeoRpcErrorCode> {
+		self.as_error_response()?.error_code()
+	}
 }
 
 #[async_trait]
@@ -285,6 +291,13 @@ pub trait Middleware: Sync + Send + Debug {
 		self.inner().send_raw_transaction(hex).await.map_err(MiddlewareError::from_err)
 	}
 
+	async fn submit_notary_request(&self, payload: String) -> Result<RawTransaction, Self::Error> {
+		self.inner()
+			.submit_notary_request(payload)
+			.await
+			.map_err(MiddlewareError::from_err)
+	}
+
 	async fn submit_block(&self, hex: String) -> Result<bool, Self::Error> {
 		self.inner().submit_block(hex).await.map_err(MiddlewareError::from_err)
 	}
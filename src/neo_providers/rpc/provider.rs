@@ -351,6 +351,16 @@ impl<P: JsonRpcClient> Middleware for Provider<P> {
 		self.request("sendrawtransaction", vec![hex.to_value()]).await
 	}
 
+	/// Submits a `P2PNotaryRequest` to the notary service of a neo-go node.
+	/// - Parameter payload: The serialized request in base64, see `P2PNotaryRequest::to_base64`
+	/// - Returns: The request object, holding the hash of the notary request
+	async fn submit_notary_request(
+		&self,
+		payload: String,
+	) -> Result<RawTransaction, ProviderError> {
+		self.request("submitnotaryrequest", vec![payload.to_value()]).await
+	}
+
 	/// Broadcasts a new block over the NEO network.
 	/// - Parameter serializedBlockAsHex: The block in hexadecimal
 	/// - Returns: The request object
@@ -12,7 +12,7 @@ use serde::{
 use serde_json::{value::RawValue, Value};
 use thiserror::Error;
 
-use neo::prelude::Bytes;
+use neo::prelude::{Bytes, NeoRpcErrorCode};
 
 /// A JSON-RPC 2.0 error
 #[derive(Deserialize, Debug, Clone, Error, PartialEq)]
@@ -38,6 +38,11 @@ fn spelunk_revert(value: &Value) -> Option<Bytes> {
 }
 
 impl JsonRpcError {
+	/// Returns the Neo RPC error code of the response, if it is a known one.
+	pub fn error_code(&self) -> Option<NeoRpcErrorCode> {
+		NeoRpcErrorCode::from_error(self)
+	}
+
 	/// Determine if the error output of the `neo_call` RPC request is a revert
 	///
 	/// Note that this may return false positives if called on an error from
@@ -0,0 +1,288 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::JsonRpcError;
+
+/// The error codes of the Neo RPC server, as defined by neo-modules since 2023.
+///
+/// Nodes before that return `-100` for every unknown item and `-500` for every rejected
+/// transaction, naming the reason in the message. [`NeoRpcErrorCode::from_error`] maps those
+/// messages to the same codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NeoRpcErrorCode {
+	// JSON-RPC
+	InvalidRequest,
+	MethodNotFound,
+	InvalidParams,
+	InternalServerError,
+	BadRequest,
+
+	// Unknown items
+	UnknownBlock,
+	UnknownContract,
+	UnknownTransaction,
+	UnknownStorageItem,
+	UnknownScriptContainer,
+	UnknownStateRoot,
+	UnknownSession,
+	UnknownIterator,
+	UnknownHeight,
+
+	// Wallet
+	InsufficientFundsWallet,
+	WalletFeeLimit,
+	NoOpenedWallet,
+	WalletNotFound,
+	WalletNotSupported,
+
+	// Transaction verification
+	VerificationFailed,
+	AlreadyExists,
+	MempoolCapReached,
+	AlreadyInPool,
+	InsufficientNetworkFee,
+	PolicyFailed,
+	InvalidScript,
+	InvalidAttribute,
+	InvalidSignature,
+	InvalidSize,
+	ExpiredTransaction,
+	InsufficientFunds,
+	InvalidContractVerification,
+
+	// Node state and services
+	AccessDenied,
+	SessionsDisabled,
+	OracleDisabled,
+	OracleRequestFinished,
+	OracleRequestNotFound,
+	OracleNotDesignatedNode,
+	UnsupportedState,
+	InvalidProof,
+	ExecutionFailed,
+}
+
+impl NeoRpcErrorCode {
+	const LEGACY_UNKNOWN_ITEM: i64 = -100;
+	const LEGACY_VERIFICATION_FAILED: i64 = -500;
+
+	const ALL: [NeoRpcErrorCode; 41] = [
+		Self::InvalidRequest,
+		Self::MethodNotFound,
+		Self::InvalidParams,
+		Self::InternalServerError,
+		Self::BadRequest,
+		Self::UnknownBlock,
+		Self::UnknownContract,
+		Self::UnknownTransaction,
+		Self::UnknownStorageItem,
+		Self::UnknownScriptContainer,
+		Self::UnknownStateRoot,
+		Self::UnknownSession,
+		Self::UnknownIterator,
+		Self::UnknownHeight,
+		Self::InsufficientFundsWallet,
+		Self::WalletFeeLimit,
+		Self::NoOpenedWallet,
+		Self::WalletNotFound,
+		Self::WalletNotSupported,
+		Self::VerificationFailed,
+		Self::AlreadyExists,
+		Self::MempoolCapReached,
+		Self::AlreadyInPool,
+		Self::InsufficientNetworkFee,
+		Self::PolicyFailed,
+		Self::InvalidScript,
+		Self::InvalidAttribute,
+		Self::InvalidSignature,
+		Self::InvalidSize,
+		Self::ExpiredTransaction,
+		Self::InsufficientFunds,
+		Self::InvalidContractVerification,
+		Self::AccessDenied,
+		Self::SessionsDisabled,
+		Self::OracleDisabled,
+		Self::OracleRequestFinished,
+		Self::OracleRequestNotFound,
+		Self::OracleNotDesignatedNode,
+		Self::UnsupportedState,
+		Self::InvalidProof,
+		Self::ExecutionFailed,
+	];
+
+	/// Returns the numeric code sent by the node.
+	pub fn code(&self) -> i64 {
+		match self {
+			Self::InvalidRequest => -32600,
+			Self::MethodNotFound => -32601,
+			Self::InvalidParams => -32602,
+			Self::InternalServerError => -32603,
+			Self::BadRequest => -32700,
+			Self::UnknownBlock => -101,
+			Self::UnknownContract => -102,
+			Self::UnknownTransaction => -103,
+			Self::UnknownStorageItem => -104,
+			Self::UnknownScriptContainer => -105,
+			Self::UnknownStateRoot => -106,
+			Self::UnknownSession => -107,
+			Self::UnknownIterator => -108,
+			Self::UnknownHeight => -109,
+			Self::InsufficientFundsWallet => -300,
+			Self::WalletFeeLimit => -301,
+			Self::NoOpenedWallet => -302,
+			Self::WalletNotFound => -303,
+			Self::WalletNotSupported => -304,
+			Self::VerificationFailed => -500,
+			Self::AlreadyExists => -501,
+			Self::MempoolCapReached => -502,
+			Self::AlreadyInPool => -503,
+			Self::InsufficientNetworkFee => -504,
+			Self::PolicyFailed => -505,
+			Self::InvalidScript => -506,
+			Self::InvalidAttribute => -507,
+			Self::InvalidSignature => -508,
+			Self::InvalidSize => -509,
+			Self::ExpiredTransaction => -510,
+			Self::InsufficientFunds => -511,
+			Self::InvalidContractVerification => -512,
+			Self::AccessDenied => -600,
+			Self::SessionsDisabled => -601,
+			Self::OracleDisabled => -602,
+			Self::OracleRequestFinished => -603,
+			Self::OracleRequestNotFound => -604,
+			Self::OracleNotDesignatedNode => -605,
+			Self::UnsupportedState => -606,
+			Self::InvalidProof => -607,
+			Self::ExecutionFailed => -608,
+		}
+	}
+
+	/// Returns the error with the numeric `code`, if it is a known one.
+	pub fn from_code(code: i64) -> Option<Self> {
+		Self::ALL.iter().copied().find(|error| error.code() == code)
+	}
+
+	/// Classifies an error response, including the message based errors of older nodes.
+	pub fn from_error(error: &JsonRpcError) -> Option<Self> {
+		match error.code {
+			Self::LEGACY_UNKNOWN_ITEM => Self::from_legacy_unknown_item(&error.message),
+			Self::LEGACY_VERIFICATION_FAILED =>
+				Self::from_legacy_verify_result(&error.message).or(Some(Self::VerificationFailed)),
+			code => Self::from_code(code),
+		}
+	}
+
+	/// Returns `true` if sending the same request again later may succeed.
+	pub fn is_retryable(&self) -> bool {
+		matches!(self, Self::InternalServerError | Self::MempoolCapReached | Self::UnknownHeight)
+	}
+
+	/// Returns `true` if the node rejected a transaction.
+	pub fn is_transaction_rejection(&self) -> bool {
+		(-512..=-500).contains(&self.code())
+	}
+
+	fn from_legacy_unknown_item(message: &str) -> Option<Self> {
+		let message = message.to_lowercase();
+		[
+			("unknown block", Self::UnknownBlock),
+			("unknown contract", Self::UnknownContract),
+			("unknown transaction", Self::UnknownTransaction),
+			("unknown storage", Self::UnknownStorageItem),
+			("unknown session", Self::UnknownSession),
+			("unknown iterator", Self::UnknownIterator),
+			("unknown height", Self::UnknownHeight),
+		]
+		.into_iter()
+		.find(|(prefix, _)| message.starts_with(prefix))
+		.map(|(_, error)| error)
+	}
+
+	// The names of the `VerifyResult` enum of the node.
+	fn from_legacy_verify_result(message: &str) -> Option<Self> {
+		match message.trim() {
+			"AlreadyExists" => Some(Self::AlreadyExists),
+			"AlreadyInPool" => Some(Self::AlreadyInPool),
+			"OutOfMemory" => Some(Self::MempoolCapReached),
+			"InvalidScript" => Some(Self::InvalidScript),
+			"InvalidAttribute" => Some(Self::InvalidAttribute),
+			"InvalidSignature" => Some(Self::InvalidSignature),
+			"OverSize" => Some(Self::InvalidSize),
+			"Expired" => Some(Self::ExpiredTransaction),
+			"InsufficientFunds" => Some(Self::InsufficientFunds),
+			"PolicyFail" => Some(Self::PolicyFailed),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for NeoRpcErrorCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?} ({})", self, self.code())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{
+		JsonRpcError, Middleware, MockResponse, NeoRpcErrorCode, Provider, RpcError,
+	};
+
+	fn error(code: i64, message: &str) -> JsonRpcError {
+		JsonRpcError { code, message: message.to_string(), data: None }
+	}
+
+	#[test]
+	fn test_from_code() {
+		for error in NeoRpcErrorCode::ALL {
+			assert_eq!(NeoRpcErrorCode::from_code(error.code()), Some(error));
+		}
+		assert_eq!(NeoRpcErrorCode::from_code(-1), None);
+	}
+
+	#[test]
+	fn test_from_error() {
+		assert_eq!(
+			error(-511, "Insufficient funds").error_code(),
+			Some(NeoRpcErrorCode::InsufficientFunds)
+		);
+		assert_eq!(
+			error(-32602, "Invalid params").error_code(),
+			Some(NeoRpcErrorCode::InvalidParams)
+		);
+		assert_eq!(error(-1, "Custom").error_code(), None);
+	}
+
+	#[test]
+	fn test_from_legacy_error() {
+		assert_eq!(error(-500, "AlreadyExists").error_code(), Some(NeoRpcErrorCode::AlreadyExists));
+		assert_eq!(error(-500, "PolicyFail").error_code(), Some(NeoRpcErrorCode::PolicyFailed));
+		assert_eq!(
+			error(-500, "Something else").error_code(),
+			Some(NeoRpcErrorCode::VerificationFailed)
+		);
+		assert_eq!(
+			error(-100, "Unknown transaction").error_code(),
+			Some(NeoRpcErrorCode::UnknownTransaction)
+		);
+		assert_eq!(error(-100, "Unexpected").error_code(), None);
+	}
+
+	#[test]
+	fn test_classification() {
+		assert!(NeoRpcErrorCode::MempoolCapReached.is_retryable());
+		assert!(!NeoRpcErrorCode::InsufficientFunds.is_retryable());
+		assert!(NeoRpcErrorCode::InvalidContractVerification.is_transaction_rejection());
+		assert!(!NeoRpcErrorCode::UnknownBlock.is_transaction_rejection());
+	}
+
+	#[tokio::test]
+	async fn test_provider_error_code() {
+		let (provider, mock) = Provider::mocked();
+		mock.push_response(MockResponse::Error(error(-503, "Already in pool")));
+
+		let err = provider.send_raw_transaction("00".to_string()).await.unwrap_err();
+		assert_eq!(RpcError::error_code(&err), Some(NeoRpcErrorCode::AlreadyInPool));
+	}
+}
@@ -9,7 +9,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
-use neo::prelude::{JsonRpcClient, ProviderError, RpcError};
+use neo::prelude::{InvocationResult, JsonRpcClient, ProviderError, RpcError, StackItem};
 
 /// Helper type that can be used to pass through the `params` value.
 /// This is necessary because the wrapper provider is supposed to skip the `params` if it's of
@@ -103,7 +103,7 @@ impl MockProvider {
 		}
 	}
 
-	/// Pushes the data to the responses
+	/// Pushes the data to the responses, which are returned last in, first out
 	pub fn push<T: Serialize + Send + Sync, K: Borrow<T>>(&self, data: K) -> Result<(), MockError> {
 		let value = serde_json::to_value(data.borrow())?;
 		self.responses.lock().unwrap().push_back(MockResponse::Value(value));
@@ -114,6 +114,17 @@ impl MockProvider {
 	pub fn push_response(&self, response: MockResponse) {
 		self.responses.lock().unwrap().push_back(response);
 	}
+
+	/// Pushes a successful invocation result with `item` on its stack
+	pub fn push_invocation(&self, item: StackItem) -> Result<(), MockError> {
+		self.push(InvocationResult::halt(vec![item]))
+	}
+
+	/// Pushes successful invocation results with the items on their stacks, in the order the
+	/// client makes the invocations
+	pub fn push_invocations(&self, items: Vec<StackItem>) -> Result<(), MockError> {
+		items.into_iter().rev().try_for_each(|item| self.push_invocation(item))
+	}
 }
 
 #[derive(Error, Debug)]
@@ -1,4 +1,5 @@
 pub use common::*;
+pub use error_code::*;
 #[cfg(all(feature = "ipc", any(unix, windows)))]
 pub use ipc::{Ipc, IpcError};
 #[cfg(feature = "legacy-ws")]
@@ -18,6 +19,7 @@ mod ipc;
 // pub use quorum::{JsonRpcClientWrapper, Quorum, QuorumError, QuorumProvider, WeightedProvider};
 
 mod common;
+mod error_code;
 /// archival websocket
 #[cfg(feature = "legacy-ws")]
 pub mod legacy_ws;
@@ -1,12 +1,15 @@
 // This module demonstrates the flexibility in handling blockchain addresses and script hashes, leveraging Rust's type system
 // and trait implementations to provide a seamless interface for converting and working with these two fundamental types.
 
-use std::hash::{Hash, Hasher};
+use std::{
+	hash::{Hash, Hasher},
+	str::FromStr,
+};
 
 use primitive_types::H160;
 use serde_derive::{Deserialize, Serialize};
 
-use neo::prelude::{Address, AddressExtension, Bytes, ScriptHashExtension};
+use neo::prelude::{Address, AddressExtension, Bytes, ScriptHashExtension, TypeError};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// An enum that can represent either a blockchain `Address` or a `ScriptHash`,
@@ -75,6 +78,30 @@ impl From<Bytes> for AddressOrScriptHash {
 	}
 }
 
+impl FromStr for AddressOrScriptHash {
+	type Err = TypeError;
+
+	/// Parses an address or a hex script hash in its big-endian form. Use `NameOrAccount` to
+	/// also accept NNS names.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::str::FromStr;
+	/// use neo_rs::prelude::AddressOrScriptHash;
+	/// let script_hash =
+	/// 	AddressOrScriptHash::from_str("0x23ba2703c53263e8d6e522dc32203339dcd8eee9").unwrap();
+	/// assert!(matches!(script_hash, AddressOrScriptHash::ScriptHash(_)));
+	/// assert!(AddressOrScriptHash::from_str("alice.neo").is_err());
+	/// ```
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if H160::from_address(s).is_ok() {
+			return Ok(Self::Address(s.to_string()))
+		}
+		H160::from_str(s).map(Self::ScriptHash).map_err(|_| TypeError::InvalidAddress)
+	}
+}
+
 impl AddressOrScriptHash {
 	/// Retrieves the `Address` representation. If the instance is a `ScriptHash`, converts it to an `Address`.
 	///
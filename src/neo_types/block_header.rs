@@ -0,0 +1,324 @@
+use base64::{engine::general_purpose, Engine};
+use primitive_types::{H160, H256};
+
+use neo::prelude::{
+	BuilderError, Bytes, CodecError, Decoder, Encoder, HashableForVec, NeoSerializable,
+	VarSizeTrait, Witness,
+};
+
+/// The header of a block in its binary form, as returned by `getblockheader` when `verbose` is
+/// false.
+///
+/// Decoding a header does not trust the node: its hash is recomputed from the header fields and
+/// `verify_witness` checks the signatures of the consensus nodes.
+///
+/// # Example
+///
+/// ```ignore
+/// use neo_rs::prelude::*;
+///
+/// let previous = BlockHeader::from_base64(&provider.get_raw_block_header_by_index(99).await?)?;
+/// let header = BlockHeader::from_base64(&provider.get_raw_block_header_by_index(100).await?)?;
+/// header.verify_successor_of(&previous, provider.network().await)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockHeader {
+	pub version: u32,
+	pub prev_hash: H256,
+	pub merkle_root: H256,
+	/// Milliseconds since the Unix epoch.
+	pub timestamp: u64,
+	pub nonce: u64,
+	pub index: u32,
+	pub primary_index: u8,
+	/// The script hash of the consensus nodes that sign the next block.
+	pub next_consensus: H160,
+	pub witness: Witness,
+}
+
+impl BlockHeader {
+	const SIZE_WITHOUT_WITNESS: usize = 4 + 32 + 32 + 8 + 8 + 4 + 1 + 20;
+
+	/// Decodes a header, rejecting any bytes after it.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, BuilderError> {
+		let mut reader = Decoder::new(bytes);
+		let header = Self::decode(&mut reader)?;
+		if reader.available() > 0 {
+			return Err(CodecError::InvalidEncoding(format!(
+				"{} unexpected bytes after the block header",
+				reader.available()
+			))
+			.into())
+		}
+		Ok(header)
+	}
+
+	/// Decodes a base64 encoded header, the form returned by the RPC nodes.
+	pub fn from_base64(base64: &str) -> Result<Self, BuilderError> {
+		let bytes = general_purpose::STANDARD
+			.decode(base64.trim())
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	/// Returns the block hash, in the big-endian form used for display and RPC calls.
+	pub fn hash(&self) -> H256 {
+		let mut hash = self.hash_bytes();
+		hash.reverse();
+		H256::from_slice(&hash)
+	}
+
+	/// Returns the data signed by the consensus nodes, i.e. the network magic followed by the
+	/// block hash.
+	pub fn get_hash_data_for_network(&self, network: u32) -> Bytes {
+		let mut data = network.to_le_bytes().to_vec();
+		data.extend(self.hash_bytes());
+		data
+	}
+
+	/// Checks that the header is signed by the consensus nodes with the script hash
+	/// `next_consensus`, which is the `next_consensus` of the previous block.
+	pub fn verify_witness(&self, next_consensus: &H160, network: u32) -> Result<(), BuilderError> {
+		if self.witness.verification.hash() != *next_consensus {
+			return Err(BuilderError::InvalidScript(format!(
+				"The block is signed by {:#x} instead of the next consensus {:#x}",
+				self.witness.verification.hash(),
+				next_consensus
+			)))
+		}
+		self.witness.verify(&self.get_hash_data_for_network(network))
+	}
+
+	/// Checks that this header follows `previous` in the chain and is signed by the consensus
+	/// nodes `previous` designated.
+	pub fn verify_successor_of(
+		&self,
+		previous: &BlockHeader,
+		network: u32,
+	) -> Result<(), BuilderError> {
+		if previous.index.checked_add(1) != Some(self.index) || self.prev_hash != previous.hash() {
+			return Err(BuilderError::IllegalState(format!(
+				"Block {} does not follow block {} ({:#x})",
+				self.index,
+				previous.index,
+				previous.hash()
+			)))
+		}
+		self.verify_witness(&previous.next_consensus, network)
+	}
+
+	fn hash_bytes(&self) -> Bytes {
+		let mut writer = Encoder::new();
+		self.serialize_unsigned(&mut writer);
+		writer.to_bytes().hash256().to_vec()
+	}
+
+	fn serialize_unsigned(&self, writer: &mut Encoder) {
+		writer.write_u32(self.version);
+		writer.write_bytes(&reversed(self.prev_hash.as_bytes()));
+		writer.write_bytes(&reversed(self.merkle_root.as_bytes()));
+		writer.write_u64(self.timestamp);
+		writer.write_u64(self.nonce);
+		writer.write_u32(self.index);
+		writer.write_u8(self.primary_index);
+		writer.write_bytes(&reversed(self.next_consensus.as_bytes()));
+	}
+}
+
+impl NeoSerializable for BlockHeader {
+	type Error = BuilderError;
+
+	fn size(&self) -> usize {
+		Self::SIZE_WITHOUT_WITNESS + vec![self.witness.clone()].var_size()
+	}
+
+	fn encode(&self, writer: &mut Encoder) {
+		self.serialize_unsigned(writer);
+		writer.write_serializable_variable_list(&[self.witness.clone()]);
+	}
+
+	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
+		let version = reader.read_u32()?;
+		if version != 0 {
+			return Err(CodecError::InvalidEncoding(format!(
+				"Unsupported block version {}",
+				version
+			))
+			.into())
+		}
+		let prev_hash = H256::from_slice(&reversed(&reader.read_bytes(32)?));
+		let merkle_root = H256::from_slice(&reversed(&reader.read_bytes(32)?));
+		let timestamp = reader.read_u64()?;
+		let nonce = reader.read_u64()?;
+		let index = reader.read_u32()?;
+		let primary_index = reader.read_u8()?;
+		let next_consensus = H160::from_slice(&reversed(&reader.read_bytes(20)?));
+
+		let mut witnesses = reader.read_serializable_list_max::<Witness>(1)?;
+		let witness = witnesses.pop().ok_or_else(|| {
+			CodecError::InvalidEncoding("A block header must have one witness".to_string())
+		})?;
+
+		Ok(Self {
+			version,
+			prev_hash,
+			merkle_root,
+			timestamp,
+			nonce,
+			index,
+			primary_index,
+			next_consensus,
+			witness,
+		})
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+fn reversed(bytes: &[u8]) -> Bytes {
+	bytes.iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::{H160, H256};
+
+	use neo::prelude::{
+		BlockHeader, BuilderError, CryptoError, KeyPair, NeoSerializable, ScriptHashExtension,
+		VerificationScript, Witness,
+	};
+
+	const NETWORK: u32 = 860833102;
+
+	/// Three consensus nodes, sorted by public key, whose 2-of-3 account signs the blocks.
+	fn consensus_nodes() -> Vec<KeyPair> {
+		let mut nodes = (0..3).map(|_| KeyPair::new_random()).collect::<Vec<_>>();
+		nodes.sort_by_key(|node| node.public_key());
+		nodes
+	}
+
+	fn consensus_script(nodes: &[KeyPair]) -> VerificationScript {
+		let mut keys = nodes.iter().map(|node| node.public_key()).collect::<Vec<_>>();
+		VerificationScript::from_multi_sig(&mut keys, 2)
+	}
+
+	fn sign(header: &mut BlockHeader, nodes: &[KeyPair], signers: &[usize]) {
+		let data = header.get_hash_data_for_network(NETWORK);
+		let signatures = signers
+			.iter()
+			.map(|i| nodes[*i].expose_secret().sign_tx(&data).unwrap())
+			.collect::<Vec<_>>();
+		header.witness =
+			Witness::create_multi_sig_witness_script(signatures, consensus_script(nodes)).unwrap();
+	}
+
+	fn header(nodes: &[KeyPair]) -> BlockHeader {
+		let mut header = BlockHeader {
+			version: 0,
+			prev_hash: H256::repeat_byte(0x11),
+			merkle_root: H256::repeat_byte(0x22),
+			timestamp: 1_700_000_000_000,
+			nonce: 0x0102030405060708,
+			index: 100,
+			primary_index: 1,
+			next_consensus: consensus_script(nodes).hash(),
+			witness: Witness::new(),
+		};
+		sign(&mut header, nodes, &[0, 2]);
+		header
+	}
+
+	#[test]
+	fn test_encode_decode() {
+		let nodes = consensus_nodes();
+		let header = header(&nodes);
+		let bytes = header.to_array();
+		assert_eq!(bytes.len(), header.size());
+
+		let decoded = BlockHeader::from_bytes(&bytes).unwrap();
+		assert_eq!(decoded, header);
+		assert_eq!(decoded.hash(), header.hash());
+
+		let decoded = BlockHeader::from_base64(&base64::encode(&bytes)).unwrap();
+		assert_eq!(decoded, header);
+
+		// The hashes are little-endian on the wire.
+		assert_eq!(bytes[4], 0x11);
+		assert_eq!(&bytes[4 + 64 + 21..4 + 64 + 41], &header.next_consensus.to_le_vec()[..]);
+	}
+
+	#[test]
+	fn test_decode_malformed_header() {
+		let nodes = consensus_nodes();
+		let bytes = header(&nodes).to_array();
+		for len in 0..bytes.len() {
+			assert!(BlockHeader::from_bytes(&bytes[..len]).is_err());
+		}
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert!(BlockHeader::from_bytes(&trailing).is_err());
+
+		let mut version = bytes.clone();
+		version[0] = 1;
+		assert!(BlockHeader::from_bytes(&version).is_err());
+	}
+
+	#[test]
+	fn test_verify_witness() {
+		let nodes = consensus_nodes();
+		let header = header(&nodes);
+		let next_consensus = consensus_script(&nodes).hash();
+
+		assert!(header.verify_witness(&next_consensus, NETWORK).is_ok());
+		assert_eq!(
+			header.verify_witness(&next_consensus, NETWORK + 1),
+			Err(BuilderError::CryptoError(CryptoError::SignatureVerificationError))
+		);
+		assert!(matches!(
+			header.verify_witness(&H160::repeat_byte(0x01), NETWORK),
+			Err(BuilderError::InvalidScript(_))
+		));
+
+		// Tampering with a field invalidates the signatures.
+		let mut tampered = header.clone();
+		tampered.timestamp += 1;
+		assert!(tampered.verify_witness(&next_consensus, NETWORK).is_err());
+
+		// The signatures must be in the order of the public keys.
+		let mut unordered = header.clone();
+		sign(&mut unordered, &nodes, &[2, 0]);
+		assert!(unordered.verify_witness(&next_consensus, NETWORK).is_err());
+	}
+
+	#[test]
+	fn test_verify_successor_of() {
+		let nodes = consensus_nodes();
+		let previous = header(&nodes);
+
+		let mut next = previous.clone();
+		next.index = previous.index + 1;
+		next.prev_hash = previous.hash();
+		sign(&mut next, &nodes, &[1, 2]);
+		assert!(next.verify_successor_of(&previous, NETWORK).is_ok());
+
+		let mut fork = next.clone();
+		fork.prev_hash = H256::repeat_byte(0x33);
+		sign(&mut fork, &nodes, &[1, 2]);
+		assert!(matches!(
+			fork.verify_successor_of(&previous, NETWORK),
+			Err(BuilderError::IllegalState(_))
+		));
+
+		let mut last = previous.clone();
+		last.index = u32::MAX;
+		let mut wrapped = next.clone();
+		wrapped.index = 0;
+		wrapped.prev_hash = last.hash();
+		assert!(wrapped.verify_successor_of(&last, NETWORK).is_err());
+	}
+}
@@ -61,6 +61,22 @@ impl InvocationResult {
 		}
 	}
 
+	/// Returns a successful result with the given stack, e.g. to mock an invocation.
+	pub fn halt(stack: Vec<StackItem>) -> Self {
+		Self::new(
+			String::new(),
+			NeoVMStateType::Halt,
+			"0".to_string(),
+			None,
+			None,
+			None,
+			stack,
+			None,
+			None,
+			None,
+		)
+	}
+
 	pub fn has_state_fault(&self) -> bool {
 		matches!(self.state, NeoVMStateType::Fault)
 	}
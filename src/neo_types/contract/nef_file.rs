@@ -4,8 +4,8 @@ use primitive_types::H160;
 use tokio::io::AsyncReadExt;
 
 use neo::prelude::{
-	Bytes, CodecError, ContractParameter, Decoder, Encoder, HashableForVec, NeoSerializable,
-	StackItem, TypeError,
+	Bytes, CallFlags, CodecError, ContractParameter, Decoder, Encoder, HashableForVec,
+	NeoSerializable, StackItem, TypeError,
 };
 
 /*
@@ -48,6 +48,7 @@ impl NefFile {
 	const COMPILER_SIZE: usize = 64;
 	const MAX_SOURCE_URL_SIZE: usize = 256;
 	const MAX_SCRIPT_LENGTH: usize = 512 * 1024;
+	const MAX_METHOD_TOKENS: usize = 128;
 	const CHECKSUM_SIZE: usize = 4;
 	pub const HEADER_SIZE: usize = Self::MAGIC_SIZE + Self::COMPILER_SIZE;
 
@@ -74,14 +75,14 @@ impl NefFile {
 		}
 
 		let mut reader = Decoder::new(&file_bytes);
-		let nef = reader.read_serializable().unwrap();
+		let nef = reader.read_serializable()?;
 		Ok(nef)
 	}
 
 	fn read_from_stack_item(item: StackItem) -> Result<Self, TypeError> {
 		if let StackItem::ByteString { value: bytes } = item {
 			let mut reader = Decoder::new(&bytes.as_bytes());
-			let nef = reader.read_serializable().unwrap();
+			let nef = reader.read_serializable()?;
 			Ok(nef)
 		} else {
 			Err(TypeError::UnexpectedReturnType(
@@ -118,7 +119,7 @@ impl NeoSerializable for NefFile {
 	}
 
 	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
-		let magic = reader.read_u32();
+		let magic = reader.read_u32()?;
 		if magic != Self::MAGIC {
 			return Err(TypeError::InvalidEncoding("Invalid magic".to_string()))
 		}
@@ -127,22 +128,21 @@ impl NeoSerializable for NefFile {
 		let compiler = String::from_utf8(compiler_bytes.to_vec())
 			.map_err(|_| CodecError::InvalidEncoding("Invalid compiler".to_string()))?;
 
-		let source_url = reader.read_var_string()?;
-		if source_url.len() > Self::MAX_SOURCE_URL_SIZE {
-			return Err(TypeError::InvalidEncoding("Invalid source url".to_string()))
-		}
+		let source_url = reader
+			.read_var_string_max(Self::MAX_SOURCE_URL_SIZE)
+			.map_err(|_| TypeError::InvalidEncoding("Invalid source url".to_string()))?;
 
-		if reader.read_u8() != 0 {
+		if reader.read_u8()? != 0 {
 			return Err(TypeError::InvalidEncoding("Invalid reserve bytes".to_string()))
 		}
 
-		let method_tokens = reader.read_serializable_list()?;
+		let method_tokens = reader.read_serializable_list_max(Self::MAX_METHOD_TOKENS)?;
 
-		if reader.read_u16() != 0 {
+		if reader.read_u16()? != 0 {
 			return Err(TypeError::InvalidEncoding("Invalid reserve bytes".to_string()))
 		}
 
-		let script = reader.read_var_bytes()?;
+		let script = reader.read_var_bytes_max(Self::MAX_SCRIPT_LENGTH)?;
 		if script.is_empty() {
 			return Err(TypeError::InvalidEncoding("Invalid script".to_string()))
 		}
@@ -178,6 +178,7 @@ impl MethodToken {
 	const PARAMS_COUNT_SIZE: usize = 2;
 	const HAS_RETURN_VALUE_SIZE: usize = 1;
 	const CALL_FLAGS_SIZE: usize = 1;
+	const MAX_METHOD_LENGTH: usize = 32;
 }
 
 impl NeoSerializable for MethodToken {
@@ -206,10 +207,16 @@ impl NeoSerializable for MethodToken {
 		Self: Sized,
 	{
 		let hash = reader.read_serializable()?;
-		let method = reader.read_var_string()?;
-		let params_count = reader.read_u16();
-		let has_return_value = reader.read_bool();
-		let call_flags = reader.read_u8();
+		let method = reader.read_var_string_max(Self::MAX_METHOD_LENGTH)?;
+		if method.starts_with('_') {
+			return Err(TypeError::InvalidEncoding("Invalid method name".to_string()))
+		}
+		let params_count = reader.read_u16()?;
+		let has_return_value = reader.read_bool()?;
+		let call_flags = reader.read_u8()?;
+		if call_flags & !CallFlags::All.value() != 0 {
+			return Err(TypeError::InvalidEncoding("Invalid call flags".to_string()))
+		}
 
 		Ok(Self { hash, method, params_count, has_return_value, call_flags })
 	}
@@ -6,6 +6,7 @@ use serde_derive::{Deserialize, Serialize};
 pub use address::*;
 pub use address_or_scripthash::*;
 pub use block::*;
+pub use block_header::*;
 pub use bytes::*;
 pub use contract::*;
 pub use error::*;
@@ -14,6 +15,7 @@ pub use numeric::*;
 pub use op_code::*;
 pub use path_or_string::*;
 pub use plugin_type::*;
+pub use raw_block::*;
 pub use script_hash::*;
 pub use serde_value::*;
 pub use serde_with_utils::*;
@@ -31,12 +33,14 @@ mod nns;
 mod address;
 mod address_or_scripthash;
 mod block;
+mod block_header;
 mod bytes;
 mod error;
 mod numeric;
 mod op_code;
 mod path_or_string;
 mod plugin_type;
+mod raw_block;
 mod script_hash;
 mod serde_value;
 mod serde_with_utils;
@@ -65,7 +69,11 @@ impl ExternBase64 for String {
 // ScryptParams
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ScryptParamsDef {
-	#[serde(rename = "n")]
+	#[serde(
+		rename = "n",
+		serialize_with = "serialize_scrypt_n",
+		deserialize_with = "deserialize_scrypt_n"
+	)]
 	pub log_n: u8,
 	#[serde(rename = "r")]
 	pub r: u32,
@@ -1,7 +1,9 @@
+pub use name_or_account::*;
 pub use nns_name::*;
 pub use record_state::*;
 pub use record_type::*;
 
+mod name_or_account;
 mod name_state;
 mod nns_name;
 mod record_state;
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use neo::prelude::{Address, AddressOrScriptHash, NNSName, ScriptHash, TypeError};
+
+/// An account given either as an NNS name such as `alice.neo`, or as an address or script hash.
+///
+/// Names are resolved with `NnsMiddleware::resolve_account`, so that only resolved accounts reach
+/// APIs taking an `AddressOrScriptHash`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameOrAccount {
+	Name(NNSName),
+	Account(AddressOrScriptHash),
+}
+
+impl From<NNSName> for NameOrAccount {
+	fn from(name: NNSName) -> Self {
+		Self::Name(name)
+	}
+}
+
+impl From<AddressOrScriptHash> for NameOrAccount {
+	fn from(account: AddressOrScriptHash) -> Self {
+		Self::Account(account)
+	}
+}
+
+impl From<Address> for NameOrAccount {
+	fn from(address: Address) -> Self {
+		Self::Account(AddressOrScriptHash::Address(address))
+	}
+}
+
+impl From<ScriptHash> for NameOrAccount {
+	fn from(script_hash: ScriptHash) -> Self {
+		Self::Account(AddressOrScriptHash::ScriptHash(script_hash))
+	}
+}
+
+impl FromStr for NameOrAccount {
+	type Err = TypeError;
+
+	/// Parses an address, a hex script hash in its big-endian form or an NNS name.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use std::str::FromStr;
+	/// use neo_rs::prelude::NameOrAccount;
+	/// let name = NameOrAccount::from_str("alice.neo").unwrap();
+	/// assert!(matches!(name, NameOrAccount::Name(_)));
+	/// ```
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match AddressOrScriptHash::from_str(s) {
+			Ok(account) => Ok(Self::Account(account)),
+			Err(_) => NNSName::new(s).map(Self::Name),
+		}
+	}
+}
@@ -4,15 +4,31 @@ use serde::{Deserialize, Serialize};
 
 use neo::prelude::TypeError;
 
+/// A valid NNS name. Deserialized names are validated like the names given to [`NNSName::new`].
 #[derive(Debug, Clone, Display, PartialEq, Eq, Serialize, Deserialize, Getters, Setters)]
+#[serde(try_from = "RawNNSName")]
 pub struct NNSName {
 	#[getset(get = "pub")]
 	name: String,
 }
 
+// The serialized form of `NNSName`, validated before it becomes one
+#[derive(Deserialize)]
+struct RawNNSName {
+	name: String,
+}
+
+impl TryFrom<RawNNSName> for NNSName {
+	type Error = TypeError;
+
+	fn try_from(raw: RawNNSName) -> Result<Self, Self::Error> {
+		Self::new(&raw.name)
+	}
+}
+
 impl NNSName {
 	pub fn new(name: &str) -> Result<Self, TypeError> {
-		Self::validate(name, true).unwrap();
+		Self::validate(name, true)?;
 		Ok(Self { name: name.to_owned() })
 	}
 
@@ -31,7 +47,7 @@ impl NNSName {
 		}
 
 		for fragment in &fragments {
-			Self::validate_fragment(fragment, fragment == fragments.last().unwrap()).unwrap();
+			Self::validate_fragment(fragment, fragment == fragments.last().unwrap())?;
 		}
 
 		Ok(())
@@ -67,8 +83,7 @@ impl NNSName {
 	}
 
 	pub fn validate(name: &str, allow_multi_fragments: bool) -> Result<(), TypeError> {
-		Self::is_valid(name, allow_multi_fragments).unwrap();
-		Ok(())
+		Self::is_valid(name, allow_multi_fragments)
 	}
 
 	pub fn bytes(&self) -> Vec<u8> {
@@ -78,6 +93,13 @@ impl NNSName {
 	pub fn is_second_level_domain(&self) -> bool {
 		Self::is_valid(&self.name, false).is_ok()
 	}
+
+	/// Returns the second-level domain of the name, e.g. `neo.org` for `docs.neo.org`. The NNS
+	/// contract tracks ownership and expiration per second-level domain.
+	pub fn second_level_domain(&self) -> String {
+		let fragments = self.name.split('.').collect::<Vec<_>>();
+		fragments[fragments.len() - 2..].join(".")
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -87,7 +109,7 @@ pub struct NNSRoot {
 
 impl NNSRoot {
 	pub fn new(root: &str) -> Result<Self, TypeError> {
-		Self::validate(root).unwrap();
+		Self::validate(root)?;
 		Ok(Self { root: root.to_owned() })
 	}
 
@@ -95,3 +117,17 @@ impl NNSRoot {
 		NNSName::validate_fragment(root, true)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::NNSName;
+
+	#[test]
+	fn test_deserialize_validates_name() {
+		let name: NNSName = serde_json::from_str(r#"{"name":"docs.neo.org"}"#).unwrap();
+		assert_eq!(name.second_level_domain(), "neo.org");
+		assert_eq!(serde_json::to_string(&name).unwrap(), r#"{"name":"docs.neo.org"}"#);
+
+		assert!(serde_json::from_str::<NNSName>(r#"{"name":"neo"}"#).is_err());
+	}
+}
@@ -1,9 +1,8 @@
 use std::hash::{Hash, Hasher};
 
-use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 
-use neo::prelude::StackItem;
+use neo::prelude::{RecordType, StackItem};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RecordState {
@@ -12,15 +11,6 @@ pub struct RecordState {
 	pub data: String,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, TryFromPrimitive)]
-#[repr(u8)]
-pub enum RecordType {
-	A = 0x01,
-	AAAA = 0x02,
-	CNAME = 0x04,
-	Delete = 0x08,
-}
-
 impl RecordState {
 	pub fn new(name: String, record_type: RecordType, data: String) -> Self {
 		Self { name, record_type, data }
@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use base64::{engine::general_purpose, Engine};
+use primitive_types::H256;
+
+use neo::prelude::{
+	BlockHeader, BuilderError, CodecError, Decoder, Encoder, JsonRpcClient, MerkleTree,
+	NeoSerializable, Transaction, VarSizeTrait,
+};
+
+/// A full block in its binary form, as returned by `getblock` when `verbose` is false.
+///
+/// Decoding rejects blocks whose transactions do not match the merkle root of the header, so the
+/// transactions of a block whose header passed `BlockHeader::verify_witness` can be trusted.
+#[derive(Debug, Clone)]
+pub struct RawBlock<P: JsonRpcClient + 'static> {
+	pub header: BlockHeader,
+	pub transactions: Vec<Transaction<P>>,
+}
+
+impl<P: JsonRpcClient + 'static> RawBlock<P> {
+	const MAX_TRANSACTIONS: usize = u16::MAX as usize;
+
+	/// Decodes a block, rejecting any bytes after it.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, BuilderError> {
+		let mut reader = Decoder::new(bytes);
+		let block = Self::decode(&mut reader)?;
+		if reader.available() > 0 {
+			return Err(CodecError::InvalidEncoding(format!(
+				"{} unexpected bytes after the block",
+				reader.available()
+			))
+			.into())
+		}
+		Ok(block)
+	}
+
+	/// Decodes a base64 encoded block, the form returned by the RPC nodes.
+	pub fn from_base64(base64: &str) -> Result<Self, BuilderError> {
+		let bytes = general_purpose::STANDARD
+			.decode(base64.trim())
+			.map_err(|e| CodecError::InvalidEncoding(e.to_string()))?;
+		Self::from_bytes(&bytes)
+	}
+
+	pub fn hash(&self) -> H256 {
+		self.header.hash()
+	}
+
+	/// Computes the merkle root of the transactions, which a valid block has in its header.
+	pub fn compute_merkle_root(&self) -> H256 {
+		let hashes = self.transactions.iter().map(|tx| tx.get_tx_id()).collect::<Vec<_>>();
+		MerkleTree::compute_root(&hashes)
+	}
+}
+
+impl<P: JsonRpcClient + 'static> NeoSerializable for RawBlock<P> {
+	type Error = BuilderError;
+
+	fn size(&self) -> usize {
+		self.header.size() + self.transactions.var_size()
+	}
+
+	fn encode(&self, writer: &mut Encoder) {
+		self.header.encode(writer);
+		writer.write_serializable_variable_list(&self.transactions);
+	}
+
+	fn decode(reader: &mut Decoder) -> Result<Self, Self::Error> {
+		let header = BlockHeader::decode(reader)?;
+		let transactions = reader.read_serializable_list_max(Self::MAX_TRANSACTIONS)?;
+		let block = Self { header, transactions };
+
+		let mut hashes = HashSet::new();
+		if !block.transactions.iter().all(|tx| hashes.insert(tx.get_tx_id())) {
+			return Err(CodecError::InvalidEncoding(
+				"The block contains a transaction twice".to_string(),
+			)
+			.into())
+		}
+		if block.compute_merkle_root() != block.header.merkle_root {
+			return Err(CodecError::InvalidEncoding(
+				"The transactions do not match the merkle root of the block".to_string(),
+			)
+			.into())
+		}
+		Ok(block)
+	}
+
+	fn to_array(&self) -> Vec<u8> {
+		let mut writer = Encoder::new();
+		self.encode(&mut writer);
+		writer.to_bytes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use primitive_types::{H160, H256};
+
+	use neo::prelude::{
+		BlockHeader, Http, MerkleTree, NeoSerializable, RawBlock, Transaction, TransactionSigner,
+		Witness, WitnessScope,
+	};
+
+	fn transaction(nonce: i32) -> Transaction<Http> {
+		let mut tx = Transaction::new();
+		tx.nonce = nonce;
+		tx.valid_until_block = 1000;
+		tx.signers = vec![TransactionSigner::new(
+			H160::repeat_byte(0x01),
+			vec![WitnessScope::CalledByEntry],
+		)
+		.into()];
+		tx.script = vec![0x40];
+		tx.witnesses = vec![Witness::new()];
+		tx
+	}
+
+	fn block(transactions: Vec<Transaction<Http>>) -> RawBlock<Http> {
+		let hashes = transactions.iter().map(|tx| tx.get_tx_id()).collect::<Vec<_>>();
+		let header = BlockHeader {
+			version: 0,
+			prev_hash: H256::repeat_byte(0x11),
+			merkle_root: MerkleTree::compute_root(&hashes),
+			timestamp: 1_700_000_000_000,
+			nonce: 7,
+			index: 100,
+			primary_index: 0,
+			next_consensus: H160::repeat_byte(0x22),
+			witness: Witness::new(),
+		};
+		RawBlock { header, transactions }
+	}
+
+	#[test]
+	fn test_encode_decode() {
+		let block = block(vec![transaction(1), transaction(2), transaction(3)]);
+		let bytes = block.to_array();
+		assert_eq!(bytes.len(), block.size());
+
+		let decoded = RawBlock::<Http>::from_bytes(&bytes).unwrap();
+		assert_eq!(decoded.header, block.header);
+		assert_eq!(decoded.transactions, block.transactions);
+		assert_eq!(decoded.compute_merkle_root(), block.header.merkle_root);
+
+		let decoded = RawBlock::<Http>::from_base64(&base64::encode(&bytes)).unwrap();
+		assert_eq!(decoded.hash(), block.hash());
+	}
+
+	#[test]
+	fn test_decode_empty_block() {
+		let block = block(vec![]);
+		assert_eq!(block.header.merkle_root, H256::zero());
+		assert!(RawBlock::<Http>::from_bytes(&block.to_array()).is_ok());
+	}
+
+	#[test]
+	fn test_decode_rejects_wrong_merkle_root() {
+		let mut block = block(vec![transaction(1), transaction(2)]);
+		block.transactions.swap(0, 1);
+		assert!(RawBlock::<Http>::from_bytes(&block.to_array()).is_err());
+	}
+
+	#[test]
+	fn test_decode_rejects_duplicate_transactions() {
+		let block = block(vec![transaction(1), transaction(1)]);
+		assert!(RawBlock::<Http>::from_bytes(&block.to_array()).is_err());
+	}
+}
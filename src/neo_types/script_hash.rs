@@ -82,6 +82,9 @@ impl ScriptHashExtension for H160 {
 			Ok(bytes) => bytes,
 			Err(_) => return Err(TypeError::InvalidAddress),
 		};
+		if bytes.len() != 25 {
+			return Err(TypeError::InvalidAddress)
+		}
 
 		let _salt = bytes[0];
 		let hash = &bytes[1..21];
@@ -59,6 +59,21 @@ where
 	Ok(bytes)
 }
 
+pub fn serialize_base64<S>(item: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	serializer.serialize_str(&base64::encode(item))
+}
+
+pub fn deserialize_base64<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let s: String = Deserialize::deserialize(deserializer)?;
+	base64::decode(s).map_err(serde::de::Error::custom)
+}
+
 pub fn serialize_url<S>(item: Url, serializer: S) -> Result<S::Ok, S::Error>
 where
 	S: Serializer,
@@ -790,10 +805,55 @@ where
 	Ok(map)
 }
 
+/// Serializes the scrypt cost parameter `log_n` as `N = 2^log_n`, as NEP-6 specifies.
+pub fn serialize_scrypt_n<S>(log_n: &u8, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let n = 1u64.checked_shl(*log_n as u32).ok_or_else(|| {
+		serde::ser::Error::custom(format!("scrypt log_n must be below 64, got {}", log_n))
+	})?;
+	serializer.serialize_u64(n)
+}
+
+/// Deserializes the scrypt cost parameter `N` into `log_n`.
+///
+/// Values below 64 are taken to be `log_n` already, as written by earlier versions of this crate.
+/// This is ambiguous for the powers of two from 1 to 32, which are read as `log_n` and not as
+/// `N`: `"n": 16` is read as `N = 65536`, not `N = 16`. Such small `N` offer no protection and
+/// are not used by NEP-6 wallets, whose default is 16384.
+pub fn deserialize_scrypt_n<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let n = u64::deserialize(deserializer)?;
+	match n {
+		0..=63 => Ok(n as u8),
+		n if n.is_power_of_two() => Ok(n.trailing_zeros() as u8),
+		n => Err(serde::de::Error::custom(format!("scrypt N must be a power of two, got {}", n))),
+	}
+}
+
 #[cfg(test)]
 mod test {
+	use neo::prelude::ScryptParamsDef;
+
 	use super::*;
 
+	#[test]
+	fn test_scrypt_n() {
+		let params: ScryptParamsDef = serde_json::from_str(r#"{"n":16384,"r":8,"p":8}"#).unwrap();
+		assert_eq!(params.log_n, 14);
+		assert_eq!(serde_json::to_value(&params).unwrap()["n"], 16384);
+		// Written as log_n by earlier versions
+		let params: ScryptParamsDef = serde_json::from_str(r#"{"n":14,"r":8,"p":8}"#).unwrap();
+		assert_eq!(params.log_n, 14);
+		assert!(serde_json::from_str::<ScryptParamsDef>(r#"{"n":1000,"r":8,"p":8}"#).is_err());
+
+		let params = ScryptParamsDef { log_n: 64, r: 8, p: 8 };
+		assert!(serde_json::to_string(&params).is_err());
+	}
+
 	#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 	struct TestStruct {
 		#[serde(serialize_with = "serialize_hashset_u256")]
@@ -65,6 +65,12 @@ pub struct MapEntry {
 	value: StackItem,
 }
 
+impl MapEntry {
+	pub fn new(key: StackItem, value: StackItem) -> Self {
+		Self { key, value }
+	}
+}
+
 impl StackItem {
 	/// The string value for `StackItem::Any`.
 	pub const ANY_VALUE: &'static str = "Any";
@@ -0,0 +1,170 @@
+//! Helpers for signing Neo transactions with keys held by AWS KMS
+use async_trait::async_trait;
+use p256::pkcs8::DecodePublicKey;
+use rusoto_core::{credential::StaticProvider, request::TlsError, HttpClient, Region, RusotoError};
+use rusoto_kms::{GetPublicKeyError, GetPublicKeyRequest, Kms, KmsClient, SignRequest};
+use thiserror::Error;
+
+use neo::prelude::{
+	CryptoError, ExternalSigner, Secp256r1PublicKey, Secp256r1Signature, SignerError,
+};
+
+/// An `ECC_NIST_P256` signing key held by AWS KMS.
+///
+/// The public key is fetched once when the signer is created, from which the Neo address and
+/// verification script are derived. Every signature is a `Sign` request for the SHA-256 digest of
+/// the signed data.
+pub struct AwsSigner {
+	kms: KmsClient,
+	key_id: String,
+	public_key: Secp256r1PublicKey,
+}
+
+/// Error when using AWS KMS
+#[derive(Error, Debug)]
+pub enum AwsSignerError {
+	#[error(transparent)]
+	GetPublicKey(#[from] RusotoError<GetPublicKeyError>),
+	#[error(transparent)]
+	Sign(#[from] RusotoError<rusoto_kms::SignError>),
+	#[error(transparent)]
+	Tls(#[from] TlsError),
+	/// The key is not a P-256 key and cannot sign Neo transactions
+	#[error("Unsupported key spec: {0}")]
+	UnsupportedKeySpec(String),
+	/// The public key is not a valid DER encoded SubjectPublicKeyInfo of a P-256 key
+	#[error("Invalid public key: {0}")]
+	InvalidPublicKey(String),
+	/// A response of KMS lacks a field
+	#[error("KMS response lacks the {0}")]
+	MissingField(&'static str),
+	#[error(transparent)]
+	CryptoError(#[from] CryptoError),
+}
+
+impl AwsSigner {
+	const KEY_SPEC: &'static str = "ECC_NIST_P256";
+	const SIGNING_ALGORITHM: &'static str = "ECDSA_SHA_256";
+
+	/// Uses the KMS key `key_id`, which may be a key id, key ARN or alias.
+	pub async fn new(kms: KmsClient, key_id: impl Into<String>) -> Result<Self, AwsSignerError> {
+		let key_id = key_id.into();
+		let response = kms
+			.get_public_key(GetPublicKeyRequest { key_id: key_id.clone(), ..Default::default() })
+			.await?;
+
+		let key_spec = response.customer_master_key_spec.unwrap_or_default();
+		if key_spec != Self::KEY_SPEC {
+			return Err(AwsSignerError::UnsupportedKeySpec(key_spec))
+		}
+		let spki = response.public_key.ok_or(AwsSignerError::MissingField("public key"))?;
+		let public_key = Self::public_key_from_spki(&spki)?;
+
+		Ok(Self { kms, key_id, public_key })
+	}
+
+	/// Creates a KMS client for a local KMS emulator listening at `endpoint`, which accepts any
+	/// credentials.
+	pub fn local_client(endpoint: impl Into<String>) -> Result<KmsClient, AwsSignerError> {
+		let region = Region::Custom { name: "us-east-1".to_string(), endpoint: endpoint.into() };
+		let credentials = StaticProvider::new_minimal("local".to_string(), "local".to_string());
+		Ok(KmsClient::new_with(HttpClient::new()?, credentials, region))
+	}
+
+	pub fn key_id(&self) -> &str {
+		&self.key_id
+	}
+
+	fn public_key_from_spki(spki: &[u8]) -> Result<Secp256r1PublicKey, AwsSignerError> {
+		p256::PublicKey::from_public_key_der(spki)
+			.map(Secp256r1PublicKey::from_public_key)
+			.map_err(|e| AwsSignerError::InvalidPublicKey(e.to_string()))
+	}
+
+	async fn sign_digest(&self, digest: &[u8]) -> Result<Secp256r1Signature, AwsSignerError> {
+		let request = SignRequest {
+			key_id: self.key_id.clone(),
+			message: digest.to_vec().into(),
+			message_type: Some("DIGEST".to_string()),
+			signing_algorithm: Self::SIGNING_ALGORITHM.to_string(),
+			..Default::default()
+		};
+		let response = self.kms.sign(request).await?;
+		let der = response.signature.ok_or(AwsSignerError::MissingField("signature"))?;
+		// KMS returns DER and may return a high s, witnesses hold the 64 bytes of r and a low s
+		Ok(Secp256r1Signature::from_der(&der)?.normalize_s())
+	}
+}
+
+#[async_trait]
+impl ExternalSigner for AwsSigner {
+	fn public_key(&self) -> &Secp256r1PublicKey {
+		&self.public_key
+	}
+
+	async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+		Ok(self.sign_digest(digest).await?)
+	}
+}
+
+// do not log the client
+impl std::fmt::Debug for AwsSigner {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("AwsSigner")
+			.field("key_id", &self.key_id)
+			.field("address", &self.address())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use p256::pkcs8::EncodePublicKey;
+	use rusoto_kms::CreateKeyRequest;
+
+	use neo::prelude::KeyPair;
+
+	use super::*;
+	use crate::neo_wallets::external_signer::tests::assert_signs_transactions;
+
+	#[test]
+	fn test_public_key_from_spki() {
+		let key_pair = KeyPair::new_random();
+		let public_key =
+			p256::PublicKey::from_sec1_bytes(&key_pair.public_key().get_encoded(true)).unwrap();
+		let spki = public_key.to_public_key_der().unwrap();
+
+		assert_eq!(
+			AwsSigner::public_key_from_spki(spki.as_bytes()).unwrap(),
+			key_pair.public_key()
+		);
+		assert!(matches!(
+			AwsSigner::public_key_from_spki(&spki.as_bytes()[1..]),
+			Err(AwsSignerError::InvalidPublicKey(_))
+		));
+	}
+
+	// Requires a KMS emulator, e.g. `docker run -p 4566:4566 localstack/localstack`, or the
+	// endpoint in `KMS_ENDPOINT`.
+	#[tokio::test]
+	#[ignore]
+	async fn test_sign_transaction_with_local_kms() {
+		let endpoint =
+			std::env::var("KMS_ENDPOINT").unwrap_or_else(|_| "http://localhost:4566".to_string());
+		let kms = AwsSigner::local_client(endpoint).unwrap();
+		let key = kms
+			.create_key(CreateKeyRequest {
+				key_usage: Some("SIGN_VERIFY".to_string()),
+				customer_master_key_spec: Some(AwsSigner::KEY_SPEC.to_string()),
+				..Default::default()
+			})
+			.await
+			.unwrap()
+			.key_metadata
+			.unwrap();
+
+		let signer = AwsSigner::new(kms, key.key_id.clone()).await.unwrap();
+		assert_eq!(signer.key_id(), key.key_id);
+		assert_signs_transactions(&signer).await;
+	}
+}
@@ -1,7 +1,7 @@
 use hex::FromHexError;
 use thiserror::Error;
 
-use neo::prelude::{BuilderError, CryptoError, TypeError, WalletError};
+use neo::prelude::{BuilderError, CryptoError, TransactionError, TypeError, WalletError};
 
 /// Represents errors that can occur within the signing process.
 ///
@@ -26,6 +26,11 @@ use neo::prelude::{BuilderError, CryptoError, TypeError, WalletError};
 /// - `CryptoError`: Covers general cryptographic failures.
 /// - `RustcFromHexError`: Specific to hex decoding issues via `rustc_serialize`.
 /// - `TypeError`: Indicates failures in type conversion or coercion.
+/// - `InvalidKey`: Signals that a key held by an external signer cannot sign for Neo.
+/// - `TransactionError`: Wraps errors from computing the data of a transaction to sign.
+/// - `YubiHsmError`: Wraps errors from the YubiHSM2 client.
+/// - `AwsSignerError`: Wraps errors from signing with AWS KMS.
+/// - `Pkcs11SignerError`: Wraps errors from signing with a PKCS#11 token.
 ///
 /// # Examples
 ///
@@ -98,4 +103,28 @@ pub enum SignerError {
 	/// such as when deserializing data into a specific structure.
 	#[error(transparent)]
 	TypeError(#[from] TypeError),
+
+	/// Signals that a key held by an external signer, such as an HSM, is not a key that can
+	/// sign Neo transactions.
+	#[error("Invalid key: {0}")]
+	InvalidKey(String),
+
+	/// Wraps errors from computing the data of a transaction that is signed.
+	#[error(transparent)]
+	TransactionError(#[from] TransactionError),
+
+	/// Wraps errors from the YubiHSM2 client, such as connection or authentication failures.
+	#[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
+	#[error(transparent)]
+	YubiHsmError(#[from] yubihsm::client::Error),
+
+	/// Wraps errors from AWS KMS, such as request failures or keys that cannot sign.
+	#[cfg(all(feature = "aws", not(target_arch = "wasm32")))]
+	#[error(transparent)]
+	AwsSignerError(#[from] neo::prelude::AwsSignerError),
+
+	/// Wraps errors from a PKCS#11 token, such as session or signing failures.
+	#[cfg(all(feature = "pkcs11", not(target_arch = "wasm32")))]
+	#[error(transparent)]
+	Pkcs11SignerError(#[from] neo::prelude::Pkcs11SignerError),
 }
@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+
+use neo::prelude::{
+	public_key_to_address, Address, HashableForVec, InvocationScript, JsonRpcClient, ScriptHash,
+	Secp256r1PublicKey, Secp256r1Signature, SignerError, Transaction, VerificationScript, Witness,
+};
+
+/// A P-256 key whose private key is held outside of this process, such as by an HSM, a PKCS#11
+/// token or a key management service.
+///
+/// Implementors provide the public key and the signature of a SHA-256 digest, from which the
+/// address, verification script and witnesses of the key are derived. Pass external signers, in
+/// any mix of kinds, to
+/// [`TransactionBuilder::sign_with`](neo::prelude::TransactionBuilder::sign_with) to sign the
+/// accounts of a transaction that hold no key pair.
+#[async_trait]
+pub trait ExternalSigner: Send + Sync {
+	/// Returns the public key of the signing key.
+	fn public_key(&self) -> &Secp256r1PublicKey;
+
+	/// Signs the SHA-256 `digest` of a message.
+	///
+	/// The Neo VM rejects signatures with a high `s`, so implementors return normalized
+	/// signatures.
+	async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError>;
+
+	fn address(&self) -> Address {
+		public_key_to_address(self.public_key())
+	}
+
+	fn verification_script(&self) -> VerificationScript {
+		VerificationScript::from_public_key(self.public_key())
+	}
+
+	fn script_hash(&self) -> ScriptHash {
+		self.verification_script().hash()
+	}
+
+	/// Signs the SHA-256 hash of `message`.
+	async fn sign_message(&self, message: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+		self.sign_prehash(&message.hash256()).await
+	}
+
+	/// Signs `tx` for `network` and returns the witness of this key.
+	async fn sign_transaction_for_network<P: JsonRpcClient + 'static>(
+		&self,
+		tx: &Transaction<P>,
+		network: u32,
+	) -> Result<Witness, SignerError>
+	where
+		Self: Sized,
+	{
+		let signature = self.sign_message(&tx.get_hash_data_for_network(network)).await?;
+		Ok(self.witness(signature))
+	}
+
+	/// Signs `tx` for the network of its provider and returns the witness of this key.
+	async fn sign_transaction<P: JsonRpcClient + 'static>(
+		&self,
+		tx: &Transaction<P>,
+	) -> Result<Witness, SignerError>
+	where
+		Self: Sized,
+	{
+		let signature = self.sign_message(&tx.get_hash_data().await?).await?;
+		Ok(self.witness(signature))
+	}
+
+	/// Returns the witness of this key holding `signature`.
+	fn witness(&self, signature: Secp256r1Signature) -> Witness {
+		Witness::from_scripts_obj(
+			InvocationScript::from_signature(signature),
+			self.verification_script(),
+		)
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use neo::prelude::{KeyPair, MockProvider};
+
+	use super::*;
+
+	// Checks the witnesses and signatures of an external signer, shared by the tests of the
+	// signer backends
+	pub(crate) async fn assert_signs_transactions<S: ExternalSigner>(signer: &S) {
+		let mut tx: Transaction<MockProvider> = Transaction::new();
+		tx.script = vec![0x40];
+
+		let witness = signer.sign_transaction_for_network(&tx, 860833102).await.unwrap();
+		assert_eq!(witness.verification, signer.verification_script());
+		witness.verify(&tx.get_hash_data_for_network(860833102)).unwrap();
+
+		for i in 0..16u8 {
+			let message = [b"Some data".as_slice(), &[i]].concat();
+			let signature = signer.sign_message(&message).await.unwrap();
+			assert!(signature.is_low_s());
+			signer.public_key().verify(&message, &signature).unwrap();
+		}
+	}
+
+	struct LocalKeySigner(KeyPair);
+
+	#[async_trait]
+	impl ExternalSigner for LocalKeySigner {
+		fn public_key(&self) -> &Secp256r1PublicKey {
+			&self.0.public_key
+		}
+
+		async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+			Ok(self.0.expose_secret().sign_prehash(digest)?)
+		}
+	}
+
+	#[tokio::test]
+	async fn test_sign_transactions() {
+		assert_signs_transactions(&LocalKeySigner(KeyPair::new_random())).await;
+	}
+}
@@ -1,293 +1,397 @@
-#![allow(unused)]
-
-use std::convert::TryFrom;
-
 use coins_ledger::{
 	common::{APDUAnswer, APDUCommand, APDUData},
-	transports::{Ledger, LedgerAsync},
+	transports::Ledger,
 };
-use futures_executor::block_on;
 use futures_util::lock::Mutex;
-use primitive_types::U256;
-use thiserror::Error;
 
-use neo::prelude::{Address, Secp256r1Signature, Transaction};
+use neo::prelude::{
+	public_key_to_address, Address, Encoder, JsonRpcClient, Secp256r1PublicKey, Secp256r1Signature,
+	Transaction,
+};
 
-use super::types::*;
+use super::{transport::LedgerTransport, types::*};
 
-/// A Ledger Neo App.
+/// A Ledger Neo N3 App.
 ///
-/// This is a simple wrapper around the [Ledger transport](Ledger)
+/// This is a simple wrapper around a [`LedgerTransport`], which is the [Ledger HID
+/// transport](Ledger) unless created with [`LedgerNeo::with_transport`].
 #[derive(Debug)]
-pub struct LedgerNeo {
-	transport: Mutex<Ledger>,
+pub struct LedgerNeo<T: LedgerTransport = Ledger> {
+	transport: Mutex<T>,
 	derivation: DerivationType,
-	pub(crate) network: u64,
+	pub(crate) network: u32,
+	pub(crate) public_key: Secp256r1PublicKey,
 	pub(crate) address: Address,
 }
 
-impl std::fmt::Display for LedgerNeo {
+impl<T: LedgerTransport> std::fmt::Display for LedgerNeo<T> {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(
 			f,
-			"LedgerApp. Key at index {} with address {:?} on network {}",
+			"LedgerApp. Key at index {} with address {} on network {}",
 			self.derivation, self.address, self.network
 		)
 	}
 }
 
-impl LedgerNeo {
+impl LedgerNeo<Ledger> {
 	/// Instantiate the application by acquiring a lock on the ledger device.
 	///
-	///
-	/// ```
-	/// use neo_rs::prelude::{HDPath, Ledger};
-	///  async fn foo() -> Result<(), Box<dyn std::error::Error>> {
-	///
-	/// let ledger = Ledger::new(HDPath::LedgerLive(0), 1).await?;
+	/// ```no_run
+	/// use neo_rs::prelude::{HDPath, LedgerNeo};
+	/// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+	/// let ledger = LedgerNeo::new(HDPath::LedgerLive(0), 860833102).await?;
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub async fn new(derivation: DerivationType, network: u64) -> Result<Self, LedgerError> {
+	pub async fn new(derivation: DerivationType, network: u32) -> Result<Self, LedgerError> {
 		let transport = Ledger::init().await?;
-		let address = Self::get_address_with_path_transport(&transport, &derivation).await?;
+		Self::with_transport(transport, derivation, network).await
+	}
+}
 
-		Ok(Self { transport: Mutex::new(transport), derivation, network, address })
+impl<T: LedgerTransport> LedgerNeo<T> {
+	/// Instantiate the application on top of `transport`, signing for `network`, the network
+	/// magic of the chain.
+	pub async fn with_transport(
+		transport: T,
+		derivation: DerivationType,
+		network: u32,
+	) -> Result<Self, LedgerError> {
+		let public_key = Self::get_public_key_with_path_transport(&transport, &derivation).await?;
+		let address = public_key_to_address(&public_key);
+
+		Ok(Self { transport: Mutex::new(transport), derivation, network, public_key, address })
 	}
 
 	/// Consume self and drop the ledger mutex
 	pub fn close(self) {}
 
-	/// Get the account which corresponds to our derivation path
-	pub async fn get_address(&self) -> Result<Address, LedgerError> {
-		self.get_address_with_path(&self.derivation).await
+	/// Get the address which corresponds to our derivation path
+	pub fn get_address(&self) -> Address {
+		self.address.clone()
 	}
 
-	/// Gets the account which corresponds to the provided derivation path
-	pub async fn get_address_with_path(
+	/// Get the public key which corresponds to our derivation path
+	pub fn get_public_key(&self) -> Secp256r1PublicKey {
+		self.public_key.clone()
+	}
+
+	/// Gets the public key which corresponds to the provided derivation path
+	pub async fn get_public_key_with_path(
 		&self,
 		derivation: &DerivationType,
-	) -> Result<Address, LedgerError> {
-		let data = APDUData::new(&Self::path_to_bytes(derivation));
+	) -> Result<Secp256r1PublicKey, LedgerError> {
 		let transport = self.transport.lock().await;
-		Self::get_address_with_path_transport(&transport, derivation).await
+		Self::get_public_key_with_path_transport(&*transport, derivation).await
 	}
 
 	#[tracing::instrument(skip(transport))]
-	async fn get_address_with_path_transport(
-		transport: &Ledger,
+	async fn get_public_key_with_path_transport(
+		transport: &T,
 		derivation: &DerivationType,
-	) -> Result<Address, LedgerError> {
-		let data = APDUData::new(&Self::path_to_bytes(derivation));
-
-		let command = APDUCommand {
-			ins: INS::GET_PUBLIC_KEY as u8,
-			p1: P1::NON_CONFIRM as u8,
-			p2: P2::NO_CHAINCODE as u8,
-			data,
-			response_len: None,
-		};
+	) -> Result<Secp256r1PublicKey, LedgerError> {
+		let command = Self::command(
+			INS::GET_PUBLIC_KEY,
+			P1::NON_CONFIRM as u8,
+			P2::LAST,
+			&Self::path_to_bytes(derivation)?,
+		);
 
-		tracing::debug!("Dispatching get_address request to ethereum app");
-		let answer = block_on(transport.exchange(&command))?;
-		let result = answer.data().ok_or(LedgerError::UnexpectedNullResponse)?;
-
-		let address = {
-			// extract the address from the response
-			let offset = 1 + result[0] as usize;
-			let address_str = &result[offset + 1..offset + 1 + result[offset] as usize];
-			let mut address = [0; 20];
-			address.copy_from_slice(&hex::decode(address_str)?);
-			Address::from(address)
-		};
-		tracing::debug!(?address, "Received address from device");
-		Ok(address)
+		tracing::debug!("Dispatching get_public_key request to neo app");
+		let answer = transport.exchange(&command).await?;
+		let result = Self::response_data(&answer)?;
+		// The uncompressed point
+		if result.len() < 65 {
+			return Err(LedgerError::ShortResponse { got: result.len(), at_least: 65 })
+		}
+		let public_key = Secp256r1PublicKey::from_bytes(&result[..65])
+			.map_err(|_| LedgerError::InvalidPublicKey)?;
+		tracing::debug!(
+			public_key = public_key.get_encoded_compressed_hex(),
+			"Received public key from device"
+		);
+		Ok(public_key)
 	}
 
 	/// Returns the semver of the Neo ledger app
 	pub async fn version(&self) -> Result<String, LedgerError> {
 		let transport = self.transport.lock().await;
-
-		let command = APDUCommand {
-			ins: INS::GET_APP_CONFIGURATION as u8,
-			p1: P1::NON_CONFIRM as u8,
-			p2: P2::NO_CHAINCODE as u8,
-			data: APDUData::new(&[]),
-			response_len: None,
-		};
+		let command = Self::command(INS::GET_VERSION, P1::NON_CONFIRM as u8, P2::LAST, &[]);
 
 		tracing::debug!("Dispatching get_version");
-		let answer = block_on(transport.exchange(&command))?;
-		let result = answer.data().ok_or(LedgerError::UnexpectedNullResponse)?;
-		if result.len() < 4 {
-			return Err(LedgerError::ShortResponse { got: result.len(), at_least: 4 })
+		let answer = transport.exchange(&command).await?;
+		let result = Self::response_data(&answer)?;
+		if result.len() < 3 {
+			return Err(LedgerError::ShortResponse { got: result.len(), at_least: 3 })
 		}
-		let version = format!("{}.{}.{}", result[1], result[2], result[3]);
+		let version = format!("{}.{}.{}", result[0], result[1], result[2]);
 		tracing::debug!(version, "Retrieved version from device");
 		Ok(version)
 	}
 
-	/// Signs a Neo transaction (requires confirmation on the ledger)
-	pub async fn sign_tx(&self, tx: &Transaction) -> Result<Secp256r1Signature, LedgerError> {
-		Ok(signature)
-	}
-
-	/// Signs an ethereum personal message
-	pub async fn sign_message<S: AsRef<[u8]>>(
+	/// Signs a Neo transaction (requires confirmation on the ledger).
+	///
+	/// The device signs the network magic followed by the hash of the transaction, like any other
+	/// witness of the transaction.
+	pub async fn sign_tx<P: JsonRpcClient + 'static>(
 		&self,
-		message: S,
+		tx: &Transaction<P>,
 	) -> Result<Secp256r1Signature, LedgerError> {
-		let message = message.as_ref();
-
-		let mut payload = Self::path_to_bytes(&self.derivation);
-		payload.extend_from_slice(&(message.len() as u32).to_be_bytes());
-		payload.extend_from_slice(message);
-
-		self.sign_payload(INS::SIGN_PERSONAL_MESSAGE, &payload).await
+		let mut encoder = Encoder::new();
+		tx.serialize_without_witnesses(&mut encoder);
+		self.sign_payload(&encoder.to_bytes()).await
 	}
 
-	#[tracing::instrument(err, skip_all, fields(command = %command, payload = hex::encode(payload)))]
-	// structs
-	pub async fn sign_payload(
-		&self,
-		command: INS,
-		payload: &Vec<u8>,
-	) -> Result<Secp256r1Signature, LedgerError> {
+	/// Signs the unsigned serialization of a transaction (requires confirmation on the ledger).
+	///
+	/// The derivation path and the network magic are sent first, followed by the transaction in
+	/// chunks of at most [`MAX_CHUNK_SIZE`] bytes. The last answer holds the DER encoded
+	/// signature.
+	#[tracing::instrument(err, skip_all, fields(payload = hex::encode(payload)))]
+	pub async fn sign_payload(&self, payload: &[u8]) -> Result<Secp256r1Signature, LedgerError> {
 		if payload.is_empty() {
 			return Err(LedgerError::EmptyPayload)
 		}
 		let transport = self.transport.lock().await;
-		let mut command = APDUCommand {
-			ins: command as u8,
-			p1: P1_FIRST,
-			p2: P2::NO_CHAINCODE as u8,
-			data: APDUData::new(&[]),
+
+		let path = Self::path_to_bytes(&self.derivation)?;
+		let command = Self::command(INS::SIGN_TX, P1::NON_CONFIRM as u8, P2::MORE, &path);
+		Self::response_data(&transport.exchange(&command).await?)?;
+
+		let magic = self.network.to_le_bytes();
+		let command = Self::command(INS::SIGN_TX, P1::NETWORK_MAGIC as u8, P2::MORE, &magic);
+		Self::response_data(&transport.exchange(&command).await?)?;
+
+		let chunks = payload.chunks(MAX_CHUNK_SIZE).collect::<Vec<_>>();
+		let mut signature = Vec::new();
+		for (index, chunk) in chunks.iter().enumerate() {
+			// The chunk index saturates, the app only tells the first chunks apart
+			let p1 = (P1::FIRST_TX_CHUNK as usize + index).min(u8::MAX as usize) as u8;
+			let p2 = if index + 1 == chunks.len() { P2::LAST } else { P2::MORE };
+
+			tracing::debug!(index, chunk = hex::encode(chunk), "Dispatching packet to device");
+			let answer = transport.exchange(&Self::command(INS::SIGN_TX, p1, p2, chunk)).await?;
+			signature = Self::response_data(&answer)?.to_vec();
+		}
+
+		let sig = Self::signature_from_der(&signature)?;
+		tracing::debug!(sig = hex::encode(sig.to_bytes()), "Received signature from device");
+		Ok(sig)
+	}
+
+	fn command(ins: INS, p1: u8, p2: P2, data: &[u8]) -> APDUCommand {
+		APDUCommand {
+			cla: CLA,
+			ins: ins as u8,
+			p1,
+			p2: p2 as u8,
+			data: APDUData::new(data),
 			response_len: None,
-		};
+		}
+	}
 
-		let mut answer = None;
-		// workaround for https://github.com/LedgerHQ/app-ethereum/issues/409
-		// TODO: remove in future version
-		let chunk_size =
-			(0..=255).rev().find(|i| payload.len() % i != 3).expect("true for any length");
-
-		// Iterate in 255 byte chunks
-		let span = tracing::debug_span!("send_loop", index = 0, chunk = "");
-		let guard = span.entered();
-		for (index, chunk) in payload.chunks(chunk_size).enumerate() {
-			guard.record("index", index);
-			guard.record("chunk", hex::encode(chunk));
-			command.data = APDUData::new(chunk);
-
-			tracing::debug!("Dispatching packet to device");
-			answer = Some(block_on(transport.exchange(&command))?);
-
-			let data = answer.as_ref().expect("just assigned").data();
-			if data.is_none() {
-				return Err(LedgerError::UnexpectedNullResponse)
-			}
-			tracing::debug!(
-				response = hex::encode(data.expect("just checked")),
-				"Received response from device"
-			);
+	// Returns the data of a successful answer, or the error for its status word
+	fn response_data(answer: &APDUAnswer) -> Result<&[u8], LedgerError> {
+		match answer.retcode() {
+			SW_OK => Ok(answer.data().unwrap_or_default()),
+			SW_DENY => Err(LedgerError::Rejected),
+			status => Err(LedgerError::Status(status)),
+		}
+	}
 
-			// We need more data
-			command.p1 = P1::MORE as u8;
+	// Decodes the `SEQUENCE { INTEGER r, INTEGER s }` returned by the app
+	fn signature_from_der(der: &[u8]) -> Result<Secp256r1Signature, LedgerError> {
+		if der.len() < 8 || der[0] != 0x30 || der[1] as usize != der.len() - 2 {
+			return Err(LedgerError::InvalidSignature("Not a DER sequence".to_string()))
 		}
-		drop(guard);
-		let answer = answer.expect("payload is non-empty, therefore loop ran");
-		let result = answer.data().expect("check in loop");
-		if result.len() < 65 {
-			return Err(LedgerError::ShortResponse { got: result.len(), at_least: 65 })
+		let (r, rest) = Self::der_integer(&der[2..])?;
+		let (s, rest) = Self::der_integer(rest)?;
+		if !rest.is_empty() {
+			return Err(LedgerError::InvalidSignature("Trailing bytes".to_string()))
 		}
-		let v = result[0] as u64;
-		let r = U256::from_big_endian(&result[1..33]);
-		let s = U256::from_big_endian(&result[33..]);
-		let sig = Secp256r1Signature { r, s, v };
-		tracing::debug!(sig = %sig, "Received signature from device");
-		Ok(sig)
+		Secp256r1Signature::from_scalars(r, s)
+			.ok_or_else(|| LedgerError::InvalidSignature("r or s out of range".to_string()))
 	}
 
-	// helper which converts a derivation path to bytes
-	fn path_to_bytes(derivation: &DerivationType) -> Vec<u8> {
-		let derivation = derivation.to_string();
-		let elements = derivation.split('/').skip(1).collect::<Vec<_>>();
-		let depth = elements.len();
+	fn der_integer(data: &[u8]) -> Result<([u8; 32], &[u8]), LedgerError> {
+		if data.len() < 2 || data[0] != 0x02 {
+			return Err(LedgerError::InvalidSignature("Expected an INTEGER".to_string()))
+		}
+		let len = data[1] as usize;
+		if len == 0 || data.len() < 2 + len {
+			return Err(LedgerError::InvalidSignature("Invalid INTEGER length".to_string()))
+		}
+		// Strip the zero byte that keeps the INTEGER positive
+		let value = &data[2..2 + len];
+		let value = &value[value.iter().position(|b| *b != 0).unwrap_or(value.len())..];
+		if value.len() > 32 {
+			return Err(LedgerError::InvalidSignature("INTEGER exceeds 32 bytes".to_string()))
+		}
+		let mut scalar = [0u8; 32];
+		scalar[32 - value.len()..].copy_from_slice(value);
+		Ok((scalar, &data[2 + len..]))
+	}
 
-		let mut bytes = vec![depth as u8];
-		for derivation_index in elements {
-			let hardened = derivation_index.contains('\'');
-			let mut index = derivation_index.replace('\'', "").parse::<u32>().unwrap();
+	// helper which converts a BIP-44 derivation path to the 20 bytes the app expects
+	fn path_to_bytes(derivation: &DerivationType) -> Result<Vec<u8>, LedgerError> {
+		let derivation = derivation.to_string();
+		let invalid = || LedgerError::InvalidDerivationPath(derivation.clone());
+		let elements = derivation.strip_prefix("m/").ok_or_else(invalid)?.split('/');
+
+		let mut bytes = Vec::with_capacity(20);
+		for element in elements {
+			let (index, hardened) = match element.strip_suffix('\'') {
+				Some(index) => (index, true),
+				None => (element, false),
+			};
+			let mut index = index.parse::<u32>().map_err(|_| invalid())?;
+			if index & 0x80000000 != 0 {
+				return Err(invalid())
+			}
 			if hardened {
 				index |= 0x80000000;
 			}
-
 			bytes.extend(index.to_be_bytes());
 		}
+		if bytes.len() != 20 {
+			return Err(invalid())
+		}
 
-		bytes
+		Ok(bytes)
 	}
 }
 
 #[cfg(all(test, feature = "ledger"))]
 mod tests {
-	use signature::digest::Mac;
+	use neo::prelude::{KeyPair, MockProvider, Transaction};
 
-	use neo::prelude::RawTransaction;
+	use super::{super::transport::ScriptedLedgerTransport, *};
 
-	use super::*;
+	const NETWORK: u32 = 860833102;
 
-	#[tokio::test]
-	#[ignore]
-	// Replace this with your ETH addresses.
-	async fn test_get_address() {
-		// Instantiate it with the default ledger derivation path
-		let ledger = LedgerNeo::new(DerivationType::LedgerLive(0), 1).await.unwrap();
-		assert_eq!(
-			ledger.get_address().await.unwrap(),
-			"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".parse().unwrap()
-		);
-		assert_eq!(
-			ledger.get_address_with_path(&DerivationType::Legacy(0)).await.unwrap(),
-			"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".parse().unwrap()
-		);
+	// `header` is CLA, INS, P1 and P2 as hex, written out so a wrong constant fails the tests
+	fn apdu(header: &str, data: &[u8]) -> Vec<u8> {
+		let mut apdu = hex::decode(header.replace(' ', "")).unwrap();
+		apdu.push(data.len() as u8);
+		apdu.extend(data);
+		apdu
+	}
+
+	fn ok(data: &[u8]) -> Vec<u8> {
+		let mut response = data.to_vec();
+		response.extend(SW_OK.to_be_bytes());
+		response
+	}
+
+	fn der(signature: &Secp256r1Signature) -> Vec<u8> {
+		let bytes = signature.to_bytes();
+		let integer = |scalar: &[u8]| {
+			let start = scalar.iter().position(|b| *b != 0).unwrap();
+			let mut value = scalar[start..].to_vec();
+			if value[0] & 0x80 != 0 {
+				value.insert(0, 0);
+			}
+			let mut integer = vec![0x02, value.len() as u8];
+			integer.extend(value);
+			integer
+		};
+		let mut body = integer(&bytes[..32]);
+		body.extend(integer(&bytes[32..]));
+		let mut der = vec![0x30, body.len() as u8];
+		der.extend(body);
+		der
+	}
+
+	fn path() -> Vec<u8> {
+		[0x8000002Cu32, 0x80000378, 0x80000000, 0, 0]
+			.iter()
+			.flat_map(|index| index.to_be_bytes())
+			.collect()
+	}
+
+	async fn ledger(
+		key_pair: &KeyPair,
+	) -> (LedgerNeo<ScriptedLedgerTransport>, ScriptedLedgerTransport) {
+		let transport = ScriptedLedgerTransport::new();
+		transport.push(apdu("80 04 00 00", &path()), ok(&key_pair.public_key().get_encoded(false)));
+		let ledger =
+			LedgerNeo::with_transport(transport.clone(), DerivationType::LedgerLive(0), NETWORK)
+				.await
+				.unwrap();
+		(ledger, transport)
+	}
+
+	#[test]
+	fn test_path_to_bytes() {
+		let bytes = LedgerNeo::<ScriptedLedgerTransport>::path_to_bytes(&DerivationType::Legacy(3))
+			.unwrap();
+		assert_eq!(hex::encode(bytes), "8000002c80000378800000000000000000000003");
+
+		assert!(LedgerNeo::<ScriptedLedgerTransport>::path_to_bytes(&DerivationType::Other(
+			"m/44'/888'/0'".to_string()
+		))
+		.is_err());
 	}
 
 	#[tokio::test]
-	#[ignore]
-	async fn test_sign_tx() {
-		let ledger = LedgerNeo::new(DerivationType::LedgerLive(0), 1).await.unwrap();
-
-		// approve uni v2 router 0xff
-		let data = hex::decode("095ea7b30000000000000000000000007a250d5630b4cf539739df2c5dacb4c659f2488dffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap();
-
-		let tx_req = RawTransaction::new()
-			.to("2ed7afa17473e17ac59908f088b4371d28585476".parse::<Address>().unwrap())
-			.gas(1000000)
-			.gas_price(400e9 as u64)
-			.nonce(5)
-			.data(data)
-			.value(utils::parse_ether(100).unwrap())
-			.into();
-		let tx = ledger.sign_transaction(&tx_req).await.unwrap();
+	async fn test_get_public_key() {
+		let key_pair = KeyPair::new_random();
+		let (ledger, transport) = ledger(&key_pair).await;
+
+		assert_eq!(ledger.get_public_key(), key_pair.public_key());
+		assert_eq!(ledger.get_address(), public_key_to_address(&key_pair.public_key()));
+		assert!(transport.is_done());
 	}
 
 	#[tokio::test]
-	#[ignore]
 	async fn test_version() {
-		let ledger = LedgerNeo::new(DerivationType::LedgerLive(0), 1).await.unwrap();
+		let (ledger, transport) = ledger(&KeyPair::new_random()).await;
+		transport.push(apdu("80 01 00 00", &[]), ok(&[1, 2, 3]));
+
+		assert_eq!(ledger.version().await.unwrap(), "1.2.3");
+	}
 
-		let version = ledger.version().await.unwrap();
-		assert_eq!(version, "1.3.7");
+	#[tokio::test]
+	async fn test_sign_tx() {
+		let key_pair = KeyPair::new_random();
+		let (ledger, transport) = ledger(&key_pair).await;
+
+		let mut tx: Transaction<MockProvider> = Transaction::new();
+		tx.script = vec![0x40; 300];
+		let mut encoder = Encoder::new();
+		tx.serialize_without_witnesses(&mut encoder);
+		let unsigned = encoder.to_bytes();
+		assert!(unsigned.len() > MAX_CHUNK_SIZE);
+
+		let hash_data = tx.get_hash_data_for_network(NETWORK);
+		let signature = key_pair.expose_secret().sign_tx(&hash_data).unwrap();
+
+		transport.push(apdu("80 02 00 80", &path()), ok(&[]));
+		transport.push(apdu("80 02 01 80", &NETWORK.to_le_bytes()), ok(&[]));
+		transport.push(apdu("80 02 02 80", &unsigned[..MAX_CHUNK_SIZE]), ok(&[]));
+		transport.push(apdu("80 02 03 00", &unsigned[MAX_CHUNK_SIZE..]), ok(&der(&signature)));
+
+		let sig = ledger.sign_tx(&tx).await.unwrap();
+		assert_eq!(sig.to_bytes(), signature.to_bytes());
+		key_pair.public_key().verify(&hash_data, &sig).unwrap();
+		assert!(transport.is_done());
 	}
 
 	#[tokio::test]
-	#[ignore]
-	async fn test_sign_message() {
-		let ledger = LedgerNeo::new(DerivationType::Legacy(0), 1).await.unwrap();
-		let message = "hello world";
-		let sig = ledger.sign_message(message).await.unwrap();
-		let addr = ledger.get_address().await.unwrap();
-		sig.verify(message, addr).unwrap();
+	async fn test_sign_tx_rejected() {
+		let (ledger, transport) = ledger(&KeyPair::new_random()).await;
+		transport.push(apdu("80 02 00 80", &path()), SW_DENY.to_be_bytes().to_vec());
+
+		let mut tx: Transaction<MockProvider> = Transaction::new();
+		tx.script = vec![0x40];
+		assert!(matches!(ledger.sign_tx(&tx).await, Err(LedgerError::Rejected)));
+	}
+
+	#[test]
+	fn test_invalid_der_signature() {
+		let result =
+			LedgerNeo::<ScriptedLedgerTransport>::signature_from_der(&[0x30, 0x02, 0x02, 0x00]);
+		assert!(matches!(result, Err(LedgerError::InvalidSignature(_))));
 	}
 }
@@ -1,4 +1,5 @@
 use signature::Signer;
 
 pub mod app;
+pub mod transport;
 pub mod types;
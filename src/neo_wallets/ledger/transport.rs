@@ -0,0 +1,76 @@
+use std::{
+	collections::VecDeque,
+	fmt::Debug,
+	sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use coins_ledger::{
+	common::{APDUAnswer, APDUCommand},
+	transports::{Ledger, LedgerAsync},
+};
+
+use super::types::LedgerError;
+
+/// Exchanges APDUs with a Ledger device.
+///
+/// Implemented for the HID transport of [`coins_ledger`], and by [`ScriptedLedgerTransport`] to
+/// test code talking to the Neo app without a device.
+#[async_trait]
+pub trait LedgerTransport: Debug + Send + Sync {
+	/// Sends `command` to the device and returns its answer, including the status word.
+	async fn exchange(&self, command: &APDUCommand) -> Result<APDUAnswer, LedgerError>;
+}
+
+#[async_trait]
+impl LedgerTransport for Ledger {
+	async fn exchange(&self, command: &APDUCommand) -> Result<APDUAnswer, LedgerError> {
+		Ok(LedgerAsync::exchange(self, command).await?)
+	}
+}
+
+/// A transport that answers a scripted sequence of commands.
+///
+/// Every exchange takes the next entry of the script, checks that the command matches the
+/// expected APDU and answers with the scripted response, which must end with the status word.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedLedgerTransport {
+	script: Arc<Mutex<VecDeque<(Vec<u8>, Vec<u8>)>>>,
+}
+
+impl ScriptedLedgerTransport {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Appends the serialized `command` the device expects next, and its `response`.
+	pub fn push(&self, command: Vec<u8>, response: Vec<u8>) {
+		self.script.lock().unwrap().push_back((command, response));
+	}
+
+	/// Returns `true` if all scripted commands have been exchanged.
+	pub fn is_done(&self) -> bool {
+		self.script.lock().unwrap().is_empty()
+	}
+}
+
+#[async_trait]
+impl LedgerTransport for ScriptedLedgerTransport {
+	async fn exchange(&self, command: &APDUCommand) -> Result<APDUAnswer, LedgerError> {
+		let (expected, response) = self
+			.script
+			.lock()
+			.unwrap()
+			.pop_front()
+			.ok_or_else(|| LedgerError::Transport("No scripted command left".to_string()))?;
+		let command = command.serialize();
+		if command != expected {
+			return Err(LedgerError::Transport(format!(
+				"Expected command {}, got {}",
+				hex::encode(expected),
+				hex::encode(command)
+			)))
+		}
+		Ok(APDUAnswer::from_answer(response)?)
+	}
+}
@@ -15,14 +15,20 @@ pub enum DerivationType {
 	Other(String),
 }
 
+impl DerivationType {
+	/// The BIP-44 coin type registered for NEO.
+	pub const COIN_TYPE: u32 = 888;
+}
+
 impl fmt::Display for DerivationType {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+		let coin_type = Self::COIN_TYPE;
 		write!(
 			f,
 			"{}",
 			match self {
-				DerivationType::Legacy(index) => format!("m/44'/60'/0'/{index}"),
-				DerivationType::LedgerLive(index) => format!("m/44'/60'/{index}'/0/0"),
+				DerivationType::Legacy(index) => format!("m/44'/{coin_type}'/0'/0/{index}"),
+				DerivationType::LedgerLive(index) => format!("m/44'/{coin_type}'/{index}'/0/0"),
 				DerivationType::Other(inner) => inner.to_owned(),
 			}
 		)
@@ -44,7 +50,7 @@ pub enum LedgerError {
 	#[error(transparent)]
 	/// Error when converting a semver requirement
 	SemVerError(#[from] semver::Error),
-	/// Error when signing EIP712 struct with not compatible Ledger ETH app
+	/// Error when signing with a not compatible Ledger Neo app
 	#[error("Ledger neo app requires at least version: {0:?}")]
 	UnsupportedAppVersion(String),
 	/// Got a response, but it didn't contain as much data as expected
@@ -53,42 +59,74 @@ pub enum LedgerError {
 	/// Payload is empty
 	#[error("Payload must not be empty")]
 	EmptyPayload,
+	/// The derivation path is not a BIP-44 path of five elements
+	#[error("Invalid derivation path: {0}")]
+	InvalidDerivationPath(String),
+	/// The device returned a public key that is not a valid point
+	#[error("Device returned an invalid public key")]
+	InvalidPublicKey,
+	/// The device returned a signature that is not a valid DER encoded ECDSA signature
+	#[error("Device returned an invalid signature: {0}")]
+	InvalidSignature(String),
+	/// The user rejected the request on the device
+	#[error("Request rejected on the device")]
+	Rejected,
+	/// The device answered with an error status word
+	#[error("Device returned status {0:#06x}")]
+	Status(u16),
+	/// The transport failed to exchange a command with the device
+	#[error("Transport error: {0}")]
+	Transport(String),
 }
 
-pub const P1_FIRST: u8 = 0x00;
+/// The class byte of all commands of the Neo N3 app.
+pub const CLA: u8 = 0x80;
+
+/// The status word of a successful command.
+pub const SW_OK: u16 = 0x9000;
+/// The status word of a request the user rejected on the device.
+pub const SW_DENY: u16 = 0x6985;
 
+/// The maximum size of the data of a single APDU.
+pub const MAX_CHUNK_SIZE: usize = 255;
+
+/// The instructions of the Neo N3 app, which differ from the codes of the Ledger app boilerplate.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum INS {
-	GET_PUBLIC_KEY = 0x02,
-	SIGN = 0x04,
-	GET_APP_CONFIGURATION = 0x06,
-	SIGN_PERSONAL_MESSAGE = 0x08,
+	GET_APP_NAME = 0x00,
+	GET_VERSION = 0x01,
+	SIGN_TX = 0x02,
+	GET_PUBLIC_KEY = 0x04,
 }
 
 impl std::fmt::Display for INS {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
+			INS::GET_APP_NAME => write!(f, "GET_APP_NAME"),
+			INS::GET_VERSION => write!(f, "GET_VERSION"),
+			INS::SIGN_TX => write!(f, "SIGN_TX"),
 			INS::GET_PUBLIC_KEY => write!(f, "GET_PUBLIC_KEY"),
-			INS::SIGN => write!(f, "SIGN"),
-			INS::GET_APP_CONFIGURATION => write!(f, "GET_APP_CONFIGURATION"),
-			INS::SIGN_PERSONAL_MESSAGE => write!(f, "SIGN_PERSONAL_MESSAGE"),
 		}
 	}
 }
 
+/// The first parameter of `SIGN_TX` is the index of the chunk: the derivation path comes first,
+/// followed by the network magic and the chunks of the unsigned transaction.
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum P1 {
 	NON_CONFIRM = 0x00,
-	MORE = 0x80,
+	NETWORK_MAGIC = 0x01,
+	FIRST_TX_CHUNK = 0x02,
 }
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
 pub enum P2 {
-	NO_CHAINCODE = 0x00,
+	LAST = 0x00,
+	MORE = 0x80,
 }
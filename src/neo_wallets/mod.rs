@@ -5,31 +5,42 @@
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 pub use yubihsm;
 
+#[cfg(all(feature = "aws", not(target_arch = "wasm32")))]
+pub use aws::{AwsSigner, AwsSignerError};
 pub use error::*;
+pub use external_signer::ExternalSigner;
 #[cfg(all(feature = "ledger", not(target_arch = "wasm32")))]
 pub use ledger::{
-	app::LedgerNeo as Ledger,
+	app::{LedgerNeo, LedgerNeo as Ledger},
+	transport::{LedgerTransport, ScriptedLedgerTransport},
 	types::{DerivationType as HDPath, LedgerError},
 };
 use neo::prelude::Account;
+#[cfg(all(feature = "pkcs11", not(target_arch = "wasm32")))]
+pub use pkcs11::{Pkcs11Signer, Pkcs11SignerError};
+pub use signed_message::SignedMessage;
 pub use wallet::*;
 pub use wallet_signer::WalletSigner;
 pub use wallet_trait::WalletTrait;
+#[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
+pub use yubi::YubiHsmSigner;
 
+mod signed_message;
 mod wallet;
 mod wallet_trait;
 
 /// A wallet instantiated with a locally stored private key
 pub type LocalSigner = WalletSigner<Account>;
 
-#[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
-/// A wallet instantiated with a YubiHSM
-pub type YubiWallet = WalletSigner<yubihsm::ecdsa::Signer<NistP256>>;
-
+#[cfg(all(feature = "aws", not(target_arch = "wasm32")))]
+mod aws;
 #[cfg(all(feature = "ledger", not(target_arch = "wasm32")))]
 mod ledger;
+#[cfg(all(feature = "pkcs11", not(target_arch = "wasm32")))]
+mod pkcs11;
 #[cfg(all(feature = "yubihsm", not(target_arch = "wasm32")))]
 mod yubi;
 
 mod error;
+mod external_signer;
 mod wallet_signer;
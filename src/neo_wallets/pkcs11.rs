@@ -0,0 +1,279 @@
+//! Helpers for signing Neo transactions with keys held by a PKCS#11 token
+use std::{
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use cryptoki::{
+	context::{CInitializeArgs, Pkcs11},
+	mechanism::Mechanism,
+	object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+	session::{Session, UserType},
+	types::AuthPin,
+};
+use signature::hazmat::PrehashSigner;
+use thiserror::Error;
+
+use neo::prelude::{
+	Account, AccountTrait, CryptoError, ExternalSigner, ProviderError, Secp256r1PublicKey,
+	Secp256r1Signature, SignerError,
+};
+
+/// An EC P-256 key pair held by a PKCS#11 token, such as a network HSM or SoftHSMv2.
+///
+/// The key pair is looked up by its `CKA_LABEL`. The public key is read from `CKA_EC_POINT` of the
+/// public key object when the signer is opened, and every signature is a `CKM_ECDSA` signature
+/// of a SHA-256 digest computed locally.
+pub struct Pkcs11Signer {
+	// The session is not `Sync`, and a token handles one operation per session at a time. It is
+	// shared with the blocking tasks that sign.
+	session: Arc<Mutex<Session>>,
+	private_key: ObjectHandle,
+	public_key: Secp256r1PublicKey,
+	// Keeps the module loaded for the lifetime of the session
+	_pkcs11: Pkcs11,
+}
+
+/// Error when using a PKCS#11 token
+#[derive(Error, Debug)]
+pub enum Pkcs11SignerError {
+	#[error(transparent)]
+	Pkcs11(#[from] cryptoki::error::Error),
+	/// There is no token in the slot
+	#[error("No token in slot {0}")]
+	SlotNotFound(u64),
+	/// There is no key object with the label
+	#[error("No {0} with label {1:?}")]
+	KeyNotFound(&'static str, String),
+	/// The key is not a P-256 key
+	#[error("Unsupported key: {0}")]
+	UnsupportedKey(String),
+	/// A thread panicked while it used the session
+	#[error("The PKCS#11 session is poisoned")]
+	PoisonedSession,
+	/// The blocking task that signs panicked or was cancelled
+	#[error(transparent)]
+	Join(#[from] tokio::task::JoinError),
+	#[error(transparent)]
+	CryptoError(#[from] CryptoError),
+	#[error(transparent)]
+	ProviderError(#[from] ProviderError),
+}
+
+impl Pkcs11Signer {
+	/// The DER encoded OID of the P-256 curve, as found in `CKA_EC_PARAMS`.
+	const P256_EC_PARAMS: [u8; 10] = [0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+
+	/// Loads the PKCS#11 `module`, logs in to the token in the slot `slot_id` with the user `pin`
+	/// and uses the key pair labelled `key_label`.
+	pub fn open(
+		module: impl AsRef<Path>,
+		slot_id: u64,
+		pin: &str,
+		key_label: &str,
+	) -> Result<Self, Pkcs11SignerError> {
+		let pkcs11 = Pkcs11::new(module)?;
+		pkcs11.initialize(CInitializeArgs::OsThreads)?;
+		let slot = pkcs11
+			.get_slots_with_token()?
+			.into_iter()
+			.find(|slot| slot.id() == slot_id)
+			.ok_or(Pkcs11SignerError::SlotNotFound(slot_id))?;
+
+		let session = pkcs11.open_ro_session(slot)?;
+		session.login(UserType::User, Some(&AuthPin::new(pin.to_string())))?;
+		Self::with_session(pkcs11, session, key_label)
+	}
+
+	/// Uses the key pair labelled `key_label` of a session the user is logged in to.
+	pub fn with_session(
+		pkcs11: Pkcs11,
+		session: Session,
+		key_label: &str,
+	) -> Result<Self, Pkcs11SignerError> {
+		let private_key =
+			Self::find_key(&session, ObjectClass::PRIVATE_KEY, key_label, "private key")?;
+		let public_key =
+			Self::find_key(&session, ObjectClass::PUBLIC_KEY, key_label, "public key")?;
+
+		let attributes = session
+			.get_attributes(public_key, &[AttributeType::EcParams, AttributeType::EcPoint])?;
+		let mut ec_params = None;
+		let mut ec_point = None;
+		for attribute in attributes {
+			match attribute {
+				Attribute::EcParams(params) => ec_params = Some(params),
+				Attribute::EcPoint(point) => ec_point = Some(point),
+				_ => {},
+			}
+		}
+		if ec_params.as_deref() != Some(&Self::P256_EC_PARAMS[..]) {
+			return Err(Pkcs11SignerError::UnsupportedKey(format!(
+				"Key {:?} is not on the P-256 curve",
+				key_label
+			)))
+		}
+		let ec_point = ec_point.ok_or_else(|| {
+			Pkcs11SignerError::UnsupportedKey(format!("Key {:?} has no EC point", key_label))
+		})?;
+		let public_key = Self::public_key_from_ec_point(&ec_point)?;
+
+		Ok(Self {
+			session: Arc::new(Mutex::new(session)),
+			private_key,
+			public_key,
+			_pkcs11: pkcs11,
+		})
+	}
+
+	/// Returns the account of the key, without a key pair since the private key stays on the
+	/// token. Use it as the signer of transactions, which are then signed by passing this signer
+	/// to [`TransactionBuilder::sign_with`](neo::prelude::TransactionBuilder::sign_with).
+	///
+	/// The account cannot sign by itself: [`TransactionBuilder::sign`] and the signing methods of
+	/// [`Account`] fail for it because it holds no key pair.
+	///
+	/// [`TransactionBuilder::sign`]: neo::prelude::TransactionBuilder::sign
+	pub fn account(&self) -> Result<Account, Pkcs11SignerError> {
+		Ok(Account::from_public_key(&self.public_key)?)
+	}
+
+	// Blocks until the token has signed
+	fn sign_digest(
+		session: &Mutex<Session>,
+		private_key: ObjectHandle,
+		digest: &[u8],
+	) -> Result<Secp256r1Signature, Pkcs11SignerError> {
+		let session = session.lock().map_err(|_| Pkcs11SignerError::PoisonedSession)?;
+		// `CKM_ECDSA` returns the 64 bytes of r and s, and tokens may return a high s which the
+		// Neo VM rejects
+		let signature = session.sign(&Mechanism::Ecdsa, private_key, digest)?;
+		Ok(Secp256r1Signature::from_bytes(&signature)?.normalize_s())
+	}
+
+	fn find_key(
+		session: &Session,
+		class: ObjectClass,
+		label: &str,
+		kind: &'static str,
+	) -> Result<ObjectHandle, Pkcs11SignerError> {
+		session
+			.find_objects(&[
+				Attribute::Class(class),
+				Attribute::KeyType(KeyType::EC),
+				Attribute::Label(label.as_bytes().to_vec()),
+			])?
+			.into_iter()
+			.next()
+			.ok_or_else(|| Pkcs11SignerError::KeyNotFound(kind, label.to_string()))
+	}
+
+	// `CKA_EC_POINT` is a DER OCTET STRING holding the SEC1 point, though some tokens return the
+	// bare point
+	fn public_key_from_ec_point(ec_point: &[u8]) -> Result<Secp256r1PublicKey, Pkcs11SignerError> {
+		let point = match ec_point {
+			[0x04, 0x41, point @ ..] if point.len() == 0x41 => point,
+			[0x04, 0x21, point @ ..] if point.len() == 0x21 => point,
+			point => point,
+		};
+		Ok(Secp256r1PublicKey::from_bytes(point)?)
+	}
+}
+
+#[async_trait]
+impl ExternalSigner for Pkcs11Signer {
+	fn public_key(&self) -> &Secp256r1PublicKey {
+		&self.public_key
+	}
+
+	async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+		let session = self.session.clone();
+		let private_key = self.private_key;
+		let digest = digest.to_vec();
+		let signature =
+			tokio::task::spawn_blocking(move || Self::sign_digest(&session, private_key, &digest))
+				.await
+				.map_err(Pkcs11SignerError::from)??;
+		Ok(signature)
+	}
+}
+
+impl PrehashSigner<Secp256r1Signature> for Pkcs11Signer {
+	fn sign_prehash(&self, prehash: &[u8]) -> Result<Secp256r1Signature, signature::Error> {
+		Self::sign_digest(&self.session, self.private_key, prehash)
+			.map_err(|_| signature::Error::new())
+	}
+}
+
+// do not log the session
+impl std::fmt::Debug for Pkcs11Signer {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Pkcs11Signer").field("address", &self.address()).finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use neo::prelude::{HashableForVec, KeyPair};
+
+	use super::*;
+	use crate::neo_wallets::external_signer::tests::assert_signs_transactions;
+
+	#[test]
+	fn test_public_key_from_ec_point() {
+		let key_pair = KeyPair::new_random();
+		let point = key_pair.public_key().get_encoded(false);
+		let mut der = vec![0x04, 0x41];
+		der.extend(&point);
+
+		assert_eq!(Pkcs11Signer::public_key_from_ec_point(&der).unwrap(), key_pair.public_key());
+		assert_eq!(Pkcs11Signer::public_key_from_ec_point(&point).unwrap(), key_pair.public_key());
+		assert!(Pkcs11Signer::public_key_from_ec_point(&der[..40]).is_err());
+	}
+
+	// Requires SoftHSMv2 with an initialized token, e.g.
+	// `softhsm2-util --init-token --free --label neo --pin 1234 --so-pin 1234`, the slot id of
+	// the token in `PKCS11_SLOT`, and the module in `PKCS11_MODULE` if it is not at the default
+	// path of Debian.
+	#[tokio::test]
+	#[ignore]
+	async fn test_sign_transaction_with_softhsm() {
+		let module = std::env::var("PKCS11_MODULE")
+			.unwrap_or_else(|_| "/usr/lib/softhsm/libsofthsm2.so".to_string());
+		let slot_id = std::env::var("PKCS11_SLOT").unwrap().parse::<u64>().unwrap();
+
+		let pkcs11 = Pkcs11::new(module).unwrap();
+		pkcs11.initialize(CInitializeArgs::OsThreads).unwrap();
+		let slot = pkcs11
+			.get_slots_with_token()
+			.unwrap()
+			.into_iter()
+			.find(|slot| slot.id() == slot_id)
+			.unwrap();
+		let session = pkcs11.open_rw_session(slot).unwrap();
+		session.login(UserType::User, Some(&AuthPin::new("1234".to_string()))).unwrap();
+
+		let label =
+			format!("neo-{}", KeyPair::new_random().public_key().get_encoded_compressed_hex());
+		let common = vec![Attribute::Token(false), Attribute::Label(label.as_bytes().to_vec())];
+		let mut public_template = common.clone();
+		public_template.extend([
+			Attribute::EcParams(Pkcs11Signer::P256_EC_PARAMS.to_vec()),
+			Attribute::Verify(true),
+		]);
+		let mut private_template = common;
+		private_template.extend([Attribute::Sign(true), Attribute::Sensitive(true)]);
+		session
+			.generate_key_pair(&Mechanism::EccKeyPairGen, &public_template, &private_template)
+			.unwrap();
+
+		let signer = Pkcs11Signer::with_session(pkcs11, session, &label).unwrap();
+		assert_eq!(signer.account().unwrap().get_script_hash(), signer.script_hash());
+		assert_signs_transactions(&signer).await;
+
+		let prehash = b"message".hash256();
+		let signature = PrehashSigner::sign_prehash(&signer, &prehash).unwrap();
+		signer.public_key().verify(b"message", &signature).unwrap();
+	}
+}
@@ -0,0 +1,168 @@
+//! Messages signed with the `signMessage` convention of NeoLine, O3 and other N3 dApi wallets
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use neo::prelude::{
+	public_key_to_address, Address, Bytes, CryptoError, Encoder, KeyPair, Secp256r1PublicKey,
+	Secp256r1Signature,
+};
+
+/// A message signed by a wallet, in the format returned by the `signMessage` and
+/// `signMessageWithoutSalt` methods of the N3 dApi.
+///
+/// The wallet does not sign the message itself but a payload wrapping it, so that a signed message
+/// can never be a valid transaction: the bytes `010001f0`, the var-int length of the salted
+/// message, the UTF-8 bytes of the salt followed by the message, and `0000`. The signature is an
+/// ECDSA signature of the SHA-256 hash of that payload.
+///
+/// [`SignedMessage::verify`] only checks that `public_key` signed the message. To authenticate a
+/// login, also check that [`SignedMessage::address`] is the address of the account logging in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedMessage {
+	/// The compressed public key of the signer, hex encoded
+	pub public_key: String,
+	/// The 64 bytes of `r` and `s` of the signature, hex encoded
+	pub data: String,
+	/// The 16 random bytes prepended to the message, hex encoded, or `None` for a message signed
+	/// without salt
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub salt: Option<String>,
+	/// The message as given to the wallet
+	pub message: String,
+}
+
+impl SignedMessage {
+	const PREFIX: [u8; 4] = [0x01, 0x00, 0x01, 0xf0];
+	const SUFFIX: [u8; 2] = [0x00, 0x00];
+	const SALT_SIZE: usize = 16;
+
+	/// Signs `message` with a random salt, like `signMessage`.
+	pub fn sign(message: &str, key_pair: &KeyPair) -> Result<Self, CryptoError> {
+		let mut salt = [0u8; Self::SALT_SIZE];
+		rand::thread_rng().fill_bytes(&mut salt);
+		Self::sign_with_salt(message, &hex::encode(salt), key_pair)
+	}
+
+	/// Signs `message` with the given hex encoded `salt`.
+	pub fn sign_with_salt(
+		message: &str,
+		salt: &str,
+		key_pair: &KeyPair,
+	) -> Result<Self, CryptoError> {
+		Self::create(message, Some(salt.to_string()), key_pair)
+	}
+
+	/// Signs `message` without a salt, like `signMessageWithoutSalt`.
+	pub fn sign_without_salt(message: &str, key_pair: &KeyPair) -> Result<Self, CryptoError> {
+		Self::create(message, None, key_pair)
+	}
+
+	/// Checks that `data` is a signature of the message and salt by `public_key`.
+	pub fn verify(&self) -> Result<(), CryptoError> {
+		let public_key = self.get_public_key()?;
+		let signature = Secp256r1Signature::from_bytes(&hex::decode(&self.data)?)?;
+		public_key.verify(&self.payload(), &signature)
+	}
+
+	/// Returns the public key of the signer.
+	pub fn get_public_key(&self) -> Result<Secp256r1PublicKey, CryptoError> {
+		Secp256r1PublicKey::from_encoded(&self.public_key).ok_or(CryptoError::InvalidPublicKey)
+	}
+
+	/// Returns the address of the single-sig account of the signer.
+	pub fn address(&self) -> Result<Address, CryptoError> {
+		Ok(public_key_to_address(&self.get_public_key()?))
+	}
+
+	/// Returns the payload signed by the wallet.
+	pub fn payload(&self) -> Bytes {
+		Self::wrap(&self.message, self.salt.as_deref())
+	}
+
+	fn create(
+		message: &str,
+		salt: Option<String>,
+		key_pair: &KeyPair,
+	) -> Result<Self, CryptoError> {
+		let payload = Self::wrap(message, salt.as_deref());
+		let signature = key_pair.expose_secret().sign_message(&payload)?;
+		Ok(Self {
+			public_key: key_pair.public_key().get_encoded_compressed_hex(),
+			data: hex::encode(signature.to_bytes()),
+			salt,
+			message: message.to_string(),
+		})
+	}
+
+	fn wrap(message: &str, salt: Option<&str>) -> Bytes {
+		let salted = format!("{}{}", salt.unwrap_or_default(), message);
+		let mut encoder = Encoder::new();
+		encoder.write_bytes(&Self::PREFIX);
+		encoder.write_var_bytes(salted.as_bytes());
+		encoder.write_bytes(&Self::SUFFIX);
+		encoder.to_bytes()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_payload() {
+		let signed = SignedMessage {
+			public_key: String::new(),
+			data: String::new(),
+			salt: Some("058b9e03e7154e4db1e489c99256b7fa".to_string()),
+			message: "Hello".to_string(),
+		};
+		assert_eq!(
+			hex::encode(signed.payload()),
+			format!(
+				"010001f025{}{}0000",
+				hex::encode("058b9e03e7154e4db1e489c99256b7fa"),
+				"48656c6c6f"
+			)
+		);
+
+		let unsalted = SignedMessage { salt: None, ..signed };
+		assert_eq!(hex::encode(unsalted.payload()), "010001f00548656c6c6f0000");
+	}
+
+	#[test]
+	fn test_sign_and_verify() {
+		let key_pair = KeyPair::new_random();
+
+		let signed = SignedMessage::sign("Login to example.com", &key_pair).unwrap();
+		assert_eq!(signed.salt.as_ref().unwrap().len(), 32);
+		assert_eq!(signed.public_key, key_pair.public_key().get_encoded_compressed_hex());
+		assert_eq!(signed.address().unwrap(), public_key_to_address(&key_pair.public_key()));
+		signed.verify().unwrap();
+
+		let unsalted = SignedMessage::sign_without_salt("Login to example.com", &key_pair).unwrap();
+		assert_eq!(unsalted.salt, None);
+		unsalted.verify().unwrap();
+
+		let tampered = SignedMessage { message: "Login to example.org".to_string(), ..signed };
+		assert!(matches!(tampered.verify(), Err(CryptoError::SignatureVerificationError)));
+		let other_key = SignedMessage {
+			public_key: KeyPair::new_random().public_key().get_encoded_compressed_hex(),
+			..unsalted
+		};
+		assert!(other_key.verify().is_err());
+	}
+
+	#[test]
+	fn test_serde() {
+		let json = r#"{"publicKey":"03b4af8d061b6b320cce6c63bc4ec7894dce107bfc5f5ef5c68a93b4ad1e136816","data":"00","salt":"058b9e03e7154e4db1e489c99256b7fa","message":"Hello"}"#;
+		let signed: SignedMessage = serde_json::from_str(json).unwrap();
+		assert_eq!(signed.salt.as_deref(), Some("058b9e03e7154e4db1e489c99256b7fa"));
+		assert_eq!(serde_json::to_string(&signed).unwrap(), json);
+
+		let unsalted = SignedMessage { salt: None, ..signed };
+		assert!(!serde_json::to_string(&unsalted).unwrap().contains("salt"));
+		let json = r#"{"publicKey":"03b4","data":"00","message":"Hello"}"#;
+		assert_eq!(serde_json::from_str::<SignedMessage>(json).unwrap().salt, None);
+	}
+}
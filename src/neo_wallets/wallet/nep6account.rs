@@ -241,11 +241,14 @@ mod tests {
 
 		account.decrypt_private_key(TestConstants::DEFAULT_ACCOUNT_PASSWORD).unwrap();
 
-		assert_eq!(account.key_pair.clone().unwrap().private_key.to_vec(), private_key.to_vec());
+		assert_eq!(
+			account.key_pair.clone().unwrap().expose_secret().to_vec(),
+			private_key.to_vec()
+		);
 
 		// Decrypt again
 		account.decrypt_private_key(TestConstants::DEFAULT_ACCOUNT_PASSWORD).unwrap();
-		assert_eq!(account.key_pair.clone().unwrap().private_key, private_key);
+		assert_eq!(account.key_pair.clone().unwrap().expose_secret(), &private_key);
 	}
 
 	#[test]
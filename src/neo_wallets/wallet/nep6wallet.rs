@@ -125,4 +125,14 @@ mod tests {
 		assert_eq!(parameter2.param_name, "signature".to_string());
 		assert_eq!(parameter2.param_type, ContractParameterType::Signature);
 	}
+
+	#[test]
+	fn test_scrypt_n() {
+		let data = include_str!("../../../test_resources/wallet/wallet_noDefaultAccount.json");
+		let wallet: NEP6Wallet = serde_json::from_str(data).unwrap();
+		assert_eq!(wallet.scrypt, ScryptParamsDef::default());
+
+		let json = serde_json::to_value(&wallet.scrypt).unwrap();
+		assert_eq!(json["n"], 16384);
+	}
 }
@@ -230,10 +230,76 @@ impl Wallet {
 		self.accounts.remove(script_hash).is_some()
 	}
 
-	pub fn encrypt_accounts(&mut self, password: &str) {
+	/// Encrypts the private keys of all accounts holding a key pair with the scrypt parameters of
+	/// the wallet. The keys are derived in parallel.
+	pub fn encrypt_accounts(&mut self, password: &str) -> Result<(), WalletError> {
+		let accounts = self
+			.accounts
+			.values_mut()
+			.filter(|account| account.key_pair.is_some())
+			.collect::<Vec<_>>();
+		let key_pairs = accounts
+			.iter()
+			.filter_map(|account| account.key_pair.clone())
+			.collect::<Vec<_>>();
+		let encrypted = NEP2::encrypt_all(password, &key_pairs, &self.scrypt_params)?;
+		for (account, encrypted) in accounts.into_iter().zip(encrypted) {
+			account.encrypted_private_key = Some(encrypted);
+			account.key_pair = None;
+		}
+		Ok(())
+	}
+
+	/// Decrypts the private keys of all encrypted accounts with the scrypt parameters of the
+	/// wallet. The keys are derived in parallel, and no account is changed if one of them fails.
+	pub fn decrypt_accounts(&mut self, password: &str) -> Result<(), WalletError> {
+		let accounts = self
+			.accounts
+			.values_mut()
+			.filter(|account| account.key_pair.is_none() && account.encrypted_private_key.is_some())
+			.collect::<Vec<_>>();
+		let encrypted = accounts
+			.iter()
+			.filter_map(|account| account.encrypted_private_key.clone())
+			.collect::<Vec<_>>();
+		let key_pairs = NEP2::decrypt_all(password, &encrypted, &self.scrypt_params)?;
+		for (account, key_pair) in accounts.into_iter().zip(key_pairs) {
+			account.key_pair = Some(key_pair);
+		}
+		Ok(())
+	}
+
+	/// Locks the wallet by dropping the decrypted key pairs of all accounts, which zeroizes their
+	/// private keys. The accounts keep their NEP-2 encrypted keys, so [`Wallet::unlock`] restores
+	/// them.
+	///
+	/// Fails without changing any account if an account holds a key pair but no encrypted key, as
+	/// its key would be lost. Use [`Wallet::encrypt_accounts`] for such wallets instead.
+	pub fn lock(&mut self) -> Result<(), WalletError> {
+		if let Some(account) = self
+			.accounts
+			.values()
+			.find(|account| account.key_pair.is_some() && account.encrypted_private_key.is_none())
+		{
+			return Err(WalletError::AccountState(format!(
+				"Account {} has no encrypted private key",
+				account.address_or_scripthash.address()
+			)))
+		}
 		for account in self.accounts.values_mut() {
-			account.encrypt_private_key(password).expect("Failed to encrypt private key");
+			account.key_pair = None;
 		}
+		Ok(())
+	}
+
+	/// Unlocks the wallet by decrypting the private keys of all accounts with `password`.
+	pub fn unlock(&mut self, password: &str) -> Result<(), WalletError> {
+		self.decrypt_accounts(password)
+	}
+
+	/// Returns `true` if no account holds a decrypted key pair.
+	pub fn is_locked(&self) -> bool {
+		self.accounts.values().all(|account| account.key_pair.is_none())
 	}
 }
 
@@ -273,14 +339,25 @@ impl Wallet {
 		let message = message.as_ref();
 		let binding = message.hash256();
 		let message_hash = binding.as_slice();
-		self.default_account()
-			.clone()
-			.key_pair()
-			.clone()
-			.unwrap()
-			.private_key()
-			.sign_tx(message_hash)
-			.map_err(|_e| WalletError::NoKeyPair)
+		Ok(self.default_key_pair()?.expose_secret().sign_tx(message_hash)?)
+	}
+
+	/// Signs `message` with the default account like the `signMessage` method of N3 dApi wallets
+	/// such as NeoLine and O3, with a random salt.
+	///
+	/// The returned [`SignedMessage`] serializes to the JSON object returned by these wallets.
+	pub fn sign_message_with_salt(&self, message: &str) -> Result<SignedMessage, WalletError> {
+		Ok(SignedMessage::sign(message, self.default_key_pair()?)?)
+	}
+
+	/// Signs `message` with the default account like the `signMessageWithoutSalt` method of N3
+	/// dApi wallets.
+	pub fn sign_message_without_salt(&self, message: &str) -> Result<SignedMessage, WalletError> {
+		Ok(SignedMessage::sign_without_salt(message, self.default_key_pair()?)?)
+	}
+
+	fn default_key_pair(&self) -> Result<&KeyPair, WalletError> {
+		self.default_account().key_pair().as_ref().ok_or(WalletError::NoKeyPair)
 	}
 
 	/// Generates a witness for a transaction using the default account's key pair.
@@ -318,8 +395,7 @@ impl Wallet {
 			tx_with_chain.set_network(self.network());
 		}
 
-		Witness::create(tx.get_hash_data()?, &self.default_account().key_pair.clone().unwrap())
-			.map_err(|_e| WalletError::NoKeyPair)
+		Ok(Witness::create(tx_with_chain.get_hash_data()?, self.default_key_pair()?)?)
 	}
 
 	/// Returns the address of the wallet's default account.
@@ -375,7 +451,10 @@ impl Wallet {
 
 #[cfg(test)]
 mod tests {
-	use neo::prelude::{Account, AccountTrait, TestConstants, Wallet, WalletTrait};
+	use neo::prelude::{
+		Account, AccountTrait, HashableForVec, ScryptParamsDef, TestConstants, Wallet, WalletError,
+		WalletTrait,
+	};
 
 	#[test]
 	fn test_is_default() {
@@ -461,9 +540,84 @@ mod tests {
 		assert!(wallet.accounts()[0].key_pair().is_some());
 		assert!(wallet.accounts()[1].key_pair().is_some());
 
-		wallet.encrypt_accounts("pw");
+		wallet.encrypt_accounts("pw").unwrap();
 
 		assert!(wallet.accounts()[0].key_pair().is_none());
 		assert!(wallet.accounts()[1].key_pair().is_none());
 	}
+
+	#[test]
+	fn test_decrypt_wallet_with_scrypt_params() {
+		let mut wallet: Wallet = Wallet::new();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 8, r: 8, p: 1 });
+		let key_pair = wallet.default_account().key_pair().clone().unwrap();
+		wallet.encrypt_accounts("pw").unwrap();
+
+		assert!(wallet.decrypt_accounts("wrong").is_err());
+		assert!(wallet.accounts()[0].key_pair().is_none());
+
+		wallet.decrypt_accounts("pw").unwrap();
+		assert_eq!(
+			wallet.default_account().key_pair().clone().unwrap().private_key_bytes(),
+			key_pair.private_key_bytes()
+		);
+	}
+
+	#[test]
+	fn test_sign_message_with_salt() {
+		let mut wallet: Wallet = Wallet::new();
+		let public_key = wallet.default_account().key_pair().clone().unwrap().public_key();
+
+		let signed = wallet.sign_message_with_salt("Hello").unwrap();
+		assert_eq!(signed.get_public_key().unwrap(), public_key);
+		assert!(signed.salt.is_some());
+		signed.verify().unwrap();
+
+		let signed = wallet.sign_message_without_salt("Hello").unwrap();
+		assert!(signed.salt.is_none());
+		signed.verify().unwrap();
+
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 8, r: 8, p: 1 });
+		wallet.encrypt_accounts("pw").unwrap();
+		assert!(matches!(wallet.sign_message_with_salt("Hello"), Err(WalletError::NoKeyPair)));
+	}
+
+	#[tokio::test]
+	async fn test_sign_message_without_key_pair() {
+		let mut wallet: Wallet = Wallet::new();
+		let public_key = wallet.default_account().key_pair().clone().unwrap().public_key();
+		let signature = wallet.sign_message("Hello").await.unwrap();
+		public_key.verify("Hello".as_bytes().hash256(), &signature).unwrap();
+
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 8, r: 8, p: 1 });
+		wallet.encrypt_accounts("pw").unwrap();
+		assert!(matches!(wallet.sign_message("Hello").await, Err(WalletError::NoKeyPair)));
+	}
+
+	#[test]
+	fn test_lock_and_unlock() {
+		let mut wallet: Wallet = Wallet::new();
+		wallet.set_scrypt_params(ScryptParamsDef { log_n: 8, r: 8, p: 1 });
+		let key_pair = wallet.default_account().key_pair().clone().unwrap();
+		assert!(!wallet.is_locked());
+		assert!(matches!(wallet.lock(), Err(WalletError::AccountState(_))));
+		assert!(!wallet.is_locked());
+
+		wallet.encrypt_accounts("pw").unwrap();
+		wallet.unlock("pw").unwrap();
+		assert!(!wallet.is_locked());
+
+		wallet.lock().unwrap();
+		assert!(wallet.is_locked());
+		assert!(wallet.default_account().encrypted_private_key.is_some());
+		assert!(matches!(wallet.sign_message_with_salt("Hello"), Err(WalletError::NoKeyPair)));
+
+		assert!(wallet.unlock("wrong").is_err());
+		assert!(wallet.is_locked());
+		wallet.unlock("pw").unwrap();
+		assert_eq!(
+			wallet.default_account().key_pair().clone().unwrap().private_key_bytes(),
+			key_pair.private_key_bytes()
+		);
+	}
 }
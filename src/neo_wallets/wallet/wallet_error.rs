@@ -1,7 +1,7 @@
 use p256::ecdsa;
 use thiserror::Error;
 
-use neo::prelude::{BuilderError, CryptoError, TransactionError};
+use neo::prelude::{BuilderError, CryptoError, Nep2Error, ProviderError, TransactionError};
 
 /// Errors that may occur within wallet operations.
 ///
@@ -24,6 +24,8 @@ use neo::prelude::{BuilderError, CryptoError, TransactionError};
 /// - `CryptoError`: Wraps cryptographic errors, potentially from operations like hashing or encryption.
 /// - `TransactionError`: Encapsulates errors that may occur during transaction creation or processing.
 /// - `BuilderError`: Wraps errors that occur during the construction of complex objects, possibly due to invalid parameters.
+/// - `Nep2Error`: Wraps errors from encrypting or decrypting private keys, such as a wrong password.
+/// - `ProviderError`: Wraps errors returned by account operations.
 ///
 /// # Examples
 ///
@@ -97,4 +99,13 @@ pub enum WalletError {
 	/// components, such as invalid parameters or configurations that cannot be applied.
 	#[error(transparent)]
 	BuilderError(#[from] BuilderError),
+
+	/// Wraps errors from NEP-2 encryption or decryption of private keys, such as a wrong
+	/// password or invalid scrypt parameters.
+	#[error(transparent)]
+	Nep2Error(#[from] Nep2Error),
+
+	/// Wraps errors returned by account operations.
+	#[error(transparent)]
+	ProviderError(#[from] ProviderError),
 }
@@ -1,112 +1,192 @@
-//! Helpers for creating wallets for YubiHSM2
-use elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
-use p256::NistP256;
+//! Helpers for signing Neo transactions with keys held by a YubiHSM2
+use async_trait::async_trait;
 use yubihsm::{
-	asymmetric::Algorithm::EcP256, ecdsa::Signer as YubiSigner, object, object::Label, Capability,
-	Client, Connector, Credentials, Domain,
+	asymmetric::Algorithm::EcP256, object, object::Label, Algorithm, Capability, Client, Connector,
+	Credentials, Domain,
 };
 
-use neo::prelude::{Secp256r1PublicKey, WalletSigner};
+use neo::prelude::{ExternalSigner, KeyPair, Secp256r1PublicKey, Secp256r1Signature, SignerError};
 
-use super::Wallet;
+/// A NIST P-256 key stored on a YubiHSM2.
+///
+/// The private key never leaves the HSM: the signer only holds the id of the key and the public
+/// key read from the HSM, from which the Neo address and verification script are derived.
+pub struct YubiHsmSigner {
+	client: Client,
+	key_id: object::Id,
+	public_key: Secp256r1PublicKey,
+}
+
+impl YubiHsmSigner {
+	/// Connects to the HSM and opens a session, reconnecting on failure.
+	pub fn connect(connector: Connector, credentials: Credentials) -> Result<Client, SignerError> {
+		Ok(Client::open(connector, credentials, true)?)
+	}
+
+	/// Uses the ECDSA key stored at `key_id`.
+	pub fn new(client: Client, key_id: object::Id) -> Result<Self, SignerError> {
+		let public_key = client.get_public_key(key_id)?;
+		if public_key.algorithm != EcP256 {
+			return Err(SignerError::InvalidKey(format!(
+				"Key {} is a {:?} key, expected a P-256 key",
+				key_id, public_key.algorithm
+			)))
+		}
 
-impl WalletSigner<YubiSigner<NistP256>> {
-	/// Connects to a yubi key's ECDSA account at the provided id
-	pub fn connect(connector: Connector, credentials: Credentials, id: object::Id) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+		// The HSM returns the uncompressed point without the SEC1 tag
+		let mut encoded = vec![0x04];
+		encoded.extend(&public_key.bytes);
+		let public_key = Secp256r1PublicKey::from_bytes(&encoded)?;
+		Ok(Self { client, key_id, public_key })
 	}
 
-	/// Creates a new random ECDSA keypair on the yubi at the provided id
-	pub fn new(
-		connector: Connector,
-		credentials: Credentials,
-		id: object::Id,
+	/// Generates a new P-256 key at `key_id` and uses it.
+	pub fn generate(
+		client: Client,
+		key_id: object::Id,
 		label: Label,
 		domain: Domain,
-	) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
-		let id = client
-			.generate_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcP256)
-			.unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+	) -> Result<Self, SignerError> {
+		let key_id = client.generate_asymmetric_key(
+			key_id,
+			label,
+			domain,
+			Capability::SIGN_ECDSA,
+			EcP256,
+		)?;
+		Self::new(client, key_id)
 	}
 
-	/// Uploads the provided keypair on the yubi at the provided id
-	pub fn from_key(
-		connector: Connector,
-		credentials: Credentials,
-		id: object::Id,
+	/// Imports the private key of `key_pair` at `key_id` and uses it.
+	pub fn import(
+		client: Client,
+		key_id: object::Id,
 		label: Label,
 		domain: Domain,
-		key: impl Into<Vec<u8>>,
-	) -> Self {
-		let client = Client::open(connector, credentials, true).unwrap();
-		let id = client
-			.put_asymmetric_key(id, label, domain, Capability::SIGN_ECDSA, EcP256, key)
-			.unwrap();
-		let signer = YubiSigner::create(client, id).unwrap();
-		signer.into()
+		key_pair: &KeyPair,
+	) -> Result<Self, SignerError> {
+		// Only the client copies the key, our copy is zeroized when dropped
+		let private_key = key_pair.private_key_bytes();
+		let key_id = client.put_asymmetric_key(
+			key_id,
+			label,
+			domain,
+			Capability::SIGN_ECDSA,
+			EcP256,
+			private_key.as_slice(),
+		)?;
+		Self::new(client, key_id)
+	}
+
+	/// Returns the ids of the P-256 keys stored on the HSM.
+	pub fn list_keys(client: &Client) -> Result<Vec<object::Id>, SignerError> {
+		let entries = client.list_objects(&[
+			object::Filter::Type(object::Type::AsymmetricKey),
+			object::Filter::Algorithm(Algorithm::Asymmetric(EcP256)),
+		])?;
+		Ok(entries.into_iter().map(|entry| entry.object_id).collect())
+	}
+
+	/// Deletes the key used by this signer from the HSM.
+	pub fn delete_key(self) -> Result<(), SignerError> {
+		Ok(self.client.delete_object(self.key_id, object::Type::AsymmetricKey)?)
+	}
+
+	pub fn key_id(&self) -> object::Id {
+		self.key_id
 	}
 }
 
-impl From<YubiSigner<NistP256>> for Wallet<YubiSigner<NistP256>> {
-	fn from(signer: YubiSigner<NistP256>) -> Self {
-		// this will never fail
-		let public_key = Secp256r1PublicKey::from_encoded_point(signer.public_key()).unwrap();
+#[async_trait]
+impl ExternalSigner for YubiHsmSigner {
+	fn public_key(&self) -> &Secp256r1PublicKey {
+		&self.public_key
+	}
 
-		Self { signer, address }
+	/// The HSM may return a signature with a high `s`, which the Neo VM rejects, so the signature
+	/// is normalized.
+	async fn sign_prehash(&self, digest: &[u8]) -> Result<Secp256r1Signature, SignerError> {
+		let der = self.client.sign_ecdsa_prehash_raw(self.key_id, digest)?;
+		Ok(Secp256r1Signature::from_der(&der)?.normalize_s())
+	}
+}
+
+// do not log the client
+impl std::fmt::Debug for YubiHsmSigner {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("YubiHsmSigner")
+			.field("key_id", &self.key_id)
+			.field("address", &self.address())
+			.finish()
 	}
 }
 
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
-	use std::str::FromStr;
-
-	use neo::prelude::Address;
+	use neo::prelude::{public_key_to_address, KeyPair, VerificationScript};
 
 	use super::*;
+	use crate::neo_wallets::external_signer::tests::assert_signs_transactions;
+
+	fn client() -> Client {
+		YubiHsmSigner::connect(Connector::mockhsm(), Credentials::default()).unwrap()
+	}
 
 	#[tokio::test]
-	async fn from_key() {
-		let key = hex::decode("2d8c44dc2dd2f0bea410e342885379192381e82d855b1b112f9b55544f1e0900")
-			.unwrap();
-
-		let connector = yubihsm::Connector::mockhsm();
-		let wallet = Wallet::from_key(
-			connector,
-			Credentials::default(),
-			0,
+	async fn test_import_key() {
+		let key_pair = KeyPair::from_private_key(
+			&hex::decode("2d8c44dc2dd2f0bea410e342885379192381e82d855b1b112f9b55544f1e0900")
+				.unwrap()
+				.try_into()
+				.unwrap(),
+		)
+		.unwrap();
+
+		let signer = YubiHsmSigner::import(
+			client(),
+			1,
 			Label::from_bytes(&[]).unwrap(),
 			Domain::at(1).unwrap(),
-			key,
-		);
+			&key_pair,
+		)
+		.unwrap();
 
-		let msg = "Some data";
-		let sig = wallet.sign_message(msg).await.unwrap();
-		assert_eq!(sig.recover(msg).unwrap(), wallet.address());
+		assert_eq!(signer.public_key(), &key_pair.public_key());
+		assert_eq!(signer.address(), public_key_to_address(&key_pair.public_key()));
 		assert_eq!(
-			wallet.address(),
-			Address::from_str("2DE2C386082Cff9b28D62E60983856CE1139eC49").unwrap()
+			signer.verification_script(),
+			VerificationScript::from_public_key(&key_pair.public_key())
 		);
+		assert_signs_transactions(&signer).await;
+	}
+
+	#[test]
+	fn test_generate_list_and_delete_keys() {
+		let client = client();
+		let label = || Label::from_bytes(&[]).unwrap();
+		let domain = Domain::at(1).unwrap();
+		YubiHsmSigner::generate(client.clone(), 1, label(), domain).unwrap();
+		let signer = YubiHsmSigner::generate(client.clone(), 2, label(), domain).unwrap();
+
+		let mut keys = YubiHsmSigner::list_keys(&client).unwrap();
+		keys.sort();
+		assert_eq!(keys, vec![1, 2]);
+
+		signer.delete_key().unwrap();
+		assert_eq!(YubiHsmSigner::list_keys(&client).unwrap(), vec![1]);
 	}
 
 	#[tokio::test]
-	async fn new_key() {
-		let connector = yubihsm::Connector::mockhsm();
-		let wallet = Wallet::<YubiSigner<NistP256>>::new(
-			connector,
-			Credentials::default(),
-			0,
+	async fn test_sign_transaction() {
+		let signer = YubiHsmSigner::generate(
+			client(),
+			1,
 			Label::from_bytes(&[]).unwrap(),
 			Domain::at(1).unwrap(),
-		);
-
-		let msg = "Some data";
-		let sig = wallet.sign_message(msg).await.unwrap();
-		assert_eq!(sig.recover(msg).unwrap(), wallet.address());
+		)
+		.unwrap();
+		assert_eq!(signer.key_id(), 1);
+		assert_signs_transactions(&signer).await;
 	}
 }